@@ -173,6 +173,206 @@ pub(crate) fn implies_pack<F: LurkField, CS: ConstraintSystem<F>>(
     cs.enforce(|| "pack", diff, premise_lc, zero);
 }
 
+/// The bitwise operations supported by `implies_bitwise`
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum BitwiseOp {
+    And,
+    Or,
+    Xor,
+}
+
+impl BitwiseOp {
+    fn apply(self, a: u64, b: u64) -> u64 {
+        match self {
+            Self::And => a & b,
+            Self::Or => a | b,
+            Self::Xor => a ^ b,
+        }
+    }
+}
+
+/// Packs the 64 least significant bits of `v` into a `u64`, if their values are known.
+fn bits_to_u64(v: &[Boolean]) -> Option<u64> {
+    v[0..64]
+        .iter()
+        .rev()
+        .try_fold(0, |acc, b| b.get_value().map(|b| (acc << 1) | u64::from(b)))
+}
+
+/// Allocates the result of `op` applied to the 64 least significant bits of `a_bits`
+/// and `b_bits`. If premise is true, enforce that the result is the packing of the
+/// resulting bits.
+///
+/// Every resulting bit is a linear combination of `a_i`, `b_i` and `a_i * b_i`, so
+/// only 64 products need to be constrained.
+pub(crate) fn implies_bitwise<F: LurkField, CS: ConstraintSystem<F>>(
+    mut cs: CS,
+    premise: &Boolean,
+    op: BitwiseOp,
+    a_bits: &[Boolean],
+    b_bits: &[Boolean],
+) -> Result<AllocatedNum<F>, SynthesisError> {
+    // the coefficients of `a_i`, `b_i` and `a_i * b_i`
+    let (coeff_a, coeff_b, coeff_ab) = match op {
+        BitwiseOp::And => (F::ZERO, F::ZERO, F::ONE),
+        BitwiseOp::Or => (F::ONE, F::ONE, -F::ONE),
+        BitwiseOp::Xor => (F::ONE, F::ONE, -F::ONE.double()),
+    };
+    let mut coeff = F::ONE;
+    let mut pack = LinearCombination::<F>::zero();
+    for (i, (a, b)) in a_bits[0..64].iter().zip(&b_bits[0..64]).enumerate() {
+        let ab = Boolean::and(ns!(cs, format!("a_{i} and b_{i}")), a, b)?;
+        pack = add_to_lc::<F, CS>(a, pack, coeff * coeff_a);
+        pack = add_to_lc::<F, CS>(b, pack, coeff * coeff_b);
+        pack = add_to_lc::<F, CS>(&ab, pack, coeff * coeff_ab);
+        coeff = coeff.double();
+    }
+    let res = AllocatedNum::alloc(cs.namespace(|| "bitwise result"), || {
+        let a = bits_to_u64(a_bits).ok_or(SynthesisError::AssignmentMissing)?;
+        let b = bits_to_u64(b_bits).ok_or(SynthesisError::AssignmentMissing)?;
+        Ok(F::from_u64(op.apply(a, b)))
+    })?;
+
+    let diff = |_| pack - res.get_variable();
+    let premise_lc = |_| premise.lc(CS::one(), F::ONE);
+    let zero = |lc| lc;
+    cs.enforce(|| "pack", diff, premise_lc, zero);
+
+    Ok(res)
+}
+
+/// Given the little-endian bits `n_bits` of a shift amount `n`, allocates the
+/// multiplier `m` used to shift a u64 `a` by `n mod 64` bits and a boolean which is
+/// true iff `n < 64`. The shifted value is found in the bits of the product `a * m`,
+/// which fits in 128 bits:
+/// * to the left, `m = 2^(n mod 64)` and the result is in the bits `0..64`
+/// * to the right, `m = 2^(64 - n mod 64)` and the result is in the bits `64..128`
+pub(crate) fn shift_multiplier<F: LurkField, CS: ConstraintSystem<F>>(
+    mut cs: CS,
+    left: bool,
+    n_bits: &[Boolean],
+) -> Result<(AllocatedNum<F>, Boolean), SynthesisError> {
+    let two = F::from_u64(2);
+    // the factor that corresponds to each of the 6 least significant bits of `n`
+    let factor = |i: u64| {
+        let pow = two.pow_vartime([1 << i]);
+        if left {
+            pow
+        } else {
+            pow.invert().unwrap()
+        }
+    };
+    let init = if left { F::ONE } else { two.pow_vartime([64]) };
+    let mut acc = pick_const(
+        ns!(cs, "multiplier bit 0"),
+        &n_bits[0],
+        init * factor(0),
+        init,
+    )?;
+    for (i, bit) in n_bits.iter().enumerate().take(6).skip(1) {
+        let factor_minus_one = factor(i as u64) - F::ONE;
+        let next = AllocatedNum::alloc(ns!(cs, format!("multiplier {i}")), || {
+            let acc = acc.get_value().ok_or(SynthesisError::AssignmentMissing)?;
+            let bit = bit.get_value().ok_or(SynthesisError::AssignmentMissing)?;
+            Ok(if bit {
+                acc + acc * factor_minus_one
+            } else {
+                acc
+            })
+        })?;
+        // bit * (acc * (factor - 1)) = next - acc
+        cs.enforce(
+            || format!("multiplier bit {i}"),
+            |_| bit.lc(CS::one(), F::ONE),
+            |lc| lc + (factor_minus_one, acc.get_variable()),
+            |lc| lc + next.get_variable() - acc.get_variable(),
+        );
+        acc = next;
+    }
+
+    // `n < 64` iff none of its remaining bits is set
+    let high = AllocatedNum::alloc(cs.namespace(|| "high bits popcount"), || {
+        n_bits[6..]
+            .iter()
+            .try_fold(F::ZERO, |acc, b| {
+                b.get_value().map(|b| if b { acc + F::ONE } else { acc })
+            })
+            .ok_or(SynthesisError::AssignmentMissing)
+    })?;
+    popcount_equal(&mut cs, &n_bits[6..], high.get_variable());
+    let small = alloc_is_zero(cs.namespace(|| "n < 64"), &high)?;
+
+    Ok((acc, small))
+}
+
+/// Allocates the u64 resulting from a shift, given the little-endian bits of the
+/// product computed with the multiplier from `shift_multiplier` and whether the
+/// shift amount is smaller than 64. Larger shifts result in zero.
+pub(crate) fn shift_result<F: LurkField, CS: ConstraintSystem<F>>(
+    mut cs: CS,
+    left: bool,
+    product_bits: &[Boolean],
+    small: &Boolean,
+) -> Result<AllocatedNum<F>, SynthesisError> {
+    let bits = if left {
+        &product_bits[0..64]
+    } else {
+        &product_bits[64..128]
+    };
+    let res = AllocatedNum::alloc(cs.namespace(|| "shift result"), || {
+        let small = small.get_value().ok_or(SynthesisError::AssignmentMissing)?;
+        let shifted = bits_to_u64(bits).ok_or(SynthesisError::AssignmentMissing)?;
+        Ok(if small { F::from_u64(shifted) } else { F::ZERO })
+    })?;
+
+    let mut coeff = F::ONE;
+    let mut pack = LinearCombination::<F>::zero();
+    for b in bits {
+        pack = add_to_lc::<F, CS>(b, pack, coeff);
+        coeff = coeff.double();
+    }
+    // pack * small = res
+    cs.enforce(
+        || "shift result packing",
+        |_| pack,
+        |_| small.lc(CS::one(), F::ONE),
+        |lc| lc + res.get_variable(),
+    );
+
+    Ok(res)
+}
+
+/// Allocates `base` raised to the power of the u64 whose little-endian bits are the
+/// first 64 of `exp_bits`, squaring `base` once per bit and multiplying the squares
+/// selected by the bits that are set
+pub(crate) fn pow<F: LurkField, CS: ConstraintSystem<F>>(
+    mut cs: CS,
+    base: &AllocatedNum<F>,
+    exp_bits: &[Boolean],
+) -> Result<AllocatedNum<F>, SynthesisError> {
+    let mut acc = AllocatedNum::alloc(cs.namespace(|| "pow bit 0"), || {
+        let base = base.get_value().ok_or(SynthesisError::AssignmentMissing)?;
+        let bit = exp_bits[0]
+            .get_value()
+            .ok_or(SynthesisError::AssignmentMissing)?;
+        Ok(if bit { base } else { F::ONE })
+    })?;
+    // bit * (base - 1) = acc - 1
+    cs.enforce(
+        || "pow bit 0 selection",
+        |_| exp_bits[0].lc(CS::one(), F::ONE),
+        |lc| lc + base.get_variable() - CS::one(),
+        |lc| lc + acc.get_variable() - CS::one(),
+    );
+    let mut square = base.clone();
+    for (i, bit) in exp_bits.iter().enumerate().take(64).skip(1) {
+        square = mul(ns!(cs, format!("square {i}")), &square, &square)?;
+        let product = mul(ns!(cs, format!("product {i}")), &acc, &square)?;
+        acc = pick(ns!(cs, format!("pow bit {i}")), bit, &product, &acc)?;
+    }
+    Ok(acc)
+}
+
 /// Adds a constraint to CS, enforcing a difference relationship between the allocated numbers a, b, and difference.
 ///
 /// a - b = difference
@@ -64,6 +64,8 @@ impl<F: LurkField + DeserializeOwned> LurkProofMeta<F> {
             let (expr, expr_out) = &proof_meta.expr_io;
             let expr = z_dag.populate_store(expr, store, &mut cache)?;
             let expr_out = z_dag.populate_store(expr_out, store, &mut cache)?;
+            let (cont, cont_out) = &proof_meta.cont_io;
+            let cont_out = z_dag.populate_store(cont_out, store, &mut cache)?;
             if full {
                 let envs = match &proof_meta.env_io {
                     Some((env, env_out)) => Some((
@@ -72,9 +74,7 @@ impl<F: LurkField + DeserializeOwned> LurkProofMeta<F> {
                     )),
                     None => None,
                 };
-                let (cont, cont_out) = &proof_meta.cont_io;
                 let cont = z_dag.populate_store(cont, store, &mut cache)?;
-                let cont_out = z_dag.populate_store(cont_out, store, &mut cache)?;
                 if let Some((env, env_out)) = envs {
                     println!(
                         "Input:\n  Expr: {}\n  Env:  {}\n  Cont: {}",
@@ -107,6 +107,9 @@ impl<F: LurkField + DeserializeOwned> LurkProofMeta<F> {
                     expr_out.fmt_to_string(store, state)
                 );
             }
+            if let Some((reason, expr)) = store.fetch_error(&cont_out) {
+                println!("Error: {reason}: {}", expr.fmt_to_string(store, state));
+            }
             println!("Iterations: {}", proof_meta.iterations);
             Ok(())
         };
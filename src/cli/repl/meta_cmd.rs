@@ -87,8 +87,9 @@ where
             let bindings = repl.store.list(vec![binding]);
             let current_env_call = repl.store.list(vec![current_env]);
             let expanded = repl.store.list(vec![l, bindings, current_env_call]);
-            let expanded_io = repl.eval_expr(expanded)?;
+            let expanded_io = repl.eval_expr(repl.macroexpand(expanded)?)?;
             repl.env = expanded_io[0];
+            repl.macros.remove(&first);
             println!("{new_name}");
             Ok(())
         },
@@ -115,13 +116,163 @@ where
             let bindings = repl.store.list(vec![binding]);
             let current_env_call = repl.store.list(vec![current_env]);
             let expanded = repl.store.list(vec![l, bindings, current_env_call]);
-            let expanded_io = repl.eval_expr(expanded)?;
+            let expanded_io = repl.eval_expr(repl.macroexpand(expanded)?)?;
             repl.env = expanded_io[0];
+            repl.macros.remove(&first);
             println!("{new_name}");
             Ok(())
         },
     };
 
+    const DEFMACRO: MetaCmd<F, C> = MetaCmd {
+        name: "defmacro",
+        summary: "Defines a macro, expanded in forms before they're evaluated.",
+        format: "!(defmacro <name> <params> <body>)",
+        description: &[
+            "The macro is a function that receives the arguments of its calls unevaluated",
+            "and returns the form to be evaluated in their place. Calls are expanded in",
+            "the REPL, in loaded files and in the forms that meta commands evaluate,",
+            "including the results of other expansions. Parameters, bindings and patterns",
+            "with the name of a macro shadow it, and names bound by `def`, `defrec` and",
+            "the other meta commands that extend the env replace it.",
+        ],
+        example: &["!(defmacro my-if (c a b) `(if ,c ,a ,b))", "(my-if t 1 2)"],
+        run: |repl, args, _path| {
+            let (name, rest) = repl.store.car_cdr_simple(args)?;
+            let (params, rest) = repl.store.car_cdr_simple(&rest)?;
+            let (body, rest) = repl.store.car_cdr_simple(&rest)?;
+            if !rest.is_nil() {
+                bail!("At most three arguments are accepted")
+            }
+            if name.tag() != &Tag::Expr(ExprTag::Sym) {
+                bail!(
+                    "Macro name must be a symbol. Got {}",
+                    name.fmt_to_string(&repl.store, &repl.state.borrow())
+                )
+            }
+            let new_name = name.fmt_to_string(&repl.store, &repl.state.borrow());
+            let lambda = repl.store.intern_lurk_symbol("lambda");
+            // the parameters shadow the macros with the same names in the body
+            let fun = repl.macroexpand(repl.store.list(vec![lambda, params, body]))?;
+            let fun_io = repl.eval_expr(fun)?;
+            if fun_io[0].tag() != &Tag::Expr(ExprTag::Fun) {
+                bail!("Macro definition must evaluate to a function")
+            }
+            repl.macros.insert(name, fun_io[0]);
+            println!("{new_name}");
+            Ok(())
+        },
+    };
+
+    const DEFSTRUCT: MetaCmd<F, C> = MetaCmd {
+        name: "defstruct",
+        summary: "Defines a record type with a constructor, a predicate and field accessors.",
+        format: "!(defstruct <name> <field>*)",
+        description: &[
+            "`!(defstruct point x y)` binds `make-point`, `point?`, `point-x` and `point-y`.",
+            "Records are conses of a commitment and the list of fields. The commitment hides",
+            "the fields with a secret derived from the type name and the field names, which",
+            "is what the predicate checks, so a hand-built list isn't a record. Accessors",
+            "fail on other values.",
+        ],
+        example: &["!(defstruct point x y)", "(point-y (make-point 1 2))"],
+        run: |repl, args, _path| {
+            let (name, fields) = repl.store.car_cdr_simple(args)?;
+            if name.tag() != &Tag::Expr(ExprTag::Sym) {
+                bail!(
+                    "Struct name must be a symbol. Got {}",
+                    name.fmt_to_string(&repl.store, &repl.state.borrow())
+                )
+            }
+            let Some(fields) = repl.store.fetch_proper_list(&fields) else {
+                bail!("Struct fields must be a list of symbols")
+            };
+            if fields.iter().any(|f| f.tag() != &Tag::Expr(ExprTag::Sym)) {
+                bail!("Struct fields must be a list of symbols")
+            }
+            let name_sym = repl.store.fetch_symbol(&name).expect("symbol was checked");
+            let type_name = name_sym.name()?.to_string();
+            let package = name_sym
+                .direct_parent()
+                .expect("non-root symbols have parents");
+            // the generated names live in the same package as the struct name
+            let sibling = |name: String| repl.store.intern_symbol(&package.direct_child(&name));
+            // only built-ins that can't be shadowed are used
+            let [lambda, quote, cons, car, cdr, atom, eq, if_, hide] = [
+                "lambda", "quote", "cons", "car", "cdr", "atom", "eq", "if", "hide",
+            ]
+            .map(|s| repl.store.intern_lurk_symbol(s));
+            let r = repl.store.intern_user_symbol("record");
+            let nil = repl.store.intern_nil();
+            // the same definition gives the same secret, so records outlive the session
+            let descriptor = repl.store.cons(name, repl.store.list(fields.clone()));
+            let (secret, _) = repl
+                .store
+                .hide_and_return_z_payload(F::NON_HIDING_COMMITMENT_SECRET, descriptor);
+            let secret = repl.store.num(secret);
+
+            // (point? (lambda (record)
+            //   (if (atom record)
+            //       nil
+            //       (eq (car record) (hide <secret> (cdr record))))))
+            let predicate = sibling(format!("{type_name}?"));
+            let is_atom = repl.store.list([atom, r]);
+            let commitment = repl.store.list([car, r]);
+            let values = repl.store.list([cdr, r]);
+            let expected = repl.store.list([hide, secret, values]);
+            let is_record = repl.store.list([eq, commitment, expected]);
+            let body = repl.store.list([if_, is_atom, nil, is_record]);
+            let params = repl.store.list([r]);
+            let fun = repl.store.list([lambda, params, body]);
+            let mut bindings = vec![repl.store.list([predicate, fun])];
+
+            // (make-point (lambda (x y)
+            //   (cons (hide <secret> (cons x (cons y nil))) (cons x (cons y nil)))))
+            let constructor = sibling(format!("make-{type_name}"));
+            let values = fields
+                .iter()
+                .rev()
+                .fold(nil, |acc, field| repl.store.list([cons, *field, acc]));
+            let commitment = repl.store.list([hide, secret, values]);
+            let body = repl.store.list([cons, commitment, values]);
+            let params = repl.store.list(fields.clone());
+            let fun = repl.store.list([lambda, params, body]);
+            bindings.push(repl.store.list([constructor, fun]));
+            let mut names = vec![predicate, constructor];
+
+            // (point-y (lambda (record)
+            //   (if (point? record) (car (cdr (cdr record))) (hide 'point-y record))))
+            for (i, field) in fields.iter().enumerate() {
+                let field_sym = repl.store.fetch_symbol(field).expect("symbol was checked");
+                let accessor = sibling(format!("{type_name}-{}", field_sym.name()?));
+                let check = repl.store.list([predicate, r]);
+                let values = (0..=i).fold(r, |acc, _| repl.store.list([cdr, acc]));
+                let field_ref = repl.store.list([car, values]);
+                // hiding with a symbol as the secret is an error naming the accessor
+                let quoted_accessor = repl.store.list([quote, accessor]);
+                let failure = repl.store.list([hide, quoted_accessor, r]);
+                let body = repl.store.list([if_, check, field_ref, failure]);
+                let params = repl.store.list([r]);
+                let fun = repl.store.list([lambda, params, body]);
+                bindings.push(repl.store.list([accessor, fun]));
+                names.push(accessor);
+            }
+
+            let l = repl.store.intern_lurk_symbol("let");
+            let current_env = repl.store.intern_lurk_symbol("current-env");
+            let bindings = repl.store.list(bindings);
+            let current_env_call = repl.store.list(vec![current_env]);
+            let expanded = repl.store.list(vec![l, bindings, current_env_call]);
+            let expanded_io = repl.eval_expr(expanded)?;
+            repl.env = expanded_io[0];
+            for name in names {
+                repl.macros.remove(&name);
+            }
+            println!("{}", name.fmt_to_string(&repl.store, &repl.state.borrow()));
+            Ok(())
+        },
+    };
+
     const ASSERT: MetaCmd<F, C> = MetaCmd {
         name: "assert",
         summary: "Assert that an expression evaluates to true.",
@@ -130,7 +281,7 @@ where
         example: &["!(assert t)", "!(assert (eq 3 (+ 1 2)))"],
         run: |repl, args, _path| {
             let first = repl.peek1(args)?;
-            let first_io = repl.eval_expr(first)?;
+            let first_io = repl.eval_expr(repl.macroexpand(first)?)?;
             if first_io[0].is_nil() {
                 eprintln!(
                     "`assert` failed. {} evaluates to nil",
@@ -151,10 +302,10 @@ where
         run: |repl, args, _path| {
             let (first, second) = repl.peek2(args)?;
             let first_io = repl
-                .eval_expr(first)
+                .eval_expr(repl.macroexpand(first)?)
                 .with_context(|| "evaluating first arg")?;
             let second_io = repl
-                .eval_expr(second)
+                .eval_expr(repl.macroexpand(second)?)
                 .with_context(|| "evaluating second arg")?;
             let (first_io_expr, second_io_expr) = (&first_io[0], &second_io[0]);
             if !repl.store.ptr_eq(first_io_expr, second_io_expr) {
@@ -187,13 +338,13 @@ where
         run: |repl, args, _path| {
             let (first, second) = repl.peek2(args)?;
             let first_io = repl
-                .eval_expr(first)
+                .eval_expr(repl.macroexpand(first)?)
                 .with_context(|| "evaluating first arg")?;
             let Some((expected_emitted, None)) = repl.store.fetch_list(&first_io[0]) else {
                 bail!("Expectation must be a list")
             };
             let emitted = repl
-                .eval_expr_collecting_emitted(second)
+                .eval_expr_collecting_emitted(repl.macroexpand(second)?)
                 .with_context(|| "evaluating second arg")?;
             let (num_expected_emitted, num_emitted) = (expected_emitted.len(), emitted.len());
             if num_expected_emitted != num_emitted {
@@ -222,7 +373,7 @@ where
         example: &["!(assert-error (1 1))"],
         run: |repl, args, _path| {
             let first = repl.peek1(args)?;
-            let first_io = repl.eval_expr_allowing_error_continuation(first)?;
+            let first_io = repl.eval_expr_allowing_error_continuation(repl.macroexpand(first)?)?;
             if !matches!(first_io[2].tag(), Tag::Cont(ContTag::Error)) {
                 eprintln!(
                     "`assert-error` failed. {} doesn't result on evaluation error.",
@@ -248,7 +399,7 @@ where
         ],
         run: |repl, args, _path| {
             let first = repl.peek1(args)?;
-            let first_io = repl.eval_expr(first)?;
+            let first_io = repl.eval_expr(repl.macroexpand(first)?)?;
             repl.hide(F::NON_HIDING_COMMITMENT_SECRET, first_io[0])
         }
     };
@@ -266,10 +417,10 @@ where
         run: |repl, args, _path| {
             let (first, second) = repl.peek2(args)?;
             let first_io = repl
-                .eval_expr(first)
+                .eval_expr(repl.macroexpand(first)?)
                 .with_context(|| "evaluating first arg")?;
             let second_io = repl
-                .eval_expr(second)
+                .eval_expr(repl.macroexpand(second)?)
                 .with_context(|| "evaluating second arg")?;
             let (Tag::Expr(ExprTag::Num), IVal::Atom(secret)) = first_io[0].parts() else {
                 bail!(
@@ -335,7 +486,7 @@ where
         example: &["!(set-env '((a . 1) (b . 2)))", "a"],
         run: |repl, args, _path| {
             let first = repl.peek1(args)?;
-            let first_io = repl.eval_expr(first)?;
+            let first_io = repl.eval_expr(repl.macroexpand(first)?)?;
             let env = first_io[0];
             if *env.tag() != Tag::Expr(ExprTag::Env) {
                 return Err(anyhow!("Value must be an environment"));
@@ -362,7 +513,8 @@ where
         ],
         run: |repl, args, _path| {
             if !args.is_nil() {
-                repl.eval_expr_and_memoize(repl.peek1(args)?)?;
+                let expr = repl.macroexpand(repl.peek1(args)?)?;
+                repl.eval_expr_and_memoize(expr)?;
             }
             repl.prove_last_frames()?;
             Ok(())
@@ -655,7 +807,7 @@ where
             let (expr, path) = repl.peek2(args)?;
             let path = get_path(repl, &path)?;
             let io = repl
-                .eval_expr(expr)
+                .eval_expr(repl.macroexpand(expr)?)
                 .with_context(|| "evaluating predicate")?;
             let mut z_dag = ZDag::default();
             let z_ptr = z_dag.populate_with(&io[0], &repl.store, &mut Default::default());
@@ -681,6 +833,7 @@ where
             let LurkData::<F> { z_ptr, z_dag } = load(&path)?;
             let ptr = z_dag.populate_store(&z_ptr, &repl.store, &mut Default::default())?;
             repl.env = repl.store.push_binding(sym, ptr, repl.env);
+            repl.macros.remove(&sym);
             Ok(())
         },
     };
@@ -737,6 +890,8 @@ where
             }
 
             let lambda = repl.store.list(vec![repl.store.intern_lurk_symbol("lambda"), vars, body]);
+            // the vars shadow the macros with the same names in the body
+            let lambda = repl.macroexpand(lambda)?;
             let io = repl.eval_expr_with_env(lambda, repl.store.intern_empty_env())?;
             let fun = io[0];
             if !fun.is_fun() {
@@ -792,6 +947,7 @@ where
             // the standard format for a processed protocol as Lurk data
             let protocol = repl.store.list(vec![fun, backend, rc, lang, description]);
             repl.env = repl.store.push_binding(name, protocol, repl.env);
+            repl.macros.remove(&name);
             Ok(())
         },
     };
@@ -804,7 +960,7 @@ where
     /// * If the reduction count is not a number or can't be converted to `u64`
     fn get_fun_backend_and_rc(repl: &Repl<F, C>, ptcl: Ptr) -> Result<(Ptr, Backend, usize)> {
         let io = repl
-            .eval_expr(ptcl)
+            .eval_expr(repl.macroexpand(ptcl)?)
             .with_context(|| "evaluating protocol")?;
         let ptcl = &io[0];
 
@@ -859,9 +1015,11 @@ where
         let quoted_args = repl
             .store
             .list(vec![repl.store.intern_lurk_symbol("quote"), args]);
-        let apply_call = repl
-            .store
-            .list(vec![*repl.get_apply_fn(), fun, quoted_args]);
+        let apply_call = repl.store.list(vec![
+            repl.store.intern_lurk_symbol("apply"),
+            fun,
+            quoted_args,
+        ]);
 
         let io = repl
             .eval_expr_with_env(apply_call, repl.store.intern_empty_env())
@@ -970,7 +1128,7 @@ where
 
             let mut args_vec_evaled = Vec::with_capacity(args_vec.len());
             for a in args_vec {
-                let io = repl.eval_expr(a)?;
+                let io = repl.eval_expr(repl.macroexpand(a)?)?;
                 args_vec_evaled.push(io[0]);
             }
 
@@ -1093,10 +1251,12 @@ where
         },
     };
 
-    const CMDS: [MetaCmd<F, C>; 28] = [
+    const CMDS: [MetaCmd<F, C>; 30] = [
         MetaCmd::LOAD,
         MetaCmd::DEF,
         MetaCmd::DEFREC,
+        MetaCmd::DEFMACRO,
+        MetaCmd::DEFSTRUCT,
         MetaCmd::ASSERT,
         MetaCmd::ASSERT_EQ,
         MetaCmd::ASSERT_EMITTED,
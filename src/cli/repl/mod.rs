@@ -13,7 +13,11 @@ use rustyline::{
 use rustyline_derive::{Completer, Helper, Highlighter, Hinter};
 use serde::{de::DeserializeOwned, Serialize};
 use std::{
-    cell::OnceCell, collections::HashMap, fs::read_to_string, io::Write, sync::Arc, thread,
+    collections::{HashMap, HashSet},
+    fs::read_to_string,
+    io::Write,
+    sync::Arc,
+    thread,
     time::Duration,
 };
 use tracing::info;
@@ -95,7 +99,7 @@ pub(crate) struct Repl<F: LurkField, C: Coprocessor<F> + Serialize + Deserialize
     evaluation: Option<Evaluation>,
     pwd_path: Utf8PathBuf,
     meta: HashMap<&'static str, MetaCmd<F, C>>,
-    apply_fn: OnceCell<Ptr>,
+    macros: HashMap<Ptr, Ptr>,
 }
 
 pub(crate) fn validate_non_zero(name: &str, x: usize) -> Result<()> {
@@ -210,7 +214,7 @@ where
             evaluation: None,
             pwd_path,
             meta: MetaCmd::cmds(),
-            apply_fn: OnceCell::new(),
+            macros: HashMap::new(),
         }
     }
 
@@ -218,27 +222,6 @@ where
         (&self.lurk_step, &self.cprocs, &self.lang)
     }
 
-    fn get_apply_fn(&self) -> &Ptr {
-        self.apply_fn.get_or_init(|| {
-            let ptr = self
-                .store
-                .read_with_default_state(
-                    "(letrec ((apply (lambda (fn args)
-                         (if args
-                           (if (cdr args)
-                             (apply (fn (car args)) (cdr args))
-                             (fn (car args)))
-                           (fn)))))
-                       apply)",
-                )
-                .unwrap();
-            let io = self
-                .eval_expr_with_env(ptr, self.store.intern_empty_env())
-                .unwrap();
-            io[0]
-        })
-    }
-
     /// Returns a map containing the the key/value pairs from a property list whose
     /// keys are listed on the `properties` slice.
     fn get_properties(&self, list: &Ptr, properties: &[&str]) -> Result<HashMap<String, Ptr>> {
@@ -437,6 +420,18 @@ where
         }
     }
 
+    /// Formats the reason and the offending expression recorded in an error
+    /// continuation, if any
+    fn error_display(&self, cont: &Ptr) -> String {
+        match self.store.fetch_error(cont) {
+            Some((reason, expr)) => format!(
+                ": {reason}: {}",
+                expr.fmt_to_string(&self.store, &self.state.borrow())
+            ),
+            None => String::new(),
+        }
+    }
+
     #[inline]
     fn eval_expr(&self, expr: Ptr) -> Result<Vec<Ptr>> {
         self.eval_expr_with_env(expr, self.env)
@@ -476,7 +471,10 @@ where
             tag => {
                 let iterations_display = Self::pretty_iterations_display(iterations);
                 if matches!(tag, Tag::Cont(ContTag::Error)) {
-                    bail!("Evaluation encountered an error after {iterations_display}")
+                    bail!(
+                        "Evaluation encountered an error after {iterations_display}{}",
+                        self.error_display(&io[2])
+                    )
                 } else {
                     bail!("Limit reached after {iterations_display}")
                 }
@@ -564,7 +562,219 @@ where
         Ok(self.store.expect_f(*hash_idx))
     }
 
+    /// Calls the function of a macro with the unevaluated arguments of the call
+    /// `form` and returns the resulting form
+    ///
+    /// # Errors
+    /// Errors with the name of the macro and `form` when the call fails
+    fn expand_macro_call(&self, fun: Ptr, form: Ptr) -> Result<Ptr> {
+        let (name, args) = self.store.car_cdr_simple(&form)?;
+        let quoted_args = self
+            .store
+            .list(vec![self.store.intern_lurk_symbol("quote"), args]);
+        let call = self.store.list(vec![
+            self.store.intern_lurk_symbol("apply"),
+            fun,
+            quoted_args,
+        ]);
+        let io = self
+            .eval_expr_with_env(call, self.store.intern_empty_env())
+            .map_err(|e| {
+                let state = self.state.borrow();
+                anyhow!(
+                    "Expanding macro {} in {} failed with: {e}",
+                    name.fmt_to_string(&self.store, &state),
+                    form.fmt_to_string(&self.store, &state)
+                )
+            })?;
+        Ok(io[0])
+    }
+
+    /// Adds to `shadowed` the names of macros that are bound by `binders`, which
+    /// is a parameter list, a binding name or a `match` pattern. Quoted parts of
+    /// patterns are compared against and bind nothing
+    fn shadow(&self, shadowed: &mut HashSet<Ptr>, binders: Ptr) {
+        let quote = self.store.intern_lurk_symbol("quote");
+        let mut pending = vec![binders];
+        while let Some(ptr) = pending.pop() {
+            match ptr.tag() {
+                Tag::Expr(ExprTag::Sym) => {
+                    if self.macros.contains_key(&ptr) {
+                        shadowed.insert(ptr);
+                    }
+                }
+                Tag::Expr(ExprTag::Cons) => {
+                    if let Ok((car, cdr)) = self.store.car_cdr_simple(&ptr) {
+                        if car != quote {
+                            pending.push(car);
+                            pending.push(cdr);
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    /// Expands the macro calls in the elements of `list`, starting from the one
+    /// at position `skip`
+    fn macroexpand_elements(&self, list: Ptr, skip: usize, shadowed: &HashSet<Ptr>) -> Result<Ptr> {
+        let Some((elts, last)) = self.store.fetch_list(&list) else {
+            return Ok(list);
+        };
+        let elts = elts
+            .into_iter()
+            .enumerate()
+            .map(|(i, elt)| {
+                if i < skip {
+                    Ok(elt)
+                } else {
+                    self.macroexpand_form(elt, shadowed)
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        match last {
+            None => Ok(self.store.list(elts)),
+            Some(last) => Ok(self.store.improper_list(elts, last)),
+        }
+    }
+
+    /// Expands the macro calls in the forms that a quasiquoted `template` unquotes
+    /// or splices, finding them the same way the evaluator does. The rest of the
+    /// template is data and is left untouched
+    fn macroexpand_quasiquoted(&self, template: Ptr, shadowed: &HashSet<Ptr>) -> Result<Ptr> {
+        if template.tag() != &Tag::Expr(ExprTag::Cons) {
+            return Ok(template);
+        }
+        let is = |ptr: &Ptr, name| ptr == &self.store.intern_lurk_symbol(name);
+        let (head, tail) = self.store.car_cdr_simple(&template)?;
+        if is(&head, "unquote") {
+            return self.macroexpand_elements(template, 1, shadowed);
+        }
+        let head = if head.tag() == &Tag::Expr(ExprTag::Cons)
+            && is(&self.store.car_cdr_simple(&head)?.0, "unquote-splicing")
+        {
+            self.macroexpand_elements(head, 1, shadowed)?
+        } else {
+            self.macroexpand_quasiquoted(head, shadowed)?
+        };
+        let tail = self.macroexpand_quasiquoted(tail, shadowed)?;
+        Ok(self.store.cons(head, tail))
+    }
+
+    /// Expands the macro calls in `expr`, including the ones that show up in the
+    /// results of other expansions. Quoted data, quasiquoted data outside of the
+    /// unquoted forms, parameter lists, binding names and `match` patterns are
+    /// left untouched, and so are calls to local functions that shadow macros
+    fn macroexpand(&self, expr: Ptr) -> Result<Ptr> {
+        self.macroexpand_form(expr, &HashSet::new())
+    }
+
+    /// Like `macroexpand`, where the names in `shadowed` are locally bound and
+    /// don't refer to macros
+    fn macroexpand_form(&self, expr: Ptr, shadowed: &HashSet<Ptr>) -> Result<Ptr> {
+        if self.macros.is_empty() || expr.tag() != &Tag::Expr(ExprTag::Cons) {
+            return Ok(expr);
+        }
+        let (head, args) = self.store.car_cdr_simple(&expr)?;
+        if !shadowed.contains(&head) {
+            if let Some(fun) = self.macros.get(&head) {
+                let expanded = self.expand_macro_call(*fun, expr)?;
+                return self.macroexpand_form(expanded, shadowed);
+            }
+        }
+        let head_is = |name| head == self.store.intern_lurk_symbol(name);
+        if head_is("quote") {
+            Ok(expr)
+        } else if head_is("quasiquote") {
+            let Ok((template, end)) = self.store.car_cdr_simple(&args) else {
+                return Ok(expr);
+            };
+            let template = self.macroexpand_quasiquoted(template, shadowed)?;
+            Ok(self.store.cons(head, self.store.cons(template, end)))
+        } else if head_is("lambda") || head_is("receive") {
+            let Some((mut elts, None)) = self.store.fetch_list(&expr) else {
+                return Ok(expr);
+            };
+            let Some(formals) = elts.get(1) else {
+                return Ok(expr);
+            };
+            let mut inner = shadowed.clone();
+            self.shadow(&mut inner, *formals);
+            // the producer of `receive` comes before its formals are bound
+            let first_body = if head_is("lambda") { 2 } else { 3 };
+            for (i, elt) in elts.iter_mut().enumerate().skip(2) {
+                let shadowed = if i < first_body { shadowed } else { &inner };
+                *elt = self.macroexpand_form(*elt, shadowed)?;
+            }
+            Ok(self.store.list(elts))
+        } else if head_is("let") || head_is("letrec") || head_is("let-values") {
+            let Some((mut elts, None)) = self.store.fetch_list(&expr) else {
+                return Ok(expr);
+            };
+            let Some(bindings) = elts.get(1) else {
+                return Ok(expr);
+            };
+            let Some((bindings, None)) = self.store.fetch_list(bindings) else {
+                return Ok(expr);
+            };
+            // `letrec` binds all its names in the values too, while `let` and
+            // `let-values` bind each name after its value
+            let is_letrec = head_is("letrec");
+            let mut inner = shadowed.clone();
+            let binders = |binding: &Ptr| self.store.car_cdr_simple(binding).ok().map(|(b, _)| b);
+            if is_letrec {
+                for binders in bindings.iter().filter_map(binders) {
+                    self.shadow(&mut inner, binders);
+                }
+            }
+            let mut expanded = Vec::with_capacity(bindings.len());
+            for binding in bindings {
+                expanded.push(self.macroexpand_elements(binding, 1, &inner)?);
+                if !is_letrec {
+                    if let Some(binders) = binders(&binding) {
+                        self.shadow(&mut inner, binders);
+                    }
+                }
+            }
+            elts[1] = self.store.list(expanded);
+            for elt in elts.iter_mut().skip(2) {
+                *elt = self.macroexpand_form(*elt, &inner)?;
+            }
+            Ok(self.store.list(elts))
+        } else if head_is("cond") {
+            let Some((mut elts, None)) = self.store.fetch_list(&expr) else {
+                return Ok(expr);
+            };
+            // clauses are lists of expressions
+            for elt in elts.iter_mut().skip(1) {
+                *elt = self.macroexpand_elements(*elt, 0, shadowed)?;
+            }
+            Ok(self.store.list(elts))
+        } else if head_is("match") {
+            let Some((mut elts, None)) = self.store.fetch_list(&expr) else {
+                return Ok(expr);
+            };
+            // clauses start with a pattern, after the scrutinee
+            for (i, elt) in elts.iter_mut().enumerate().skip(1) {
+                *elt = if i == 1 {
+                    self.macroexpand_form(*elt, shadowed)?
+                } else if let Ok((pattern, _)) = self.store.car_cdr_simple(elt) {
+                    let mut inner = shadowed.clone();
+                    self.shadow(&mut inner, pattern);
+                    self.macroexpand_elements(*elt, 1, &inner)?
+                } else {
+                    *elt
+                };
+            }
+            Ok(self.store.list(elts))
+        } else {
+            self.macroexpand_elements(expr, 0, shadowed)
+        }
+    }
+
     pub(crate) fn handle_non_meta(&mut self, expr_ptr: Ptr) -> Result<()> {
+        let expr_ptr = self.macroexpand(expr_ptr)?;
         let (output, iterations) = self.eval_expr_and_memoize(expr_ptr)?;
         let iterations_display = Self::pretty_iterations_display(iterations);
         match output[2].tag() {
@@ -576,7 +786,10 @@ where
                 Ok(())
             }
             Tag::Cont(ContTag::Error) => {
-                bail!("Evaluation encountered an error after {iterations_display}")
+                bail!(
+                    "Evaluation encountered an error after {iterations_display}{}",
+                    self.error_display(&output[2])
+                )
             }
             _ => bail!("Limit reached after {iterations_display}"),
         }
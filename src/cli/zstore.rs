@@ -404,7 +404,8 @@ mod tests {
         field::LurkField,
         lem::{pointers::Ptr, store::Store, tag::Tag},
         tag::{
-            ContTag, ExprTag, Op1, Op2, CONT_TAG_INIT, EXPR_TAG_INIT, OP1_TAG_INIT, OP2_TAG_INIT,
+            ContTag, ExprTag, Op1, Op2, Op3, CONT_TAG_INIT, EXPR_TAG_INIT, OP1_TAG_INIT,
+            OP2_TAG_INIT, OP3_TAG_INIT,
         },
     };
 
@@ -413,7 +414,7 @@ mod tests {
     /// helper function that interns random data into a store
     fn rng_interner(rng: &mut StdRng, max_depth: usize, store: &Store<Bn>) -> Ptr {
         let rnd = rng.gen::<usize>();
-        let tag = match rnd % 4 {
+        let tag = match rnd % 5 {
             0 => {
                 Tag::Expr(ExprTag::try_from((rnd % ExprTag::COUNT) as u16 + EXPR_TAG_INIT).unwrap())
             }
@@ -422,6 +423,7 @@ mod tests {
             }
             2 => Tag::Op1(Op1::try_from((rnd % Op1::COUNT) as u16 + OP1_TAG_INIT).unwrap()),
             3 => Tag::Op2(Op2::try_from((rnd % Op2::COUNT) as u16 + OP2_TAG_INIT).unwrap()),
+            4 => Tag::Op3(Op3::try_from((rnd % Op3::COUNT) as u16 + OP3_TAG_INIT).unwrap()),
             _ => unreachable!(),
         };
         if max_depth == 0 {
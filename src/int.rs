@@ -0,0 +1,97 @@
+#[cfg(not(target_arch = "wasm32"))]
+use lurk_macros::serde_test;
+#[cfg(not(target_arch = "wasm32"))]
+use proptest_derive::Arbitrary;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::Display,
+    ops::{Add, Div, Mul, Rem, Sub},
+};
+
+/// Signed fixed-width integer type for Lurk.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Eq, Serialize, Deserialize)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Arbitrary))]
+#[cfg_attr(not(target_arch = "wasm32"), serde_test)]
+pub enum Int {
+    I64(i64),
+}
+
+impl Int {
+    pub fn is_zero(&self) -> bool {
+        match self {
+            Int::I64(n) => *n == 0,
+        }
+    }
+
+    /// The two's complement bit pattern of the integer, which is how it's
+    /// represented in the field
+    pub fn to_bits_u64(&self) -> u64 {
+        match self {
+            Int::I64(n) => *n as u64,
+        }
+    }
+}
+
+impl From<i64> for Int {
+    fn from(n: i64) -> Self {
+        Self::I64(n)
+    }
+}
+
+impl From<Int> for i64 {
+    fn from(i: Int) -> i64 {
+        match i {
+            Int::I64(n) => n,
+        }
+    }
+}
+
+impl Display for Int {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Int::I64(n) => write!(f, "{n}"),
+        }
+    }
+}
+
+impl Add for Int {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        match (self, other) {
+            (Int::I64(a), Int::I64(b)) => Int::I64(a.wrapping_add(b)),
+        }
+    }
+}
+
+impl Sub for Int {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        match (self, other) {
+            (Int::I64(a), Int::I64(b)) => Int::I64(a.wrapping_sub(b)),
+        }
+    }
+}
+impl Div for Int {
+    type Output = Self;
+    fn div(self, other: Self) -> Self {
+        match (self, other) {
+            (Int::I64(a), Int::I64(b)) => Int::I64(a.wrapping_div(b)),
+        }
+    }
+}
+impl Mul for Int {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        match (self, other) {
+            (Int::I64(a), Int::I64(b)) => Int::I64(a.wrapping_mul(b)),
+        }
+    }
+}
+impl Rem for Int {
+    type Output = Self;
+    fn rem(self, other: Self) -> Self {
+        match (self, other) {
+            (Int::I64(a), Int::I64(b)) => Int::I64(a.wrapping_rem(b)),
+        }
+    }
+}
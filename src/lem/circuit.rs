@@ -38,8 +38,9 @@ use crate::{
     circuit::gadgets::{
         constraints::{
             alloc_equal, alloc_is_zero, div, enforce_product_and_sum,
-            enforce_selector_with_premise, implies_equal, implies_equal_const, implies_pack,
-            implies_u64, implies_unequal_const, mul, or, pick, sub,
+            enforce_selector_with_premise, implies_bitwise, implies_equal, implies_equal_const,
+            implies_pack, implies_u64, implies_unequal_const, mul, or, pick, pow, shift_multiplier,
+            shift_result, sub, BitwiseOp,
         },
         data::allocate_constant,
         pointer::AllocatedPtr,
@@ -55,6 +56,7 @@ use crate::{
 
 use super::{
     interpreter::Frame,
+    multiframe::compute_witness_size,
     pointers::{Ptr, ZPtr},
     slot::*,
     store::Store,
@@ -62,6 +64,21 @@ use super::{
     Block, Ctrl, Func, Op, Var,
 };
 
+/// The size of the circuit that is synthesized for a frame of a `Func`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitCost {
+    pub num_constraints: usize,
+    pub num_aux: usize,
+}
+
+impl CircuitCost {
+    #[inline]
+    fn add(&mut self, num_constraints: usize, num_aux: usize) {
+        self.num_constraints += num_constraints;
+        self.num_aux += num_aux;
+    }
+}
+
 #[derive(Clone)]
 pub enum AllocatedVal<F: LurkField> {
     Pointer(AllocatedPtr<F>),
@@ -448,7 +465,14 @@ impl Block {
                 | Op::Mul(..)
                 | Op::Lt(..)
                 | Op::Trunc(..)
-                | Op::DivRem64(..) => {
+                | Op::DivRem64(..)
+                | Op::Inv(..)
+                | Op::BitAnd(..)
+                | Op::BitOr(..)
+                | Op::BitXor(..)
+                | Op::Shl(..)
+                | Op::Shr(..)
+                | Op::Pow(..) => {
                     g.alloc_tag(cs, &Num);
                 }
                 Op::Div(..) => {
@@ -991,6 +1015,32 @@ fn synthesize_block<F: LurkField, CS: ConstraintSystem<F>, C: Coprocessor<F>>(
                 let c = AllocatedPtr::from_parts(tag, quotient);
                 bound_allocations.insert_ptr(tgt.clone(), c);
             }
+            Op::Inv(tgt, a) => {
+                let a_num = bound_allocations.get_ptr(a)?.hash();
+                // `inv` is only the actual inverse on the non-dummy path, so
+                // that a single constraint `a * inv = not_dummy` suffices
+                let inv_val = a_num.get_value().and_then(|a| {
+                    not_dummy.get_value().map(|not_dummy| {
+                        if not_dummy {
+                            a.invert().unwrap_or(F::ZERO)
+                        } else {
+                            F::ZERO
+                        }
+                    })
+                });
+                let inv = AllocatedNum::alloc_infallible(cs.namespace(|| "inv"), || {
+                    inv_val.unwrap_or(F::ZERO)
+                });
+                cs.enforce(
+                    || "a * inv = not_dummy",
+                    |lc| lc + a_num.get_variable(),
+                    |lc| lc + inv.get_variable(),
+                    |_| not_dummy.lc(CS::one(), F::ONE),
+                );
+                let tag = ctx.global_allocator.alloc_tag_cloned(&mut cs, &Num);
+                let c = AllocatedPtr::from_parts(tag, inv);
+                bound_allocations.insert_ptr(tgt.clone(), c);
+            }
             Op::Lt(tgt, a, b) => {
                 // To find out whether a < b, we will use the following reasoning:
                 // 1) when a and b have the same sign, a < b iff a - b is negative
@@ -1120,6 +1170,90 @@ fn synthesize_block<F: LurkField, CS: ConstraintSystem<F>, C: Coprocessor<F>>(
                 bound_allocations.insert_ptr(tgt[0].clone(), div_ptr);
                 bound_allocations.insert_ptr(tgt[1].clone(), rem_ptr);
             }
+            Op::BitAnd(tgt, a, b) | Op::BitOr(tgt, a, b) | Op::BitXor(tgt, a, b) => {
+                let a = bound_allocations.get_ptr(a)?.hash();
+                let b = bound_allocations.get_ptr(b)?.hash();
+                let (a_preimg, a_bits) = &ctx.bit_decomp_slots[next_slot.consume_bit_decomp()];
+                let AllocatedVal::Bits(a_bits) = a_bits else {
+                    panic!("Expected bits")
+                };
+                let (b_preimg, b_bits) = &ctx.bit_decomp_slots[next_slot.consume_bit_decomp()];
+                let AllocatedVal::Bits(b_bits) = b_bits else {
+                    panic!("Expected bits")
+                };
+                implies_equal(
+                    ns!(cs, "implies equal for a_preimg"),
+                    not_dummy,
+                    a,
+                    &a_preimg[0],
+                );
+                implies_equal(
+                    ns!(cs, "implies equal for b_preimg"),
+                    not_dummy,
+                    b,
+                    &b_preimg[0],
+                );
+                let bitwise_op = match op {
+                    Op::BitAnd(..) => BitwiseOp::And,
+                    Op::BitOr(..) => BitwiseOp::Or,
+                    _ => BitwiseOp::Xor,
+                };
+                let res =
+                    implies_bitwise(ns!(cs, "bitwise"), not_dummy, bitwise_op, a_bits, b_bits)?;
+                let tag = ctx.global_allocator.alloc_tag_cloned(&mut cs, &Num);
+                let c = AllocatedPtr::from_parts(tag, res);
+                bound_allocations.insert_ptr(tgt.clone(), c);
+            }
+            Op::Shl(tgt, a, b) | Op::Shr(tgt, a, b) => {
+                let left = matches!(op, Op::Shl(..));
+                let a = bound_allocations.get_ptr(a)?.hash();
+                let b = bound_allocations.get_ptr(b)?.hash();
+                let (b_preimg, b_bits) = &ctx.bit_decomp_slots[next_slot.consume_bit_decomp()];
+                let AllocatedVal::Bits(b_bits) = b_bits else {
+                    panic!("Expected bits")
+                };
+                implies_equal(
+                    ns!(cs, "implies equal for b_preimg"),
+                    not_dummy,
+                    b,
+                    &b_preimg[0],
+                );
+                let (multiplier, small) = shift_multiplier(ns!(cs, "multiplier"), left, b_bits)?;
+                let product = mul(ns!(cs, "product"), a, &multiplier)?;
+                let (product_preimg, product_bits) =
+                    &ctx.bit_decomp_slots[next_slot.consume_bit_decomp()];
+                let AllocatedVal::Bits(product_bits) = product_bits else {
+                    panic!("Expected bits")
+                };
+                implies_equal(
+                    ns!(cs, "implies equal for product_preimg"),
+                    not_dummy,
+                    &product,
+                    &product_preimg[0],
+                );
+                let res = shift_result(ns!(cs, "shift"), left, product_bits, &small)?;
+                let tag = ctx.global_allocator.alloc_tag_cloned(&mut cs, &Num);
+                let c = AllocatedPtr::from_parts(tag, res);
+                bound_allocations.insert_ptr(tgt.clone(), c);
+            }
+            Op::Pow(tgt, a, b) => {
+                let a = bound_allocations.get_ptr(a)?.hash();
+                let b = bound_allocations.get_ptr(b)?.hash();
+                let (b_preimg, b_bits) = &ctx.bit_decomp_slots[next_slot.consume_bit_decomp()];
+                let AllocatedVal::Bits(b_bits) = b_bits else {
+                    panic!("Expected bits")
+                };
+                implies_equal(
+                    ns!(cs, "implies equal for b_preimg"),
+                    not_dummy,
+                    b,
+                    &b_preimg[0],
+                );
+                let res = pow(ns!(cs, "pow"), a, b_bits)?;
+                let tag = ctx.global_allocator.alloc_tag_cloned(&mut cs, &Num);
+                let c = AllocatedPtr::from_parts(tag, res);
+                bound_allocations.insert_ptr(tgt.clone(), c);
+            }
             Op::Emit(_) | Op::Unit(_) => (),
             Op::Recv(tgt) => {
                 let ptr = if let Ok(val) = ctx.bindings.get(tgt) {
@@ -1529,19 +1663,68 @@ impl Func {
     /// also an explicit way to document and attest how the number of constraints
     /// grow.
     pub fn num_constraints<F: LurkField>(&self, store: &Store<F>) -> usize {
+        self.circuit_cost(store).num_constraints
+    }
+
+    /// Computes the number of auxiliary variables that `synthesize` should allocate
+    pub fn num_aux<F: LurkField>(&self, store: &Store<F>) -> usize {
+        self.circuit_cost(store).num_aux
+    }
+
+    /// Statically computes the number of constraints and auxiliary variables
+    /// of the circuit for one frame, as built by `synthesize_frame_aux`. That
+    /// is, including the allocations of the constants, the input and the slots.
+    ///
+    /// The circuits of coprocessors aren't accounted for, and neither are the
+    /// queries to coroutines, which are proven by their own circuits.
+    pub fn circuit_cost<F: LurkField>(&self, store: &Store<F>) -> CircuitCost {
+        /// The number of `Return`s that `synthesize_call` collects outputs from
+        fn num_returns(block: &Block) -> usize {
+            match &block.ctrl {
+                Ctrl::Return(..) => 1,
+                Ctrl::If(_, true_block, false_block) => {
+                    num_returns(true_block) + num_returns(false_block)
+                }
+                Ctrl::MatchTag(_, cases, def) => {
+                    cases.values().map(num_returns).sum::<usize>()
+                        + def.as_ref().map_or(0, |def| num_returns(def))
+                }
+                Ctrl::MatchValue(_, _, cases, def) => {
+                    cases.values().map(num_returns).sum::<usize>()
+                        + def.as_ref().map_or(0, |def| num_returns(def))
+                }
+            }
+        }
+
+        /// Accounts for the outputs of a function, which are allocated and
+        /// constrained by `allocate_return` when it has more than one `Return`
+        fn returns(func: &Func, cost: &mut CircuitCost) -> bool {
+            let branches = num_returns(&func.body) > 1;
+            if branches {
+                cost.add(0, 2 * func.output_size);
+            }
+            branches
+        }
+
         fn recurse<F: LurkField>(
             block: &Block,
             globals: &mut HashSet<FWrap<F>>,
             store: &Store<F>,
             is_nested: bool,
             branches: bool,
-        ) -> usize {
-            let mut num_constraints = 0;
+            cost: &mut CircuitCost,
+        ) {
             for op in &block.ops {
                 match op {
-                    Op::Crout(..) => unimplemented!(),
+                    Op::Crout(..) => (),
+                    Op::Cproc(out, ..) => {
+                        // the output is counted as allocated from the collected data, as
+                        // it is when the coprocessor has no circuit of its own
+                        cost.add(0, 2 * out.len());
+                    }
                     Op::Call(_, func, _) => {
-                        num_constraints += recurse(&func.body, globals, store, is_nested, false);
+                        let branches = returns(func, cost);
+                        recurse(&func.body, globals, store, is_nested, branches, cost);
                     }
                     Op::Zero(_, tag) => {
                         // constrain tag and hash
@@ -1578,75 +1761,108 @@ impl Func {
                         globals.insert(FWrap(tag.to_field()));
                     }
                     Op::EqTag(..) | Op::EqVal(..) => {
-                        num_constraints += 3;
+                        // the resulting bit and the inverse of the difference
+                        cost.add(3, 2);
                     }
                     Op::Add(..) | Op::Sub(..) | Op::Mul(..) => {
                         globals.insert(FWrap(Num.to_field()));
-                        num_constraints += 1;
+                        cost.add(1, 1);
                     }
                     Op::Div(..) => {
                         globals.insert(FWrap(Num.to_field()));
                         globals.insert(FWrap(F::ONE));
-                        num_constraints += 5;
+                        // 2 for `alloc_is_zero`, the divisor and the quotient
+                        cost.add(5, 4);
+                    }
+                    Op::Inv(..) => {
+                        globals.insert(FWrap(Num.to_field()));
+                        cost.add(1, 1);
                     }
                     Op::Lt(..) => {
                         globals.insert(FWrap(Num.to_field()));
-                        num_constraints += 11;
+                        // the difference, 3 doubles, 1 xor, 2 ands and 1 or
+                        cost.add(11, 8);
                     }
                     Op::Trunc(..) => {
                         globals.insert(FWrap(Num.to_field()));
                         // 1 implies_equal, 1 implies_pack
-                        num_constraints += 2;
+                        cost.add(2, 1);
                     }
                     Op::DivRem64(..) => {
                         globals.insert(FWrap(Num.to_field()));
-                        // three implies_u64, one sub and one linear
-                        num_constraints += 197;
+                        // three implies_u64, one sub and one linear, with 64
+                        // bits for each implies_u64
+                        cost.add(197, 195);
+                    }
+                    Op::BitAnd(..) | Op::BitOr(..) | Op::BitXor(..) => {
+                        globals.insert(FWrap(Num.to_field()));
+                        // 2 implies_equal, 64 bit products and 1 implies_pack
+                        cost.add(67, 65);
+                    }
+                    Op::Shl(..) | Op::Shr(..) => {
+                        globals.insert(FWrap(Num.to_field()));
+                        // 2 implies_equal, 6 for the multiplier, 4 for the range
+                        // check of the shift amount, 1 product and 1 for the result
+                        cost.add(14, 11);
                     }
-                    Op::Not(..)
-                    | Op::Emit(_)
-                    | Op::Recv(_)
-                    | Op::Cproc(..)
-                    | Op::Copy(..)
-                    | Op::Unit(_) => (),
+                    Op::Pow(..) => {
+                        globals.insert(FWrap(Num.to_field()));
+                        // 1 implies_equal, 1 for the first bit and a square, a
+                        // product and a pick for each of the other 63 bits
+                        cost.add(191, 190);
+                    }
+                    Op::Recv(_) => {
+                        cost.add(0, 2);
+                    }
+                    Op::Not(..) => {
+                        globals.insert(FWrap(Num.to_field()));
+                    }
+                    Op::Emit(_) | Op::Copy(..) | Op::Unit(_) => (),
                     Op::Cons2(_, tag, _) => {
                         // tag for the image
                         globals.insert(FWrap(tag.to_field()));
                         // tag and hash for 2 preimage pointers
-                        num_constraints += 4;
+                        cost.add(4, 0);
                     }
                     Op::Cons3(_, tag, _) => {
                         // tag for the image
                         globals.insert(FWrap(tag.to_field()));
                         // tag and hash for 3 preimage pointers
-                        num_constraints += 6;
+                        cost.add(6, 0);
                     }
                     Op::PushBinding(..) => {
+                        globals.insert(FWrap(Sym.to_field()));
                         globals.insert(FWrap(Env.to_field()));
-                        num_constraints += 6;
+                        cost.add(6, 0);
                     }
                     Op::Cons4(_, tag, _) => {
                         // tag for the image
                         globals.insert(FWrap(tag.to_field()));
                         // tag and hash for 4 preimage pointers
-                        num_constraints += 8;
+                        cost.add(8, 0);
+                    }
+                    Op::And(..) | Op::Or(..) => {
+                        globals.insert(FWrap(Num.to_field()));
+                        // one constraint for the resulting bit
+                        cost.add(1, 1);
+                    }
+                    Op::Decons2(..) | Op::Decons3(..) | Op::Decons4(..) => {
+                        // one constraint for the image's hash
+                        cost.add(1, 0);
                     }
-                    Op::And(..)
-                    | Op::Or(..)
-                    | Op::Decons2(..)
-                    | Op::Decons3(..)
-                    | Op::Decons4(..)
-                    | Op::PopBinding(..) => {
+                    Op::PopBinding(..) => {
+                        globals.insert(FWrap(Sym.to_field()));
+                        globals.insert(FWrap(Env.to_field()));
                         // one constraint for the image's hash
-                        num_constraints += 1;
+                        cost.add(1, 0);
                     }
                     Op::Hide(..) => {
-                        num_constraints += 4;
+                        cost.add(4, 0);
                         globals.insert(FWrap(Num.to_field()));
                         globals.insert(FWrap(Comm.to_field()));
                     }
                     Op::Open(..) => {
-                        num_constraints += 2;
+                        cost.add(2, 0);
                         globals.insert(FWrap(Num.to_field()));
                         globals.insert(FWrap(Comm.to_field()));
                     }
@@ -1655,52 +1871,49 @@ impl Func {
             match &block.ctrl {
                 Ctrl::Return(vars) => {
                     if branches {
-                        num_constraints + 2 * vars.len()
-                    } else {
-                        num_constraints
+                        cost.add(2 * vars.len(), 0);
                     }
                 }
                 Ctrl::If(_, true_block, false_block) => {
-                    num_constraints
-                        + if is_nested { 2 } else { 0 }
-                        + recurse(true_block, globals, store, true, true)
-                        + recurse(false_block, globals, store, true, true)
+                    if is_nested {
+                        cost.add(2, 2);
+                    }
+                    recurse(true_block, globals, store, true, branches, cost);
+                    recurse(false_block, globals, store, true, branches, cost);
                 }
                 Ctrl::MatchTag(_, cases, def) => {
                     // We allocate one boolean per case and constrain it once
                     // per case. Then we add 1 constraint to enforce only one
                     // case was selected
-                    num_constraints += 2 * cases.len() + 1;
+                    cost.add(2 * cases.len() + 1, cases.len());
 
                     for block in cases.values() {
-                        num_constraints += recurse(block, globals, store, true, true);
+                        recurse(block, globals, store, true, branches, cost);
                     }
                     if let Some(def) = def {
                         // constraints for the boolean, the inequalities and the default case
-                        num_constraints += 1 + cases.len();
-                        num_constraints += recurse(def, globals, store, true, true);
+                        cost.add(1 + cases.len(), 1 + cases.len());
+                        recurse(def, globals, store, true, branches, cost);
                     }
-                    num_constraints
                 }
                 Ctrl::MatchValue(_, lit_type, cases, def) => {
                     let tag = lit_type.tag();
                     // First we enforce the tag of the pointer being matched
-                    num_constraints += 1;
+                    cost.add(1, 0);
                     globals.insert(FWrap(tag.to_field()));
                     // We allocate one boolean per case and constrain it once
                     // per case. Then we add 1 constraint to enforce only one
                     // case was selected
-                    num_constraints += 2 * cases.len() + 1;
+                    cost.add(2 * cases.len() + 1, cases.len());
 
                     for block in cases.values() {
-                        num_constraints += recurse(block, globals, store, true, true);
+                        recurse(block, globals, store, true, branches, cost);
                     }
                     if let Some(def) = def {
                         // constraints for the boolean, the inequalities and the default case
-                        num_constraints += 1 + cases.len();
-                        num_constraints += recurse(def, globals, store, true, true);
+                        cost.add(1 + cases.len(), 1 + cases.len());
+                        recurse(def, globals, store, true, branches, cost);
                     }
-                    num_constraints
                 }
             }
         }
@@ -1719,7 +1932,25 @@ impl Func {
             + store.hash8_cost() * self.slots_count.hash8
             + store.hash3_cost() * self.slots_count.commitment
             + bit_decomp_cost * self.slots_count.bit_decomp;
-        let num_constraints = recurse(&self.body, globals, store, false, false);
-        slot_constraints + num_constraints + globals.len()
+        let slot_aux = [
+            (SlotType::Hash4, self.slots_count.hash4),
+            (SlotType::Hash6, self.slots_count.hash6),
+            (SlotType::Hash8, self.slots_count.hash8),
+            (SlotType::Commitment, self.slots_count.commitment),
+            (SlotType::BitDecomp, self.slots_count.bit_decomp),
+        ]
+        .iter()
+        .map(|(slot_type, num_slots)| compute_witness_size(slot_type, store) * num_slots)
+        .sum::<usize>();
+        // the input is allocated as a pair of unconstrained numbers per pointer
+        let mut cost = CircuitCost {
+            num_constraints: slot_constraints,
+            num_aux: slot_aux + 2 * self.input_params.len(),
+        };
+        let branches = returns(self, &mut cost);
+        recurse(&self.body, globals, store, false, branches, &mut cost);
+        // each constant is allocated and constrained once
+        cost.add(globals.len(), globals.len());
+        cost
     }
 }
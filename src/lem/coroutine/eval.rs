@@ -150,6 +150,21 @@ fn run<F: LurkField>(
                 };
                 bindings.insert_ptr(tgt.clone(), c);
             }
+            Op::Inv(tgt, a) => {
+                let a = *bindings.get_ptr(a)?.val();
+                let c = if let IVal::Atom(f) = a {
+                    let f = scope.store.expect_f(f);
+                    if f == &F::ZERO {
+                        bail!("Can't invert zero")
+                    }
+                    scope
+                        .store
+                        .intern_atom(Tag::Expr(Num), f.invert().expect("not zero"))
+                } else {
+                    bail!("`Inv` only works on numbers")
+                };
+                bindings.insert_ptr(tgt.clone(), c);
+            }
             Op::Lt(tgt, a, b) => {
                 let a = *bindings.get_ptr(a)?.val();
                 let b = *bindings.get_ptr(b)?.val();
@@ -198,6 +213,56 @@ fn run<F: LurkField>(
                 bindings.insert_ptr(tgt[0].clone(), c1);
                 bindings.insert_ptr(tgt[1].clone(), c2);
             }
+            Op::BitAnd(tgt, a, b) | Op::BitOr(tgt, a, b) | Op::BitXor(tgt, a, b) => {
+                let a = *bindings.get_ptr(a)?.val();
+                let b = *bindings.get_ptr(b)?.val();
+                let c = if let (IVal::Atom(f), IVal::Atom(g)) = (a, b) {
+                    let f = scope.store.expect_f(f).to_u64_unchecked();
+                    let g = scope.store.expect_f(g).to_u64_unchecked();
+                    let c = match op {
+                        Op::BitAnd(..) => f & g,
+                        Op::BitOr(..) => f | g,
+                        _ => f ^ g,
+                    };
+                    scope.store.intern_atom(Tag::Expr(Num), F::from_u64(c))
+                } else {
+                    bail!("Bitwise operations only work on atoms")
+                };
+                bindings.insert_ptr(tgt.clone(), c);
+            }
+            Op::Shl(tgt, a, b) | Op::Shr(tgt, a, b) => {
+                let a = *bindings.get_ptr(a)?.val();
+                let b = *bindings.get_ptr(b)?.val();
+                let c = if let (IVal::Atom(f), IVal::Atom(g)) = (a, b) {
+                    let f = scope.store.expect_f(f).to_u64_unchecked();
+                    let c = match scope.store.expect_f(g).to_u64() {
+                        Some(g) if g < 64 => {
+                            if matches!(op, Op::Shl(..)) {
+                                f << g
+                            } else {
+                                f >> g
+                            }
+                        }
+                        _ => 0,
+                    };
+                    scope.store.intern_atom(Tag::Expr(Num), F::from_u64(c))
+                } else {
+                    bail!("Shift operations only work on atoms")
+                };
+                bindings.insert_ptr(tgt.clone(), c);
+            }
+            Op::Pow(tgt, a, b) => {
+                let a = *bindings.get_ptr(a)?.val();
+                let b = *bindings.get_ptr(b)?.val();
+                let c = if let (IVal::Atom(f), IVal::Atom(g)) = (a, b) {
+                    let f = *scope.store.expect_f(f);
+                    let g = scope.store.expect_f(g).to_u64_unchecked();
+                    scope.store.intern_atom(Tag::Expr(Num), f.pow_vartime([g]))
+                } else {
+                    bail!("`Pow` only works on atoms")
+                };
+                bindings.insert_ptr(tgt.clone(), c);
+            }
             Op::Emit(a) => {
                 // TODO: send `a` through a channel as in the original interpreter
                 let a = bindings.get_ptr(a)?;
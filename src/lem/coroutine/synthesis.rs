@@ -11,8 +11,8 @@ use crate::field::{FWrap, LurkField};
 
 use crate::circuit::gadgets::constraints::{
     alloc_equal, alloc_is_zero, div, enforce_product_and_sum, enforce_selector_with_premise,
-    implies_equal, implies_equal_const, implies_pack, implies_u64, implies_unequal_const, mul, or,
-    pick, sub,
+    implies_bitwise, implies_equal, implies_equal_const, implies_pack, implies_u64,
+    implies_unequal_const, mul, or, pick, pow, shift_multiplier, shift_result, sub, BitwiseOp,
 };
 use crate::circuit::gadgets::pointer::AllocatedPtr;
 use crate::coroutine::memoset::{CircuitQuery, CircuitScope, LogMemoCircuit};
@@ -369,6 +369,32 @@ fn synthesize_run<'a, F: LurkField, CS: ConstraintSystem<F>>(
                 let c = AllocatedPtr::from_parts(tag, quotient);
                 bound_allocations.insert_ptr(tgt.clone(), c);
             }
+            Op::Inv(tgt, a) => {
+                let a_num = bound_allocations.get_ptr(a)?.hash();
+                // `inv` is only the actual inverse on the non-dummy path, so
+                // that a single constraint `a * inv = not_dummy` suffices
+                let inv_val = a_num.get_value().and_then(|a| {
+                    not_dummy.get_value().map(|not_dummy| {
+                        if not_dummy {
+                            a.invert().unwrap_or(F::ZERO)
+                        } else {
+                            F::ZERO
+                        }
+                    })
+                });
+                let inv = AllocatedNum::alloc_infallible(cs.namespace(|| "inv"), || {
+                    inv_val.unwrap_or(F::ZERO)
+                });
+                cs.enforce(
+                    || "a * inv = not_dummy",
+                    |lc| lc + a_num.get_variable(),
+                    |lc| lc + inv.get_variable(),
+                    |_| not_dummy.lc(CS::one(), F::ONE),
+                );
+                let tag = g.alloc_tag_cloned(&mut cs, &Num);
+                let c = AllocatedPtr::from_parts(tag, inv);
+                bound_allocations.insert_ptr(tgt.clone(), c);
+            }
             Op::Lt(tgt, a, b) => {
                 // To find out whether a < b, we will use the following reasoning:
                 // 1) when a and b have the same sign, a < b iff a - b is negative
@@ -458,6 +484,44 @@ fn synthesize_run<'a, F: LurkField, CS: ConstraintSystem<F>>(
                 bound_allocations.insert_ptr(tgt[0].clone(), div_ptr);
                 bound_allocations.insert_ptr(tgt[1].clone(), rem_ptr);
             }
+            Op::BitAnd(tgt, a, b) | Op::BitOr(tgt, a, b) | Op::BitXor(tgt, a, b) => {
+                let a = bound_allocations.get_ptr(a)?.hash();
+                let b = bound_allocations.get_ptr(b)?.hash();
+                let a_bits = a.to_bits_le_strict(ns!(cs, "a_bits"))?;
+                let b_bits = b.to_bits_le_strict(ns!(cs, "b_bits"))?;
+                let bitwise_op = match op {
+                    Op::BitAnd(..) => BitwiseOp::And,
+                    Op::BitOr(..) => BitwiseOp::Or,
+                    _ => BitwiseOp::Xor,
+                };
+                let res =
+                    implies_bitwise(ns!(cs, "bitwise"), not_dummy, bitwise_op, &a_bits, &b_bits)?;
+                let tag = g.alloc_tag_cloned(&mut cs, &Num);
+                let c = AllocatedPtr::from_parts(tag, res);
+                bound_allocations.insert_ptr(tgt.clone(), c);
+            }
+            Op::Shl(tgt, a, b) | Op::Shr(tgt, a, b) => {
+                let left = matches!(op, Op::Shl(..));
+                let a = bound_allocations.get_ptr(a)?.hash();
+                let b = bound_allocations.get_ptr(b)?.hash();
+                let b_bits = b.to_bits_le_strict(ns!(cs, "b_bits"))?;
+                let (multiplier, small) = shift_multiplier(ns!(cs, "multiplier"), left, &b_bits)?;
+                let product = mul(ns!(cs, "product"), a, &multiplier)?;
+                let product_bits = product.to_bits_le_strict(ns!(cs, "product_bits"))?;
+                let res = shift_result(ns!(cs, "shift"), left, &product_bits, &small)?;
+                let tag = g.alloc_tag_cloned(&mut cs, &Num);
+                let c = AllocatedPtr::from_parts(tag, res);
+                bound_allocations.insert_ptr(tgt.clone(), c);
+            }
+            Op::Pow(tgt, a, b) => {
+                let a = bound_allocations.get_ptr(a)?.hash();
+                let b = bound_allocations.get_ptr(b)?.hash();
+                let b_bits = b.to_bits_le_strict(ns!(cs, "b_bits"))?;
+                let res = pow(ns!(cs, "pow"), a, &b_bits)?;
+                let tag = g.alloc_tag_cloned(&mut cs, &Num);
+                let c = AllocatedPtr::from_parts(tag, res);
+                bound_allocations.insert_ptr(tgt.clone(), c);
+            }
             Op::Emit(_) | Op::Unit(_) => (),
             Op::Recv(_) => todo!("not supported yet"),
             Op::Hide(tgt, sec, pay) => {
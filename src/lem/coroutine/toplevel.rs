@@ -6,6 +6,7 @@ use crate::coroutine::memoset::{
 use crate::field::LurkField;
 use crate::lem::circuit::BoundAllocations;
 use crate::lem::{circuit::GlobalAllocator, pointers::Ptr, store::Store, Func};
+use crate::state::user_sym;
 use crate::symbol::Symbol;
 
 use anyhow::{bail, Context, Result};
@@ -13,6 +14,7 @@ use bellpepper_core::boolean::Boolean;
 use bellpepper_core::{ConstraintSystem, SynthesisError};
 use indexmap::IndexMap;
 use std::marker::PhantomData;
+use std::path::Path;
 use std::sync::Arc;
 
 use super::eval::call;
@@ -28,22 +30,43 @@ pub struct Coroutine<F> {
 #[derive(Clone)]
 pub struct Toplevel<F>(IndexMap<Symbol, Coroutine<F>>);
 
-fn compute_rc(_func: &Func) -> usize {
-    // TODO
-    1
+/// The number of constraints that the queries proven by one coroutine circuit
+/// may take, according to the static estimate of their function
+const CONSTRAINT_BUDGET: usize = 1 << 12;
+
+/// Computes how many queries to `func` fit in `CONSTRAINT_BUDGET`, according
+/// to `Func::num_constraints`. At least one query is proven per circuit.
+///
+/// The estimate is of the circuit of `func` alone, so the constraints that the
+/// memoset adds for every query aren't taken into account.
+fn compute_rc<F: LurkField>(func: &Func, store: &Store<F>) -> usize {
+    (CONSTRAINT_BUDGET / func.num_constraints(store).max(1)).max(1)
 }
 
-impl<F> Toplevel<F> {
-    pub fn new(funcs: Vec<(Symbol, Func)>) -> Self {
+impl<F: LurkField> Toplevel<F> {
+    pub fn new(funcs: Vec<(Symbol, Func)>, store: &Store<F>) -> Self {
         let mut toplevel = IndexMap::new();
         for (name, func) in funcs.into_iter() {
-            let rc = compute_rc(&func);
+            let rc = compute_rc(&func, store);
             let _p = PhantomData;
             toplevel.insert(name, Coroutine { func, rc, _p });
         }
         Toplevel(toplevel)
     }
 
+    /// Loads the functions of the `.lem` file at `path` as coroutines, each one
+    /// named by its function name in the `.lurk.user` package, like `QUERY`
+    /// names them
+    pub fn from_file<P: AsRef<Path>>(path: P, store: &Store<F>) -> Result<Self> {
+        let funcs = Func::all_from_file(path)?
+            .into_iter()
+            .map(|func| (user_sym(&func.name), func))
+            .collect();
+        Ok(Self::new(funcs, store))
+    }
+}
+
+impl<F> Toplevel<F> {
     pub fn get(&self, name: &Symbol) -> Option<&Coroutine<F>> {
         self.0.get(name)
     }
@@ -274,8 +297,8 @@ mod test {
     use super::*;
     use crate::coroutine::memoset::prove::MemosetProver;
     use crate::coroutine::memoset::CoroutineCircuit;
+    use crate::func;
     use crate::proof::RecursiveSNARKTrait;
-    use crate::{func, state::user_sym};
 
     use bellpepper::util_cs::bench_cs::BenchCS;
     use expect_test::expect;
@@ -284,6 +307,7 @@ mod test {
     fn sample_toplevel() -> (Arc<Toplevel<F>>, [Symbol; 4]) {
         let id = func!(id(x): 1 => { return (x) });
         let factorial = func!(factorial(n): 1 => {
+            let n = cast(n, Expr::Num);
             let zero = Num(0);
             let one = Num(1);
             let n_is_zero = eq_val(n, zero);
@@ -292,10 +316,12 @@ mod test {
             }
             let m = sub(n, one);
             let p = QUERY("factorial", m);
+            let p = cast(p, Expr::Num);
             let res = mul(n, p);
             return (res)
         });
         let even = func!(even(n): 1 => {
+            let n = cast(n, Expr::Num);
             let zero = Num(0);
             let one = Num(1);
             let n_is_zero = eq_val(n, zero);
@@ -307,6 +333,7 @@ mod test {
             return (res)
         });
         let odd = func!(odd(n): 1 => {
+            let n = cast(n, Expr::Num);
             let zero = Num(0);
             let n_is_zero = eq_val(n, zero);
             if n_is_zero {
@@ -321,12 +348,15 @@ mod test {
         let factorial_sym = user_sym("factorial");
         let even_sym = user_sym("even");
         let odd_sym = user_sym("odd");
-        let toplevel = Arc::new(Toplevel::<F>::new(vec![
-            (id_sym.clone(), id),
-            (factorial_sym.clone(), factorial),
-            (even_sym.clone(), even),
-            (odd_sym.clone(), odd),
-        ]));
+        let toplevel = Arc::new(Toplevel::<F>::new(
+            vec![
+                (id_sym.clone(), id),
+                (factorial_sym.clone(), factorial),
+                (even_sym.clone(), even),
+                (odd_sym.clone(), odd),
+            ],
+            &Store::default(),
+        ));
         let symbols = [id_sym, factorial_sym, even_sym, odd_sym];
         (toplevel, symbols)
     }
@@ -348,6 +378,18 @@ mod test {
         assert_eq!(res3, scope.store.num_u64(1));
     }
 
+    #[test]
+    fn lem_coroutine_from_file_test() {
+        let (sample, _) = sample_toplevel();
+        let toplevel = Toplevel::<F>::from_file("demo/coroutines.lem", &Store::default()).unwrap();
+        assert_eq!(toplevel.0.len(), sample.0.len());
+        for (loaded, expected) in toplevel.0.iter().zip(&sample.0) {
+            assert_eq!(loaded.0, expected.0);
+            assert_eq!(loaded.1.func, expected.1.func);
+            assert_eq!(loaded.1.rc, expected.1.rc);
+        }
+    }
+
     #[test]
     fn lem_coroutine_prove_test() {
         let (toplevel, _) = sample_toplevel();
@@ -420,9 +462,52 @@ mod test {
         expect!("1772").assert_eq(&cs.num_constraints().to_string());
     }
 
+    /// The number of constraints of a blank circuit proving `rc` queries to the
+    /// coroutine at `index`
+    fn blank_num_constraints(toplevel: &Arc<Toplevel<F>>, index: usize, rc: usize) -> usize {
+        let s = Arc::new(Store::<F>::default());
+        let circuit = CoroutineCircuit::<_, _, ToplevelQuery<_>>::blank(index, rc, &s, toplevel);
+        let mut cs = BenchCS::new();
+        let dummy = [(); 6].map(|_| AllocatedPtr::alloc_infallible(&mut cs, || unreachable!()));
+        circuit.supernova_synthesize(&mut cs, &dummy).unwrap();
+        cs.num_constraints()
+    }
+
+    #[test]
+    fn lem_coroutine_cost_test() {
+        let (toplevel, [id, ..]) = sample_toplevel();
+        let s = Store::<F>::default();
+        // the constraints that one more query adds to a circuit
+        let per_query = |index| {
+            blank_num_constraints(&toplevel, index, 2) - blank_num_constraints(&toplevel, index, 1)
+        };
+        // the identity's circuit is all memoset overhead, which every query pays
+        let id_index = toplevel.0.get_index_of(&id).unwrap();
+        let overhead = per_query(id_index);
+        for (index, coroutine) in toplevel.0.values().enumerate() {
+            // coroutines hash inline and pay for the queries they make, which the
+            // estimate doesn't see, so it's only a lower bound of what they add
+            let estimate = coroutine.func.num_constraints(&s);
+            assert!(estimate <= per_query(index) - overhead);
+        }
+    }
+
+    #[test]
+    fn lem_coroutine_rc_test() {
+        let (toplevel, [id, factorial, ..]) = sample_toplevel();
+        let s = Store::<F>::default();
+        // the identity costs no constraints, so it's priced as one
+        assert_eq!(toplevel.get(&id).unwrap().func.num_constraints(&s), 0);
+        assert_eq!(toplevel.get(&id).unwrap().rc, CONSTRAINT_BUDGET);
+        let factorial = toplevel.get(&factorial).unwrap();
+        expect!("12").assert_eq(&factorial.func.num_constraints(&s).to_string());
+        expect!("341").assert_eq(&factorial.rc.to_string());
+    }
+
     #[test]
     fn prove_sum_list_coroutine() {
         let build_list = func!(build_list(n): 1 => {
+            let n = cast(n, Expr::Num);
             let zero = Num(0);
             let n_is_zero = eq_val(n, zero);
             if n_is_zero {
@@ -444,6 +529,8 @@ mod test {
                 Expr::Cons => {
                     let (n, ys) = decons2(xs);
                     let m = QUERY("sum-list", ys);
+                    let n = cast(n, Expr::Num);
+                    let m = cast(m, Expr::Num);
                     let res = add(n, m);
                     return (res)
                 }
@@ -457,12 +544,15 @@ mod test {
         let main_sym = user_sym("main");
         let build_list_sym = user_sym("build-list");
         let sum_list_sym = user_sym("sum-list");
-        let toplevel = Arc::new(Toplevel::<F>::new(vec![
-            (main_sym.clone(), main),
-            (build_list_sym.clone(), build_list),
-            (sum_list_sym.clone(), sum_list),
-        ]));
         let s = Arc::new(Store::<F>::default());
+        let toplevel = Arc::new(Toplevel::<F>::new(
+            vec![
+                (main_sym.clone(), main),
+                (build_list_sym.clone(), build_list),
+                (sum_list_sym.clone(), sum_list),
+            ],
+            &s,
+        ));
 
         let query = ToplevelQuery::<F>::new(main_sym, vec![s.num_u64(10)], &toplevel).unwrap();
         let scope =
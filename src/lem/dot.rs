@@ -0,0 +1,298 @@
+//! Renders the control flow of a LEM `Func` in the DOT language of Graphviz
+//!
+//! Every block becomes a node that lists its operations and ends with the
+//! slots it needs: the ones taken by its own operations and the ones of the
+//! whole subtree, as computed by `Block::count_slots`. Operations that take
+//! slots are annotated with their costs, which is how less-than comparisons
+//! show up with the three bit decomposition slots each of them needs.
+//!
+//! The edges leaving a `MatchTag`, `MatchValue` or `If` are labeled with their
+//! cases. Since the slots are shared among branches, the number of slots of
+//! each kind is the maximum over the alternatives, so the edges leading to the
+//! branches that reach it are highlighted along with the kinds they drive.
+//! Calls are dashed edges to a cluster with the blocks of the called function,
+//! which is rendered once no matter how many copies of it `Func::new` made.
+//!
+//! ```text
+//! dot -Tsvg step.dot > step.svg
+//! ```
+
+use std::{collections::HashMap, fmt::Write};
+
+use super::{
+    slot::SlotsCounter,
+    text::{fmt_lit, fmt_tag, fmt_vars},
+    Block, Ctrl, Func, Op,
+};
+
+fn kinds(slots: &SlotsCounter) -> [(&'static str, usize); 5] {
+    [
+        ("hash4", slots.hash4),
+        ("hash6", slots.hash6),
+        ("hash8", slots.hash8),
+        ("commitment", slots.commitment),
+        ("bit_decomp", slots.bit_decomp),
+    ]
+}
+
+fn fmt_slots(slots: &SlotsCounter) -> String {
+    let kinds = kinds(slots)
+        .into_iter()
+        .filter(|(_, count)| *count != 0)
+        .map(|(kind, count)| format!("{kind}: {count}"))
+        .collect::<Vec<_>>();
+    if kinds.is_empty() {
+        "none".into()
+    } else {
+        kinds.join(", ")
+    }
+}
+
+/// The kinds of slots for which a branch needs as many as the most demanding
+/// of the alternatives
+fn drives(branch: &SlotsCounter, max: &SlotsCounter) -> Vec<&'static str> {
+    kinds(branch)
+        .into_iter()
+        .zip(kinds(max))
+        .filter(|((_, count), (_, max))| *max != 0 && count == max)
+        .map(|((kind, _), _)| kind)
+        .collect()
+}
+
+/// Escapes the lines of a label and left-justifies them
+fn label(lines: &[String]) -> String {
+    lines.iter().fold(String::new(), |mut label, line| {
+        let line = line.replace('\\', "\\\\").replace('"', "\\\"");
+        write!(label, "{line}\\l").unwrap();
+        label
+    })
+}
+
+/// The copies of a function that `Func::new` makes for its call sites only
+/// differ in the unique suffixes of their variables, so they are compared by
+/// their text with the suffixes renumbered in order of appearance
+fn canonical(func: &Func) -> String {
+    let text = func.to_string();
+    let mut suffixes = HashMap::new();
+    let mut canonical = String::with_capacity(text.len());
+    let mut rest = text.as_str();
+    while let Some(i) = rest.find('#') {
+        canonical.push_str(&rest[..=i]);
+        rest = &rest[i + 1..];
+        let end = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let (suffix, tail) = rest.split_at(end);
+        let next = suffixes.len();
+        canonical.push_str(&suffixes.entry(suffix).or_insert(next).to_string());
+        rest = tail;
+    }
+    canonical.push_str(rest);
+    canonical
+}
+
+#[derive(Default)]
+struct Renderer {
+    /// The clusters of the functions rendered so far
+    clusters: String,
+    /// The functions rendered so far, in canonical form, and their entry nodes
+    rendered: Vec<(String, String)>,
+    edges: String,
+    nodes_count: usize,
+    clusters_count: usize,
+}
+
+impl Renderer {
+    /// Renders `func` in a cluster of its own and returns its entry node
+    fn func(&mut self, func: &Func) -> String {
+        self.clusters_count += 1;
+        let cluster = self.clusters_count;
+        let mut nodes = String::new();
+        let entry = self.block(&func.body, &mut nodes);
+        let title = [
+            format!(
+                "fn {}({}): {}",
+                func.name,
+                fmt_vars(&func.input_params),
+                func.output_size
+            ),
+            format!("slots: {}", fmt_slots(&func.slots_count)),
+        ];
+        self.clusters.push_str(&format!(
+            "    subgraph cluster_{cluster} {{\n        label=\"{}\";\n{nodes}    }}\n",
+            label(&title)
+        ));
+        entry
+    }
+
+    /// Renders `block` and the blocks it branches to, returning its node
+    fn block(&mut self, block: &Block, nodes: &mut String) -> String {
+        self.nodes_count += 1;
+        let id = format!("n{}", self.nodes_count);
+        let mut lines = vec![];
+        let mut own_slots = SlotsCounter::default();
+        let mut calls = vec![];
+        for op in &block.ops {
+            let slots = op.count_slots();
+            own_slots = own_slots.add(slots);
+            match op {
+                Op::Call(_, func, _) => {
+                    lines.push(op.to_string());
+                    calls.push(func);
+                }
+                _ if slots != SlotsCounter::default() => {
+                    lines.push(format!("{op}  [{}]", fmt_slots(&slots)))
+                }
+                _ => lines.push(op.to_string()),
+            }
+        }
+        let branches = match &block.ctrl {
+            Ctrl::Return(vars) => {
+                lines.push(format!("return ({})", fmt_vars(vars)));
+                vec![]
+            }
+            Ctrl::MatchTag(var, cases, def) => {
+                lines.push(format!("match {var}.tag"));
+                let mut branches = cases
+                    .iter()
+                    .map(|(tag, block)| (fmt_tag(tag), block))
+                    .collect::<Vec<_>>();
+                if let Some(def) = def {
+                    branches.push(("_".into(), def.as_ref()));
+                }
+                branches
+            }
+            Ctrl::MatchValue(var, _, cases, def) => {
+                lines.push(format!("match {var}"));
+                let mut branches = cases
+                    .iter()
+                    .map(|(lit, block)| (fmt_lit(lit), block))
+                    .collect::<Vec<_>>();
+                if let Some(def) = def {
+                    branches.push(("_".into(), def.as_ref()));
+                }
+                branches
+            }
+            Ctrl::If(x, true_block, false_block) => {
+                lines.push(format!("if {x}"));
+                vec![
+                    ("true".into(), true_block.as_ref()),
+                    ("false".into(), false_block.as_ref()),
+                ]
+            }
+        };
+        lines.push(String::new());
+        lines.push(format!("ops slots: {}", fmt_slots(&own_slots)));
+        lines.push(format!("total slots: {}", fmt_slots(&block.count_slots())));
+        nodes.push_str(&format!("        {id} [label=\"{}\"];\n", label(&lines)));
+
+        for func in calls {
+            let canonical = canonical(func);
+            let entry = match self
+                .rendered
+                .iter()
+                .find(|(rendered, _)| *rendered == canonical)
+            {
+                Some((_, entry)) => entry.clone(),
+                None => {
+                    let entry = self.func(func);
+                    self.rendered.push((canonical, entry.clone()));
+                    entry
+                }
+            };
+            self.edges.push_str(&format!(
+                "    {id} -> {entry} [style=dashed, label=\"{}\"];\n",
+                func.name
+            ));
+        }
+
+        let max = branches
+            .iter()
+            .fold(SlotsCounter::default(), |acc, (_, block)| {
+                acc.cmp_max(block.count_slots())
+            });
+        for (case, branch) in branches {
+            let branch_id = self.block(branch, nodes);
+            let drives = drives(&branch.count_slots(), &max);
+            if drives.is_empty() {
+                self.edges.push_str(&format!(
+                    "    {id} -> {branch_id} [label=\"{}\"];\n",
+                    case.replace('"', "\\\"")
+                ));
+            } else {
+                self.edges.push_str(&format!(
+                    "    {id} -> {branch_id} [label=\"{}\\nmax {}\", color=red, penwidth=2];\n",
+                    case.replace('"', "\\\""),
+                    drives.join(", ")
+                ));
+            }
+        }
+        id
+    }
+}
+
+impl Func {
+    /// Renders the control flow of `self` as a Graphviz digraph
+    pub fn to_dot(&self) -> String {
+        let mut renderer = Renderer::default();
+        renderer.func(self);
+        format!(
+            "digraph \"{}\" {{\n    node [shape=box, fontname=monospace];\n{}{}}}\n",
+            self.name, renderer.clusters, renderer.edges
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{aux_func, func};
+
+    #[test]
+    fn test_to_dot() {
+        let tail = aux_func!(tail(xs): 1 => {
+            let (_x, rest) = decons2(xs);
+            return (rest)
+        });
+        let func = func!(foo(xs, n): 1 => {
+            match xs.tag {
+                Expr::Cons => {
+                    let (rest) = tail(xs);
+                    let (rest) = tail(rest);
+                    return (rest)
+                }
+                Expr::Num => {
+                    let zero = Num(0);
+                    let neg = lt(xs, zero);
+                    if neg {
+                        return (zero)
+                    }
+                    return (n)
+                }
+            }
+        });
+        expect_test::expect![[r#"
+            digraph "foo" {
+                node [shape=box, fontname=monospace];
+                subgraph cluster_2 {
+                    label="fn tail(xs#4): 1\lslots: hash4: 1\l";
+                    n3 [label="let (_x#5, rest#6) = decons2(xs#4)  [hash4: 1]\lreturn (rest#6)\l\lops slots: hash4: 1\ltotal slots: hash4: 1\l"];
+                }
+                subgraph cluster_1 {
+                    label="fn foo(xs#1, n#2): 1\lslots: hash4: 2, bit_decomp: 3\l";
+                    n1 [label="match xs#1.tag\l\lops slots: none\ltotal slots: hash4: 2, bit_decomp: 3\l"];
+                    n2 [label="let (rest#3) = tail(xs#1)\llet (rest#7) = tail(rest#3)\lreturn (rest#7)\l\lops slots: hash4: 2\ltotal slots: hash4: 2\l"];
+                    n4 [label="let zero#11 = Num(0)\llet neg#12 = lt(xs#1, zero#11)  [bit_decomp: 3]\lif neg#12\l\lops slots: bit_decomp: 3\ltotal slots: bit_decomp: 3\l"];
+                    n5 [label="return (zero#11)\l\lops slots: none\ltotal slots: none\l"];
+                    n6 [label="return (n#2)\l\lops slots: none\ltotal slots: none\l"];
+                }
+                n2 -> n3 [style=dashed, label="tail"];
+                n2 -> n3 [style=dashed, label="tail"];
+                n1 -> n2 [label="Expr::Cons\nmax hash4", color=red, penwidth=2];
+                n4 -> n5 [label="true"];
+                n4 -> n6 [label="false"];
+                n1 -> n4 [label="Expr::Num\nmax bit_decomp", color=red, penwidth=2];
+            }
+        "#]]
+        .assert_eq(&func.to_dot());
+    }
+}
@@ -449,6 +449,22 @@ fn car_cdr_simple() -> Func {
     })
 }
 
+/// Turns a non-empty list of body forms into a single expression: the form
+/// itself if there's only one, or a `begin` wrapping all of them otherwise
+fn begin_body() -> Func {
+    aux_func!(begin_body(body): 1 => {
+        let (form, more) = decons2(body);
+        match more.tag {
+            Expr::Nil => {
+                return (form)
+            }
+        };
+        let begin = Symbol("begin");
+        let expr: Expr::Cons = cons2(begin, body);
+        return (expr)
+    })
+}
+
 /// This `Func` is used to call a standalone coprocessor out of the Lurk's step
 /// function. It checks whether the coprocessor expression corresponds to the
 /// right coprocessor. If it doesn't, evaluation will error and the circuit
@@ -462,7 +478,7 @@ fn car_cdr_simple() -> Func {
 ///
 /// ```ignore
 /// run_cproc(cproc, env, cont): 3 {
-///     let err: Cont::Error = HASH_8_ZEROS;
+///     let arity_mismatch = String("arity mismatch");
 ///     let nil = Symbol("nil");
 ///     let nil = cast(nil, Expr::Nil);
 ///     match cproc.tag {
@@ -488,10 +504,13 @@ fn car_cdr_simple() -> Func {
 ///                                 let expr: Expr::Thunk = cons2(expr, cont);
 ///                                 return (expr, env, cont);
 ///                             }
+///                             let err: Cont::Error = cons2(arity_mismatch, evaluated_args_cp);
 ///                             return (evaluated_args_cp, env, err);
 ///                         }
+///                         let err: Cont::Error = cons2(arity_mismatch, evaluated_args_cp);
 ///                         return (evaluated_args_cp, env, err);
 ///                     }
+///                     let err: Cont::Error = cons2(arity_mismatch, evaluated_args_cp);
 ///                     return (evaluated_args_cp, env, err);
 ///                 }
 ///             }
@@ -516,7 +535,12 @@ fn run_cproc(cproc_sym: Symbol, arity: usize) -> Func {
     let cproc_name = Var::new("cproc_name");
     let cproc_out = vec![expr.clone(), env.clone(), cont.clone()];
     let func_out = vec![expr, env.clone(), cont.clone()];
-    let err_block = Block::ctrl(ctrl!(return (evaluated_args_cp, env, err)));
+    // `run_cproc` doesn't go through `make_thunk`, so the error continuation
+    // must be built here
+    let err_block = Block {
+        ops: vec![op!(let err: Cont::Error = cons2(arity_mismatch, evaluated_args_cp))],
+        ctrl: ctrl!(return (evaluated_args_cp, env, err)),
+    };
     let mut cproc_inp = (0..arity)
         .map(|i| Var(format!("x{i}").into()))
         .collect::<Vec<_>>();
@@ -573,7 +597,7 @@ fn run_cproc(cproc_sym: Symbol, arity: usize) -> Func {
             vec![]
         } else {
             vec![
-                op!(let err: Cont::Error = HASH_8_ZEROS),
+                op!(let arity_mismatch = String("arity mismatch")),
                 op!(let nil = Symbol("nil")),
                 op!(let nil = cast(nil, Expr::Nil)),
             ]
@@ -656,7 +680,8 @@ fn is_cproc(cprocs: &[(&Symbol, usize)]) -> Func {
 ///
 /// ```ignore
 /// match_and_run_cproc(cproc_name, evaluated_args, env, cont): 4 {
-///     let err: Cont::Error = HASH_8_ZEROS;
+///     let cproc_err = String("coprocessor error");
+///     let arity_mismatch = String("arity mismatch");
 ///     let nil = Symbol("nil");
 ///     let nil = cast(nil, Expr::Nil);
 ///     let makethunk = Symbol("make-thunk");
@@ -680,7 +705,7 @@ fn is_cproc(cprocs: &[(&Symbol, usize)]) -> Func {
 ///                         Op::Cproc([expr, env, cont], x, [x0, x1, ..., x{n-1}, env, cont]);
 ///                         match cont.tag {
 ///                             Cont::Error => {
-///                                 return (expr, env, err, errctrl);
+///                                 return (expr, env, cproc_err, errctrl);
 ///                             }
 ///                             Cont::Terminal => {
 ///                                 return (expr, env, cont, ret);
@@ -688,11 +713,11 @@ fn is_cproc(cprocs: &[(&Symbol, usize)]) -> Func {
 ///                         };
 ///                         return (expr, env, cont, makethunk);
 ///                     }
-///                     return (evaluated_args_cp, env, err, errctrl);
+///                     return (evaluated_args_cp, env, arity_mismatch, errctrl);
 ///                 }
-///                 return (evaluated_args_cp, env, err, errctrl);
+///                 return (evaluated_args_cp, env, arity_mismatch, errctrl);
 ///             }
-///             return (evaluated_args_cp, env, err, errctrl);
+///             return (evaluated_args_cp, env, arity_mismatch, errctrl);
 ///         }
 ///         ...
 ///     }
@@ -713,8 +738,10 @@ fn match_and_run_cproc(cprocs: &[(&Symbol, usize)]) -> Func {
     let is_nil = Var::new("is_nil");
     let cproc_out = vec![expr.clone(), env.clone(), cont.clone()];
     let func_out = vec![expr, env.clone(), cont.clone(), Var::new("makethunk")];
-    let err_block = Block::ctrl(ctrl!(return (evaluated_args_cp, env, err, errctrl)));
-    let err_block_from_cproc = Block::ctrl(ctrl!(return (expr, env, err, errctrl)));
+    let err_block = Block::ctrl(ctrl!(
+        return (evaluated_args_cp, env, arity_mismatch, errctrl)
+    ));
+    let err_block_from_cproc = Block::ctrl(ctrl!(return (expr, env, cproc_err, errctrl)));
     let ret_block_from_cproc = Block::ctrl(ctrl!(return (expr, env, cont, ret)));
     let check_cproc_error_ctrl = Ctrl::match_tag(
         cont.clone(),
@@ -775,14 +802,15 @@ fn match_and_run_cproc(cprocs: &[(&Symbol, usize)]) -> Func {
     let func_inp = vec![cproc_name, evaluated_args, env, cont];
     let ops = if max_arity == 0 {
         vec![
-            op!(let err: Cont::Error = HASH_8_ZEROS),
+            op!(let cproc_err = String("coprocessor error")),
             op!(let makethunk = Symbol("make-thunk")),
             op!(let errctrl = Symbol("error")),
             op!(let ret = Symbol("return")),
         ]
     } else {
         vec![
-            op!(let err: Cont::Error = HASH_8_ZEROS),
+            op!(let cproc_err = String("coprocessor error")),
+            op!(let arity_mismatch = String("arity mismatch")),
             op!(let makethunk = Symbol("make-thunk")),
             op!(let errctrl = Symbol("error")),
             op!(let ret = Symbol("return")),
@@ -801,6 +829,7 @@ fn match_and_run_cproc(cprocs: &[(&Symbol, usize)]) -> Func {
 fn reduce(cprocs: &[(&Symbol, usize)]) -> Func {
     // Auxiliary functions
     let car_cdr_simple = car_cdr_simple();
+    let begin_body = begin_body();
     let expand_bindings = aux_func!(expand_bindings(head, body, body1, rest_bindings): 1 => {
         match rest_bindings.tag {
             Expr::Nil => {
@@ -835,6 +864,10 @@ fn reduce(cprocs: &[(&Symbol, usize)]) -> Func {
                 let op: Op1::U64;
                 return (op);
             }
+            Symbol("i64") => {
+                let op: Op1::I64;
+                return (op);
+            }
             Symbol("comm") => {
                 let op: Op1::Comm;
                 return (op);
@@ -859,6 +892,62 @@ fn reduce(cprocs: &[(&Symbol, usize)]) -> Func {
                 let op: Op1::Emit;
                 return (op);
             }
+            Symbol("lognot") => {
+                let op: Op1::LogNot;
+                return (op);
+            }
+            Symbol("vector-length") => {
+                let op: Op1::VectorLength;
+                return (op);
+            }
+            Symbol("vector?") => {
+                let op: Op1::IsVector;
+                return (op);
+            }
+            Symbol("string-length") => {
+                let op: Op1::StringLength;
+                return (op);
+            }
+            Symbol("string->list") => {
+                let op: Op1::StringToList;
+                return (op);
+            }
+            Symbol("num->string") => {
+                let op: Op1::NumToString;
+                return (op);
+            }
+            Symbol("string->num") => {
+                let op: Op1::StringToNum;
+                return (op);
+            }
+            Symbol("u64->string") => {
+                let op: Op1::U64ToString;
+                return (op);
+            }
+            Symbol("char->num") => {
+                let op: Op1::CharToNum;
+                return (op);
+            }
+            Symbol("symbol->string") => {
+                let op: Op1::SymbolToString;
+                return (op);
+            }
+            Symbol("symbol-package") => {
+                let op: Op1::SymbolPackage;
+                return (op);
+            }
+            Symbol("keyword?") => {
+                let op: Op1::IsKeyword;
+                return (op);
+            }
+            Symbol("inverse") => {
+                let op: Op1::Inverse;
+                return (op);
+            }
+            Symbol("env->list") => {
+                let op: Op1::EnvToList;
+                return (op);
+            }
         };
         return (nil)
     });
@@ -922,6 +1011,89 @@ fn reduce(cprocs: &[(&Symbol, usize)]) -> Func {
                 let op: Op2::GreaterEqual;
                 return (op);
             }
+            Symbol("logand") => {
+                let op: Op2::LogAnd;
+                return (op);
+            }
+            Symbol("logor") => {
+                let op: Op2::LogOr;
+                return (op);
+            }
+            Symbol("logxor") => {
+                let op: Op2::LogXor;
+                return (op);
+            }
+            Symbol("shl") => {
+                let op: Op2::Shl;
+                return (op);
+            }
+            Symbol("shr") => {
+                let op: Op2::Shr;
+                return (op);
+            }
+            Symbol("vector-ref") => {
+                let op: Op2::VectorRef;
+                return (op);
+            }
+            Symbol("catch") => {
+                let op: Op2::Catch;
+                return (op);
+            }
+            Symbol("throw") => {
+                let op: Op2::Throw;
+                return (op);
+            }
+            Symbol("apply") => {
+                let op: Op2::Apply;
+                return (op);
+            }
+            Symbol("string-append") => {
+                let op: Op2::StringAppend;
+                return (op);
+            }
+            Symbol("string<") => {
+                let op: Op2::StringLess;
+                return (op);
+            }
+            Symbol("expt") => {
+                let op: Op2::Expt;
+                return (op);
+            }
+            Symbol("env-lookup") => {
+                let op: Op2::EnvLookup;
+                return (op);
+            }
+            Symbol("+!") => {
+                let op: Op2::CheckedSum;
+                return (op);
+            }
+            Symbol("-!") => {
+                let op: Op2::CheckedDiff;
+                return (op);
+            }
+            Symbol("*!") => {
+                let op: Op2::CheckedProduct;
+                return (op);
+            }
+        };
+        return (nil)
+    });
+    let get_ternop = aux_func!(get_ternop(head): 1 => {
+        let nil = Symbol("nil");
+        let nil = cast(nil, Expr::Nil);
+        match head.value {
+            Symbol("vector-set") => {
+                let op: Op3::VectorSet;
+                return (op);
+            }
+            Symbol("substring") => {
+                let op: Op3::Substring;
+                return (op);
+            }
+            Symbol("env-extend") => {
+                let op: Op3::EnvExtend;
+                return (op);
+            }
         };
         return (nil)
     });
@@ -948,12 +1120,54 @@ fn reduce(cprocs: &[(&Symbol, usize)]) -> Func {
         let nil = cast(nil, Expr::Nil);
         return (nil)
     });
+    // Unlike `if`, `lambda` or `cond`, the built-ins below yield to a binding of
+    // their name. Outside of an empty environment, their forms first look their
+    // name up like any function call, under a `Call` continuation that holds the
+    // form. If the lookup fails, the form comes back with its environment tagged
+    // as `Nil`. Returns the environment to use and `t` if the form runs as the
+    // built-in, or `nil` if its name must be looked up first
+    let builtin_env = aux_func!(builtin_env(head, env): 2 => {
+        let t = Symbol("t");
+        match head.value {
+            Symbol("i64") | Symbol("lognot") | Symbol("logand") | Symbol("logor")
+            | Symbol("logxor") | Symbol("shl") | Symbol("shr") | Symbol("vector")
+            | Symbol("vector-length") | Symbol("vector?") | Symbol("vector-ref")
+            | Symbol("vector-set") | Symbol("catch") | Symbol("throw") | Symbol("apply")
+            | Symbol("match") | Symbol("string-length") | Symbol("string->list")
+            | Symbol("string-append") | Symbol("string<") | Symbol("substring")
+            | Symbol("num->string") | Symbol("string->num") | Symbol("u64->string")
+            | Symbol("char->num") | Symbol("symbol->string") | Symbol("string->symbol")
+            | Symbol("symbol-package") | Symbol("keyword?") | Symbol("expt")
+            | Symbol("inverse") | Symbol("receive") | Symbol("let-values") | Symbol("values")
+            | Symbol("call/cc") | Symbol("env->list") | Symbol("env-lookup")
+            | Symbol("env-extend") | Symbol("+!") | Symbol("-!") | Symbol("*!") => {
+                match env.tag {
+                    Expr::Nil => {
+                        let env = cast(env, Expr::Env);
+                        return (env, t)
+                    }
+                };
+                let zero = Num(0);
+                let env_is_zero = eq_val(env, zero);
+                if env_is_zero {
+                    return (env, t)
+                }
+                let nil = Symbol("nil");
+                let nil = cast(nil, Expr::Nil);
+                return (env, nil)
+            }
+        };
+        return (env, t)
+    });
     let is_cproc = is_cproc(cprocs);
+    // The value of the top binding of a `letrec` group of more than one
+    // binding is tagged `RecTop`, see `apply_cont`. While the lookup goes on,
+    // `state` is tagged `Env`: it's "not_found" or, past the top of a group,
+    // the environment of the group. A lookup that succeeds returns that state
+    // tagged `RecEnv`
     let lookup = aux_func!(lookup(expr, env, state): 3 => {
-        let found = Symbol("found");
-        let not_found = Symbol("not_found");
         let error = Symbol("error");
-        let continue = eq_val(not_found, state);
+        let continue = eq_tag(state, env);
         if !continue {
             return (expr, env, state)
         }
@@ -965,9 +1179,21 @@ fn reduce(cprocs: &[(&Symbol, usize)]) -> Func {
         let (var, val, smaller_env) = pop_binding(env);
         let eq_val = eq_val(var, expr);
         if eq_val {
+            let found = cast(state, Expr::RecEnv);
             return (val, env, found)
         }
-        return (expr, smaller_env, not_found)
+        // the state changes at the top of a group, or at the binding pushed for
+        // the group passed by the iterations before this one
+        match val.tag {
+            Expr::RecEnv => {
+                let group = cast(val, Expr::Env);
+                return (expr, smaller_env, group)
+            }
+            Expr::RecTop => {
+                return (expr, smaller_env, env)
+            }
+        };
+        return (expr, smaller_env, state)
     });
 
     // 1. receive data from channel;
@@ -1001,6 +1227,12 @@ fn reduce(cprocs: &[(&Symbol, usize)]) -> Func {
         }
 
         let errctrl = Symbol("error");
+        // reasons recorded in the error continuation, see `make_thunk`
+        let unbound_var = String("unbound variable");
+        let malformed = String("malformed expression");
+        let arity_mismatch = String("arity mismatch");
+        let not_fun = String("not a function");
+        let invalid_arg = String("invalid argument");
 
         match cont.tag {
             Cont::StreamStart => {
@@ -1021,7 +1253,7 @@ fn reduce(cprocs: &[(&Symbol, usize)]) -> Func {
                                 return(callable, env, cont, ret);
                             }
                         };
-                        return (expr, env, err, errctrl);
+                        return (expr, env, invalid_arg, errctrl);
                     }
                 };
                 // `stutter != nil` is the stuttering condition when in `StreamPause`
@@ -1062,10 +1294,11 @@ fn reduce(cprocs: &[(&Symbol, usize)]) -> Func {
                     return (expr, env, cont, apply)
                 }
                 let not_found = Symbol("not_found");
+                let not_found = cast(not_found, Expr::Env);
                 // `expr` is the symbol. If the lookup was successful, it will
-                // return the result as `res` and a "found" state. If the lookup
-                // is incomplete, it will return the original symbol as `res`
-                // and a "not_found" state
+                // return the result as `res` and a state tagged `RecEnv`. If the
+                // lookup is incomplete, it will return the original symbol as
+                // `res` and a state tagged `Env`, see `lookup`
                 let (res, res_env, state) = lookup(expr, env, not_found);
                 let (res, res_env, state) = lookup(res, res_env, state);
                 let (res, res_env, state) = lookup(res, res_env, state);
@@ -1074,29 +1307,77 @@ fn reduce(cprocs: &[(&Symbol, usize)]) -> Func {
                 let (res, res_env, state) = lookup(res, res_env, state);
                 let (res, res_env, state) = lookup(res, res_env, state);
                 let (res, res_env, state) = lookup(res, res_env, state);
-                match state.value {
-                    Symbol("error") => {
-                        return (expr, env, err, errctrl)
-                    }
-                    Symbol("found") => {
+                match state.tag {
+                    Expr::RecEnv => {
                         match res.tag {
-                            // if `val2` is a recursive closure, then extend its environment
+                            // the top binding of a group, whose environment is `res_env`
+                            Expr::RecTop => {
+                                let (args, body, closed_env, member) = decons4(res);
+                                match member.tag {
+                                    Expr::Nil => {
+                                        let rec = cast(res, Expr::Rec);
+                                        let extended = push_binding(expr, rec, closed_env);
+                                        let fun: Expr::Fun = cons4(args, body, extended, foo);
+                                        return (fun, res_env, cont, apply)
+                                    }
+                                    Expr::Sym => {
+                                        let fun: Expr::Fun = cons4(args, body, res_env, foo);
+                                        return (fun, res_env, cont, apply)
+                                    }
+                                };
+                                // any value other than a recursive closure is wrapped
+                                return (args, res_env, cont, apply)
+                            }
+                            // if `res` is a recursive closure, then extend its environment
                             Expr::Rec => {
-                                let (args, body, closed_env, _foo) = decons4(res);
-                                // remember `expr` is the original symbol, i.e. the symbol
-                                // of the recursive closure
-                                let extended = push_binding(expr, res, closed_env);
-                                let fun: Expr::Fun = cons4(args, body, extended, foo);
+                                let (args, body, closed_env, member) = decons4(res);
+                                // a member of a group found past its top closes over the group
+                                let outside = eq_val(state, not_found);
+                                let single = eq_tag(member, nil);
+                                let single = or(single, outside);
+                                if single {
+                                    // remember `expr` is the original symbol, i.e. the symbol
+                                    // of the recursive closure
+                                    let extended = push_binding(expr, res, closed_env);
+                                    let fun: Expr::Fun = cons4(args, body, extended, foo);
+                                    return (fun, res_env, cont, apply)
+                                }
+                                let group = cast(state, Expr::Env);
+                                let fun: Expr::Fun = cons4(args, body, group, foo);
                                 return (fun, res_env, cont, apply)
                             }
                         };
                         return (res, res_env, cont, apply)
                     }
-                    Symbol("not_found") => {
+                    Expr::Env => {
                         // if it's not yet found, we must keep reducing
-                        return (res, res_env, cont, ret)
+                        let outside = eq_val(state, not_found);
+                        if outside {
+                            return (res, res_env, cont, ret)
+                        }
+                        // past the top of a group, which the next iteration takes
+                        // from a binding pushed for it. It's bound to `t`, which is
+                        // never looked up
+                        let group = cast(state, Expr::RecEnv);
+                        let env = push_binding(t, group, res_env);
+                        return (res, env, cont, ret)
                     }
-                }
+                };
+                // the name of a built-in that isn't bound brings its
+                // form back, see `builtin_env`
+                match cont.tag {
+                    Cont::Call => {
+                        let (_args, call_env, continuation, form) = decons4(cont);
+                        match form.tag {
+                            Expr::Cons => {
+                                let call_env = cast(call_env, Expr::Nil);
+                                return (form, call_env, continuation, ret)
+                            }
+                        };
+                        return (expr, env, unbound_var, errctrl)
+                    }
+                };
+                return (expr, env, unbound_var, errctrl)
             }
             Expr::Cons => {
                 // No need for `car_cdr_simple` since the expression is already a `Cons`
@@ -1106,7 +1387,7 @@ fn reduce(cprocs: &[(&Symbol, usize)]) -> Func {
                 let rest_is_nil_or_cons = or(rest_is_nil, rest_is_cons);
                 if !rest_is_nil_or_cons {
                     // rest's tag can only be Nil or Cons
-                    return (expr, env, err, errctrl);
+                    return (expr, env, malformed, errctrl);
                 }
                 match head.tag {
                     Expr::Sym => {
@@ -1121,7 +1402,7 @@ fn reduce(cprocs: &[(&Symbol, usize)]) -> Func {
                             // Only a single body form allowed for now.
                             match body.tag {
                                 Expr::Nil => {
-                                    return (expr, env, err, errctrl)
+                                    return (expr, env, malformed, errctrl)
                                 }
                             };
                             match rest_body.tag {
@@ -1138,33 +1419,44 @@ fn reduce(cprocs: &[(&Symbol, usize)]) -> Func {
                                             let (val, end) = car_cdr_simple(vals);
                                             let end_is_nil = eq_tag(end, nil);
                                             if !end_is_nil {
-                                                return (expr, env, err, errctrl)
+                                                return (expr, env, malformed, errctrl)
                                             }
-                                            let (expanded) = expand_bindings(head, body, body1, rest_bindings);
                                             if head_is_let_sym {
+                                                let (expanded) = expand_bindings(head, body, body1, rest_bindings);
                                                 let cont: Cont::Let = cons4(var, env, expanded, cont);
                                                 return (val, env, cont, ret)
                                             }
-                                            let cont: Cont::LetRec = cons4(var, env, expanded, cont);
+                                            // `letrec` evaluates the bindings of its group one after
+                                            // the other, each where the previous ones are bound.
+                                            // The body and the environment below the group are
+                                            // kept in a `Let` continuation, which is never applied
+                                            let frame: Cont::Let = cons4(foo, env, body1, cont);
+                                            let cont: Cont::LetRec = cons4(var, rest_bindings, env, frame);
                                             return (val, env, cont, ret)
                                         }
                                     };
-                                    return (expr, env, err, errctrl)
+                                    return (expr, env, malformed, errctrl)
                                 }
                             };
-                            return (expr, env, err, errctrl)
+                            return (expr, env, malformed, errctrl)
+                        }
+                        let (env, builtin) = builtin_env(head, env);
+                        let is_builtin = eq_val(builtin, t);
+                        if !is_builtin {
+                            let cont: Cont::Call = cons4(rest, env, cont, expr);
+                            return (head, env, cont, ret)
                         }
                         match head.value {
                             Symbol("lambda") => {
                                 let (vars, rest) = car_cdr_simple(rest);
                                 let rest_nil = eq_tag(rest, nil);
                                 if rest_nil {
-                                    return (expr, env, err, errctrl)
+                                    return (expr, env, malformed, errctrl)
                                 }
                                 let (body, end) = car_cdr_simple(rest);
                                 let end_nil = eq_tag(end, nil);
                                 if !end_nil {
-                                    return (expr, env, err, errctrl)
+                                    return (expr, env, malformed, errctrl)
                                 }
                                 match vars.tag {
                                     Expr::Cons => {
@@ -1175,24 +1467,48 @@ fn reduce(cprocs: &[(&Symbol, usize)]) -> Func {
                                                 return (fun, env, cont, apply)
                                             }
                                         };
-                                        return (expr, env, err, errctrl)
+                                        return (expr, env, malformed, errctrl)
                                     }
                                     Expr::Nil => {
                                         let fun: Expr::Fun = cons4(vars, body, env, foo);
                                         return (fun, env, cont, apply)
                                     }
                                 };
-                                return (expr, env, err, errctrl)
+                                return (expr, env, malformed, errctrl)
                             }
                             Symbol("quote") => {
-                                let (quoted, end) = car_cdr_simple(rest);
-
+                                // `(quote)` has nothing to quote, so it's malformed
+                                match rest.tag {
+                                    Expr::Cons => {
+                                        let (quoted, end) = decons2(rest);
+                                        match end.tag {
+                                            Expr::Nil => {
+                                                return (quoted, env, cont, apply)
+                                            }
+                                        };
+                                        return (expr, env, malformed, errctrl)
+                                    }
+                                };
+                                return (expr, env, malformed, errctrl)
+                            }
+                            Symbol("quasiquote") => {
+                                let (template, end) = car_cdr_simple(rest);
                                 match end.tag {
                                     Expr::Nil => {
-                                        return (quoted, env, cont, apply)
+                                        match template.tag {
+                                            Expr::Cons => {
+                                                // the template is walked by `Quasiquote`
+                                                // continuations, starting at level 1
+                                                let level = Num(1);
+                                                let cont: Cont::Quasiquote = cons4(level, env, cont, foo);
+                                                return (template, env, cont, apply)
+                                            }
+                                        };
+                                        // atoms are quoted as they are
+                                        return (template, env, cont, apply)
                                     }
                                 };
-                                return (expr, env, err, errctrl)
+                                return (expr, env, malformed, errctrl)
                             }
                             Symbol("begin") => {
                                 let (arg1, more) = car_cdr_simple(rest);
@@ -1208,7 +1524,7 @@ fn reduce(cprocs: &[(&Symbol, usize)]) -> Func {
                             Symbol("eval") => {
                                 match rest.tag {
                                     Expr::Nil => {
-                                        return (expr, env, err, errctrl)
+                                        return (expr, env, arity_mismatch, errctrl)
                                     }
                                 };
                                 let (arg1, more) = car_cdr_simple(rest);
@@ -1223,16 +1539,249 @@ fn reduce(cprocs: &[(&Symbol, usize)]) -> Func {
                                 let cont: Cont::Binop = cons4(op, env, more, cont);
                                 return (arg1, env, cont, ret)
                             }
+                            Symbol("string->symbol") => {
+                                match rest.tag {
+                                    Expr::Nil => {
+                                        return (expr, env, arity_mismatch, errctrl)
+                                    }
+                                };
+                                // the package argument is optional, so `more` may be nil
+                                let (arg1, more) = car_cdr_simple(rest);
+                                let op: Op2::StringToSymbol;
+                                let cont: Cont::Binop = cons4(op, env, more, cont);
+                                return (arg1, env, cont, ret)
+                            }
                             Symbol("if") => {
                                 let (condition, more) = car_cdr_simple(rest);
                                 match more.tag {
                                     Expr::Nil => {
-                                        return (expr, env, err, errctrl)
+                                        return (expr, env, malformed, errctrl)
                                     }
                                 };
-                                let cont: Cont::If = cons4(more, env, cont, foo);
+                                // the whole form is kept to report it if malformed
+                                let cont: Cont::If = cons4(more, env, cont, expr);
                                 return (condition, env, cont, ret)
                             }
+                            Symbol("cond") => {
+                                match rest.tag {
+                                    Expr::Nil => {
+                                        return (nil, env, cont, apply)
+                                    }
+                                };
+                                let (clause, clauses) = decons2(rest);
+                                match clause.tag {
+                                    Expr::Cons => {
+                                        // the clause's condition is evaluated first and
+                                        // its body is kept aside until it's needed
+                                        let (condition, body) = decons2(clause);
+                                        let cont: Cont::Cond = cons4(body, clauses, env, cont);
+                                        return (condition, env, cont, ret)
+                                    }
+                                    Expr::Sym => {
+                                        // a variable can't be a clause, so this is a call of
+                                        // whatever `cond` is bound to, as in code that
+                                        // predates the form
+                                        let cont: Cont::Call = cons4(rest, env, cont, foo);
+                                        return (head, env, cont, ret)
+                                    }
+                                };
+                                return (expr, env, malformed, errctrl)
+                            }
+                            Symbol("and") => {
+                                match rest.tag {
+                                    Expr::Nil => {
+                                        return (t, env, cont, apply)
+                                    }
+                                };
+                                let (arg1, more) = decons2(rest);
+                                match more.tag {
+                                    Expr::Nil => {
+                                        return (arg1, env, cont, ret)
+                                    }
+                                    Expr::Cons => {
+                                        let cont: Cont::And = cons4(more, env, cont, foo);
+                                        return (arg1, env, cont, ret)
+                                    }
+                                };
+                                return (expr, env, malformed, errctrl)
+                            }
+                            Symbol("or") => {
+                                match rest.tag {
+                                    Expr::Nil => {
+                                        return (nil, env, cont, apply)
+                                    }
+                                };
+                                let (arg1, more) = decons2(rest);
+                                match more.tag {
+                                    Expr::Nil => {
+                                        return (arg1, env, cont, ret)
+                                    }
+                                    Expr::Cons => {
+                                        let cont: Cont::Or = cons4(more, env, cont, foo);
+                                        return (arg1, env, cont, ret)
+                                    }
+                                };
+                                return (expr, env, malformed, errctrl)
+                            }
+                            Symbol("when") => {
+                                let (condition, body) = car_cdr_simple(rest);
+                                match body.tag {
+                                    Expr::Cons => {
+                                        // `(when c a b)` is evaluated as `(if c (begin a b) nil)`
+                                        let (body) = begin_body(body);
+                                        let branches: Expr::Cons = cons2(nil, nil);
+                                        let branches: Expr::Cons = cons2(body, branches);
+                                        let cont: Cont::If = cons4(branches, env, cont, foo);
+                                        return (condition, env, cont, ret)
+                                    }
+                                };
+                                return (expr, env, malformed, errctrl)
+                            }
+                            Symbol("unless") => {
+                                let (condition, body) = car_cdr_simple(rest);
+                                match body.tag {
+                                    Expr::Cons => {
+                                        // `(unless c a b)` is evaluated as `(if c nil (begin a b))`
+                                        let (body) = begin_body(body);
+                                        let branches: Expr::Cons = cons2(body, nil);
+                                        let branches: Expr::Cons = cons2(nil, branches);
+                                        let cont: Cont::If = cons4(branches, env, cont, foo);
+                                        return (condition, env, cont, ret)
+                                    }
+                                };
+                                return (expr, env, malformed, errctrl)
+                            }
+                            Symbol("match") => {
+                                let (scrutinee, clauses) = car_cdr_simple(rest);
+                                match clauses.tag {
+                                    Expr::Cons => {
+                                        let cont: Cont::Match = cons4(clauses, env, cont, foo);
+                                        return (scrutinee, env, cont, ret)
+                                    }
+                                };
+                                return (expr, env, malformed, errctrl)
+                            }
+                            Symbol("receive") => {
+                                let (formals, rest) = car_cdr_simple(rest);
+                                let (producer, rest) = car_cdr_simple(rest);
+                                let (body, end) = car_cdr_simple(rest);
+                                let rest_is_nil = eq_tag(rest, nil);
+                                if rest_is_nil {
+                                    return (expr, env, malformed, errctrl)
+                                }
+                                let end_is_nil = eq_tag(end, nil);
+                                if !end_is_nil {
+                                    return (expr, env, malformed, errctrl)
+                                }
+                                match formals.tag {
+                                    Expr::Nil | Expr::Cons => {
+                                        let cont: Cont::Receive = cons4(formals, env, body, cont);
+                                        return (producer, env, cont, ret)
+                                    }
+                                };
+                                return (expr, env, malformed, errctrl)
+                            }
+                            Symbol("let-values") => {
+                                // `(let-values ((formals producer) . more) body)` is evaluated
+                                // as `(receive formals producer (let-values more body))`
+                                let (bindings, rest) = car_cdr_simple(rest);
+                                let (body, end) = car_cdr_simple(rest);
+                                let rest_is_nil = eq_tag(rest, nil);
+                                if rest_is_nil {
+                                    return (expr, env, malformed, errctrl)
+                                }
+                                let end_is_nil = eq_tag(end, nil);
+                                if !end_is_nil {
+                                    return (expr, env, malformed, errctrl)
+                                }
+                                match bindings.tag {
+                                    Expr::Nil => {
+                                        return (body, env, cont, ret)
+                                    }
+                                    Expr::Cons => {
+                                        let (binding, more) = decons2(bindings);
+                                        let (formals, vals) = car_cdr_simple(binding);
+                                        let (producer, vals_end) = car_cdr_simple(vals);
+                                        let vals_is_nil = eq_tag(vals, nil);
+                                        if vals_is_nil {
+                                            return (expr, env, malformed, errctrl)
+                                        }
+                                        let vals_end_is_nil = eq_tag(vals_end, nil);
+                                        if !vals_end_is_nil {
+                                            return (expr, env, malformed, errctrl)
+                                        }
+                                        match more.tag {
+                                            Expr::Nil => {
+                                                let cont: Cont::Receive = cons4(formals, env, body, cont);
+                                                return (producer, env, cont, ret)
+                                            }
+                                        };
+                                        let body: Expr::Cons = cons2(body, nil);
+                                        let body: Expr::Cons = cons2(more, body);
+                                        let body: Expr::Cons = cons2(head, body);
+                                        let cont: Cont::Receive = cons4(formals, env, body, cont);
+                                        return (producer, env, cont, ret)
+                                    }
+                                };
+                                return (expr, env, malformed, errctrl)
+                            }
+                            Symbol("values") => {
+                                // Under a receiving form, the values are bound one by one
+                                // in its environment instead of being collected
+                                match cont.tag {
+                                    Cont::Receive => {
+                                        match rest.tag {
+                                            Expr::Nil => {
+                                                let (formals, saved_env, body, continuation) = decons4(cont);
+                                                match formals.tag {
+                                                    Expr::Nil => {
+                                                        return (body, saved_env, continuation, ret)
+                                                    }
+                                                };
+                                                return (expr, env, arity_mismatch, errctrl)
+                                            }
+                                        };
+                                        let (arg, more) = decons2(rest);
+                                        match more.tag {
+                                            Expr::Nil => {
+                                                // a single value is received as is
+                                                return (arg, env, cont, ret)
+                                            }
+                                        };
+                                        // the receiving form is kept as is, to bind the
+                                        // first of its formals
+                                        let cont: Cont::Values = cons4(more, env, cont, foo);
+                                        return (arg, env, cont, ret)
+                                    }
+                                };
+                                // Elsewhere, only a single value is accepted
+                                let (arg, end) = car_cdr_simple(rest);
+                                if rest_is_nil {
+                                    return (expr, env, arity_mismatch, errctrl)
+                                }
+                                let end_is_nil = eq_tag(end, nil);
+                                if end_is_nil {
+                                    return (arg, env, cont, ret)
+                                }
+                                return (expr, env, arity_mismatch, errctrl)
+                            }
+                            Symbol("call/cc") => {
+                                // the current continuation is reified as a value and
+                                // passed to the function. That value can only be applied:
+                                // no operation takes it apart, and it's printed opaquely
+                                let (fun, end) = car_cdr_simple(rest);
+                                if rest_is_nil {
+                                    return (expr, env, arity_mismatch, errctrl)
+                                }
+                                let end_is_nil = eq_tag(end, nil);
+                                if !end_is_nil {
+                                    return (expr, env, arity_mismatch, errctrl)
+                                }
+                                let k: Expr::Cont = cons2(cont, foo);
+                                let args: Expr::Cons = cons2(k, nil);
+                                let cont: Cont::Call = cons4(args, env, cont, foo);
+                                return (fun, env, cont, ret)
+                            }
                             Symbol("empty-env") => {
                                 match rest.tag {
                                     Expr::Nil => {
@@ -1240,7 +1789,7 @@ fn reduce(cprocs: &[(&Symbol, usize)]) -> Func {
                                         return (empty_env, env, cont, apply)
                                     }
                                 };
-                                return (expr, env, err, errctrl)
+                                return (expr, env, arity_mismatch, errctrl)
                             }
                             Symbol("current-env") => {
                                 match rest.tag {
@@ -1248,7 +1797,22 @@ fn reduce(cprocs: &[(&Symbol, usize)]) -> Func {
                                         return (env, env, cont, apply)
                                     }
                                 };
-                                return (expr, env, err, errctrl)
+                                return (expr, env, arity_mismatch, errctrl)
+                            }
+                            Symbol("vector") => {
+                                match rest.tag {
+                                    Expr::Nil => {
+                                        let zero = Num(0);
+                                        let one = Num(1);
+                                        let empty_vec: Expr::Vector = cons4(zero, one, nil, foo);
+                                        return (empty_vec, env, cont, apply)
+                                    }
+                                };
+                                let (arg, unevaled_args) = decons2(rest);
+                                let args: Expr::Cons = cons2(unevaled_args, nil);
+                                let zero = Num(0);
+                                let cont: Cont::Vector = cons4(args, zero, env, cont);
+                                return (arg, env, cont, ret)
                             }
                         };
                         // unops
@@ -1262,9 +1826,9 @@ fn reduce(cprocs: &[(&Symbol, usize)]) -> Func {
                                     let cont: Cont::Unop = cons4(op, cont, foo, foo);
                                     return (arg1, env, cont, ret)
                                 }
-                                return (expr, env, err, errctrl);
+                                return (expr, env, arity_mismatch, errctrl);
                             }
-                            return (expr, env, err, errctrl);
+                            return (expr, env, arity_mismatch, errctrl);
                         }
                         // binops
                         let (op) = get_binop(head);
@@ -1277,9 +1841,28 @@ fn reduce(cprocs: &[(&Symbol, usize)]) -> Func {
                                     let cont: Cont::Binop = cons4(op, env, more, cont);
                                     return (arg1, env, cont, ret);
                                 }
-                                return (expr, env, err, errctrl);
+                                return (expr, env, arity_mismatch, errctrl);
                             }
-                            return (expr, env, err, errctrl);
+                            return (expr, env, arity_mismatch, errctrl);
+                        }
+                        // ternops
+                        let (op) = get_ternop(head);
+                        let op_is_nil = eq_tag(op, nil);
+                        if !op_is_nil {
+                            let (arg1, more) = car_cdr_simple(rest);
+                            let (_arg2, last) = car_cdr_simple(more);
+                            match last.tag {
+                                Expr::Cons => {
+                                    let (_arg3, end) = decons2(last);
+                                    let end_is_nil = eq_tag(end, nil);
+                                    if end_is_nil {
+                                        let cont: Cont::Ternop = cons4(op, env, more, cont);
+                                        return (arg1, env, cont, ret);
+                                    }
+                                    return (expr, env, arity_mismatch, errctrl);
+                                }
+                            };
+                            return (expr, env, arity_mismatch, errctrl);
                         }
                         //coprocessors
                         let (is_cproc) = is_cproc(head);
@@ -1308,7 +1891,7 @@ fn reduce(cprocs: &[(&Symbol, usize)]) -> Func {
                     let cont: Cont::Call = cons4(rest, env, cont, foo);
                     return (head, env, cont, ret);
                 }
-                return (expr, env, err, errctrl)
+                return (expr, env, not_fun, errctrl)
             }
         }
     })
@@ -1328,9 +1911,9 @@ fn reduce(cprocs: &[(&Symbol, usize)]) -> Func {
 fn choose_cproc_call(cprocs: &[(&Symbol, usize)], ivc: bool) -> Func {
     if cprocs.is_empty() {
         aux_func!(no_cproc_error(cproc_name, _evaluated_args, env, _cont): 4 => {
-            let err: Cont::Error = HASH_8_ZEROS;
+            let cproc_err = String("coprocessor error");
             let errctrl = Symbol("error");
-            return (cproc_name, env, err, errctrl);
+            return (cproc_name, env, cproc_err, errctrl);
         })
     } else if ivc {
         match_and_run_cproc(cprocs)
@@ -1346,9 +1929,30 @@ fn choose_cproc_call(cprocs: &[(&Symbol, usize)], ivc: bool) -> Func {
 
 fn apply_cont(cprocs: &[(&Symbol, usize)], ivc: bool) -> Func {
     let car_cdr_simple = car_cdr_simple();
-    // Returns 0u64 if both arguments are U64, 0 (num) if the arguments are some kind of number (either U64 or Num),
-    // and nil otherwise
-    let args_num_type = aux_func!(args_num_type(arg1, arg2): 1 => {
+    let begin_body = begin_body();
+    // Functions are bound by the groups of more than one `letrec` binding as
+    // recursive closures. The ones closed over the environment where the member
+    // is evaluated are marked as members, which `lookup` recognizes
+    let letrec_member = aux_func!(letrec_member(val, members_env): 1 => {
+        match val.tag {
+            Expr::Fun => {
+                let (args, body, closed_env, _foo) = decons4(val);
+                let is_member = eq_val(closed_env, members_env);
+                if is_member {
+                    let t = Symbol("t");
+                    let rec: Expr::Rec = cons4(args, body, closed_env, t);
+                    return (rec)
+                }
+                let rec = cast(val, Expr::Rec);
+                return (rec)
+            }
+        };
+        return (val)
+    });
+    // Returns 0u64 if both arguments are U64, 0s64 if both arguments are I64, 0 (num) if the arguments are some
+    // kind of number (either U64 or Num), and nil otherwise. Also returns the first argument that doesn't fit
+    // the type of the other, which is what a type error reports
+    let args_num_type = aux_func!(args_num_type(arg1, arg2): 2 => {
         let nil = Symbol("nil");
         let nil = cast(nil, Expr::Nil);
         match arg1.tag {
@@ -1356,44 +1960,626 @@ fn apply_cont(cprocs: &[(&Symbol, usize)], ivc: bool) -> Func {
                 match arg2.tag {
                     Expr::Num => {
                         let ret: Expr::Num;
-                        return (ret)
+                        return (ret, arg2)
                     }
                     Expr::U64 => {
                         let ret: Expr::Num;
-                        return (ret)
+                        return (ret, arg2)
                     }
                 };
-                return (nil)
+                return (nil, arg2)
             }
             Expr::U64 => {
                 match arg2.tag {
                     Expr::Num => {
                         let ret: Expr::Num;
-                        return (ret)
+                        return (ret, arg2)
                     }
                     Expr::U64 => {
                         let ret: Expr::U64;
-                        return (ret)
+                        return (ret, arg2)
                     }
                 };
-                return (nil)
+                return (nil, arg2)
+            }
+            Expr::I64 => {
+                match arg2.tag {
+                    Expr::I64 => {
+                        let ret: Expr::I64;
+                        return (ret, arg2)
+                    }
+                };
+                return (nil, arg2)
             }
         };
-        return (nil)
+        return (nil, arg1)
     });
-    let open_if_num_or_comm = aux_func!(open_if_num_or_comm(input): 1 => {
-        let num: Expr::Num;
-        let comm: Expr::Comm;
-        let input_is_num = eq_tag(input, num);
-        let input_is_comm = eq_tag(input, comm);
-        let input_is_num_or_comm = or(input_is_num, input_is_comm);
-        if input_is_num_or_comm {
-            let cast = cast(input, Expr::Comm);
-            let (_secret, payload) = open(cast);
-            return (payload)
+    // Returns the first argument that isn't a U64, for the operations that only
+    // take U64s
+    let non_u64_arg = aux_func!(non_u64_arg(arg1, arg2): 1 => {
+        match arg1.tag {
+            Expr::U64 => {
+                return (arg2)
+            }
+        };
+        return (arg1)
+    });
+    // I64s are represented by their two's complement, as 64 bits numbers, so
+    // they use the same operations as U64s and only need conversions like this
+    // one where the sign matters. Returns the field element with the same sign
+    // as the I64 `x`
+    let i64_to_num = aux_func!(i64_to_num(x): 1 => {
+        let low = truncate(x, 63);
+        let is_pos = eq_val(x, low);
+        if is_pos {
+            let num = cast(x, Expr::Num);
+            return (num)
+        }
+        let size_u64 = Num(18446744073709551616);
+        let num = sub(x, size_u64);
+        return (num)
+    });
+    // Returns the two's complement negation of the 64 bits number `x`
+    let neg_i64 = aux_func!(neg_i64(x): 1 => {
+        let zero = Num(0);
+        let is_zero = eq_val(x, zero);
+        if is_zero {
+            return (x)
+        }
+        let size_u64 = Num(18446744073709551616);
+        let neg = sub(size_u64, x);
+        return (neg)
+    });
+    // Returns the absolute value of the 64-bit integer `x` of type `num_type` and
+    // `t` if `x` is a negative I64, or `nil` otherwise
+    let abs_int = aux_func!(abs_int(x, num_type): 2 => {
+        let nil = Symbol("nil");
+        let nil = cast(nil, Expr::Nil);
+        match num_type.tag {
+            Expr::I64 => {
+                let low = truncate(x, 63);
+                let is_pos = eq_val(x, low);
+                if is_pos {
+                    return (x, nil)
+                }
+                let t = Symbol("t");
+                let size_u64 = Num(18446744073709551616);
+                let abs = sub(size_u64, x);
+                return (abs, t)
+            }
+        };
+        return (x, nil)
+    });
+    // Returns the quotient, rounded towards zero, and the remainder of the
+    // division of the 64-bit integer `a` by the non-zero `b`, both of type
+    // `num_type`. The remainder has the same sign as `a`. U64s and I64s share
+    // the same division of absolute values
+    let div_rem_int = aux_func!(div_rem_int(a, b, num_type): 2 => {
+        let nil = Symbol("nil");
+        let nil = cast(nil, Expr::Nil);
+        let (a_abs, a_neg) = abs_int(a, num_type);
+        let (b_abs, b_neg) = abs_int(b, num_type);
+        let (div, rem) = div_rem64(a_abs, b_abs);
+        let a_is_pos = eq_tag(a_neg, nil);
+        let same_sign = eq_tag(a_neg, b_neg);
+        if a_is_pos {
+            if same_sign {
+                return (div, rem)
+            }
+            let (div) = neg_i64(div);
+            return (div, rem)
+        }
+        let (rem) = neg_i64(rem);
+        if same_sign {
+            return (div, rem)
+        }
+        let (div) = neg_i64(div);
+        return (div, rem)
+    });
+    // Returns `t` if `a` is less than `b`, both of type `num_type`, or `nil`
+    // otherwise. I64s are offset by 2^63, which orders them like U64s, and
+    // compared by checking that their difference plus 2^64 fits in 64 bits,
+    // which takes fewer bit decompositions than `lt`
+    let less_than = aux_func!(less_than(a, b, num_type): 1 => {
+        let nil = Symbol("nil");
+        let nil = cast(nil, Expr::Nil);
+        let t = Symbol("t");
+        match num_type.tag {
+            Expr::I64 => {
+                let offset = Num(9223372036854775808);
+                let size_u64 = Num(18446744073709551616);
+                let a = add(a, offset);
+                let a = truncate(a, 64);
+                let b = add(b, offset);
+                let b = truncate(b, 64);
+                let diff = sub(a, b);
+                let diff = add(diff, size_u64);
+                let low = truncate(diff, 64);
+                let is_less = eq_val(diff, low);
+                if is_less {
+                    return (t)
+                }
+                return (nil)
+            }
+        };
+        let is_less = lt(a, b);
+        if is_less {
+            return (t)
+        }
+        return (nil)
+    });
+    // Vectors are headers `(len, cap, root, foo)` over 4-ary trees of depth
+    // log4(cap), whose leaves are the elements of the vector. Returns the child
+    // of `node` at position `pos`.
+    //
+    // There's no default arm: positions come from `vector_digit`, which always
+    // returns a position below 4
+    let vector_child = aux_func!(vector_child(node, pos): 1 => {
+        let (c0, c1, c2, c3) = decons4(node);
+        match pos.value {
+            Num(0) => {
+                return (c0)
+            }
+            Num(1) => {
+                return (c1)
+            }
+            Num(2) => {
+                return (c2)
+            }
+            Num(3) => {
+                return (c3)
+            }
+        }
+    });
+    // Returns a copy of `node` with its child at position `pos` replaced by `x`.
+    // Like in `vector_child`, `pos` is always below 4
+    let vector_replace = aux_func!(vector_replace(node, pos, x): 1 => {
+        let (c0, c1, c2, c3) = decons4(node);
+        match pos.value {
+            Num(0) => {
+                let node: Expr::Vector = cons4(x, c1, c2, c3);
+                return (node)
+            }
+            Num(1) => {
+                let node: Expr::Vector = cons4(c0, x, c2, c3);
+                return (node)
+            }
+            Num(2) => {
+                let node: Expr::Vector = cons4(c0, c1, x, c3);
+                return (node)
+            }
+            Num(3) => {
+                let node: Expr::Vector = cons4(c0, c1, c2, x);
+                return (node)
+            }
+        }
+    });
+    // Scales an index below `cap` to an offset below 2^64 whose top two bits
+    // are the position of the index at the root of a tree of capacity `cap`,
+    // so that walking down the tree doesn't need to divide by the capacity
+    // of each level. Capacities are powers of 4 that divide 2^64
+    let vector_offset = aux_func!(vector_offset(idx, cap): 1 => {
+        let idx = cast(idx, Expr::Num);
+        let cap = cast(cap, Expr::Num);
+        let size_u64 = Num(18446744073709551616);
+        let scale = div(size_u64, cap);
+        let offset = mul(idx, scale);
+        return (offset)
+    });
+    // Splits an offset below 2^64 into its top two bits, which are the position
+    // at the current level, and the offset for the next level
+    let vector_digit = aux_func!(vector_digit(offset): 2 => {
+        let offset = cast(offset, Expr::Num);
+        let low = truncate(offset, 62);
+        let top = sub(offset, low);
+        let unit = Num(4611686018427387904);
+        let pos = div(top, unit);
+        let four = Num(4);
+        let offset = mul(low, four);
+        return (pos, offset)
+    });
+    // Prepends `nil`s to the reversed list of `count` elements of a tree level
+    // until its length is a multiple of 4, which pads the end of the level
+    let vector_pad = aux_func!(vector_pad(list, count): 1 => {
+        let nil = Symbol("nil");
+        let nil = cast(nil, Expr::Nil);
+        let rem = truncate(count, 2);
+        match rem.value {
+            Num(1) => {
+                let list: Expr::Cons = cons2(nil, list);
+                let list: Expr::Cons = cons2(nil, list);
+                let list: Expr::Cons = cons2(nil, list);
+                return (list)
+            }
+            Num(2) => {
+                let list: Expr::Cons = cons2(nil, list);
+                let list: Expr::Cons = cons2(nil, list);
+                return (list)
+            }
+            Num(3) => {
+                let list: Expr::Cons = cons2(nil, list);
+                return (list)
+            }
+        };
+        return (list)
+    });
+    // Pushes the first of the `reversed` elements collected by a string or
+    // environment scan onto `acc`, which is either a string or a list, and
+    // returns the result along with the continuation that pushes the remaining ones
+    let str_build_step = aux_func!(str_build_step(reversed, acc, continuation): 2 => {
+        let foo: Expr::Nil;
+        match reversed.tag {
+            Expr::Nil => {
+                return (acc, continuation)
+            }
+        };
+        let (c, rest) = decons2(reversed);
+        let cont: Cont::StrBuild = cons4(rest, continuation, foo, foo);
+        match acc.tag {
+            Expr::Str => {
+                let acc: Expr::Str = cons2(c, acc);
+                match rest.tag {
+                    Expr::Nil => {
+                        return (acc, continuation)
+                    }
+                };
+                return (acc, cont)
+            }
+        };
+        let acc: Expr::Cons = cons2(c, acc);
+        match rest.tag {
+            Expr::Nil => {
+                return (acc, continuation)
+            }
+        };
+        return (acc, cont)
+    });
+    // Returns the value of the digit whose character has the code `c`, in bases
+    // up to 16 and in either case, or `nil` if it isn't one
+    let char_digit = aux_func!(char_digit(c): 1 => {
+        match c.value {
+            Num(48) | Num(49) | Num(50) | Num(51) | Num(52) | Num(53) | Num(54) | Num(55) | Num(56) | Num(57) => {
+                let zero_char = Num(48);
+                let digit = sub(c, zero_char);
+                return (digit)
+            }
+            Num(65) | Num(66) | Num(67) | Num(68) | Num(69) | Num(70) => {
+                let offset = Num(55);
+                let digit = sub(c, offset);
+                return (digit)
+            }
+            Num(97) | Num(98) | Num(99) | Num(100) | Num(101) | Num(102) => {
+                let offset = Num(87);
+                let digit = sub(c, offset);
+                return (digit)
+            }
+        };
+        let nil = Symbol("nil");
+        let nil = cast(nil, Expr::Nil);
+        return (nil)
+    });
+    // Returns the character of the hexadecimal digit `digit`, in lowercase like
+    // the printer writes them
+    let hex_char = aux_func!(hex_char(digit): 1 => {
+        let zero_char = Num(48);
+        let c = add(digit, zero_char);
+        match digit.value {
+            Num(10) | Num(11) | Num(12) | Num(13) | Num(14) | Num(15) => {
+                let offset = Num(39);
+                let c = add(c, offset);
+                let c = cast(c, Expr::Char);
+                return (c)
+            }
+        };
+        let c = cast(c, Expr::Char);
+        return (c)
+    });
+    // Reads the minus sign of a number in the non-empty `string`, returning `t`
+    // and the rest of the string if it's there, or `nil` and the whole string
+    let num_sign = aux_func!(num_sign(string): 2 => {
+        let (c, rest) = decons2(string);
+        let minus = Num(45);
+        let is_minus = eq_val(c, minus);
+        if is_minus {
+            let t = Symbol("t");
+            return (t, rest)
+        }
+        let nil = Symbol("nil");
+        let nil = cast(nil, Expr::Nil);
+        return (nil, string)
+    });
+    // Reads the base prefix of the digits of a number in `string`, returning
+    // the base and the rest of the string. Like the reader, digits without a
+    // prefix are decimal
+    let num_prefix = aux_func!(num_prefix(string): 2 => {
+        let empty_str = String("");
+        let ten = Num(10);
+        let is_empty = eq_val(string, empty_str);
+        if is_empty {
+            return (ten, string)
+        }
+        let (c, rest) = decons2(string);
+        let zero_char = Num(48);
+        let is_zero_char = eq_val(c, zero_char);
+        if !is_zero_char {
+            return (ten, string)
+        }
+        let rest_is_empty = eq_val(rest, empty_str);
+        if rest_is_empty {
+            return (ten, string)
+        }
+        let (code, digits) = decons2(rest);
+        let code = cast(code, Expr::Num);
+        match code.value {
+            Num(98) => {
+                let two = Num(2);
+                return (two, digits)
+            }
+            Num(111) => {
+                let eight = Num(8);
+                return (eight, digits)
+            }
+            Num(100) => {
+                return (ten, digits)
+            }
+            Num(120) => {
+                let sixteen = Num(16);
+                return (sixteen, digits)
+            }
+        };
+        return (ten, string)
+    });
+    let open_if_num_or_comm = aux_func!(open_if_num_or_comm(input): 1 => {
+        let num: Expr::Num;
+        let comm: Expr::Comm;
+        let input_is_num = eq_tag(input, num);
+        let input_is_comm = eq_tag(input, comm);
+        let input_is_num_or_comm = or(input_is_num, input_is_comm);
+        if input_is_num_or_comm {
+            let cast = cast(input, Expr::Comm);
+            let (_secret, payload) = open(cast);
+            return (payload)
         }
         return (input)
     });
+    // Tells whether `var` is `&rest`, returning "rest" or "no-rest"
+    let rest_state = aux_func!(rest_state(var): 1 => {
+        let rest_sym = Symbol("&rest");
+        let is_rest_tag = eq_tag(var, rest_sym);
+        let is_rest_val = eq_val(var, rest_sym);
+        let is_rest = and(is_rest_tag, is_rest_val);
+        if is_rest {
+            let rest = Symbol("rest");
+            return (rest)
+        }
+        let no_rest = Symbol("no-rest");
+        return (no_rest)
+    });
+    // Returns the variable that collects the remaining arguments from the
+    // `rest_vars` following `&rest`, which must be exactly one symbol, and
+    // whether they are "ok" or an "error"
+    let get_rest_var = aux_func!(get_rest_var(rest_vars): 2 => {
+        let nil = Symbol("nil");
+        let nil = cast(nil, Expr::Nil);
+        let (rest_var, end) = car_cdr_simple(rest_vars);
+        let error = Symbol("error");
+        let end_is_nil = eq_tag(end, nil);
+        if end_is_nil {
+            match rest_var.tag {
+                Expr::Sym => {
+                    let ok = Symbol("ok");
+                    return (rest_var, ok)
+                }
+            };
+            return (rest_var, error)
+        }
+        return (rest_var, error)
+    });
+    // Arguments are evaluated in the environment saved by `Call` continuations,
+    // except for the ones passed by `apply`, which are values already and come
+    // with `nil` in its place. Returns the environment and the control of the
+    // next argument
+    let next_arg = aux_func!(next_arg(args_env, env): 2 => {
+        match args_env.tag {
+            Expr::Env => {
+                let ret = Symbol("return");
+                return (args_env, ret)
+            }
+        };
+        let makethunk = Symbol("make-thunk");
+        return (env, makethunk)
+    });
+    // Returns the continuation underneath `cont`, or nil if `cont` is atomic
+    let cont_parent = aux_func!(cont_parent(cont): 1 => {
+        let nil = Symbol("nil");
+        let nil = cast(nil, Expr::Nil);
+        match cont.tag {
+            // the bottom of the stack of an evaluation and of each stream call,
+            // so throws that reach them are uncaught
+            Cont::Outermost | Cont::StreamDispatch | Cont::StreamPause => {
+                return (nil)
+            }
+            Cont::Emit => {
+                let (parent, _foo, _foo, _foo) = decons4(cont);
+                return (parent)
+            }
+            Cont::Unop | Cont::Catch | Cont::StrBuild | Cont::EnvScan | Cont::Quasiquote3 => {
+                let (_foo, parent, _foo, _foo) = decons4(cont);
+                return (parent)
+            }
+            Cont::Call
+            | Cont::Binop2
+            | Cont::If
+            | Cont::VectorRef
+            | Cont::VectorNode
+            | Cont::RestReverse
+            | Cont::And
+            | Cont::Or
+            | Cont::Match
+            | Cont::Splice
+            | Cont::Quasiquote
+            | Cont::Digits
+            | Cont::Values
+            | Cont::Ternop3 => {
+                let (_foo, _foo, parent, _foo) = decons4(cont);
+                return (parent)
+            }
+            Cont::Call2
+            | Cont::Let
+            | Cont::LetRec
+            | Cont::Binop
+            | Cont::Cproc
+            | Cont::Vector
+            | Cont::VectorBuild
+            | Cont::VectorSet
+            | Cont::Rest
+            | Cont::Cond
+            | Cont::StrScan
+            | Cont::Receive
+            | Cont::MatchPattern
+            | Cont::Ternop
+            | Cont::Ternop2
+            | Cont::Quasiquote2 => {
+                let (_foo, _foo, _foo, parent) = decons4(cont);
+                return (parent)
+            }
+        };
+        return (nil)
+    });
+    // Takes the next `(pattern . value)` pair from the pending `work` of a
+    // `match` clause. Returns "done" if there's none left and "continue" otherwise
+    let match_next = aux_func!(match_next(work, bound_env): 5 => {
+        let continue = Symbol("continue");
+        match work.tag {
+            Expr::Nil => {
+                let done = Symbol("done");
+                return (done, work, work, work, bound_env)
+            }
+        };
+        let (pair, work) = decons2(work);
+        let (pattern, value) = decons2(pair);
+        return (continue, pattern, value, work, bound_env)
+    });
+    // Matches `value` against `pattern` as part of a `match` clause, extending
+    // `bound_env` with the variable it binds, if any. The components of a cons
+    // pattern are matched by the next steps, pushing the `(pattern . value)`
+    // pair of the cdrs to the pending `work`.
+    // Returns "failed" if `value` doesn't fit `pattern`, "done" if the whole
+    // pattern of the clause fits and "continue" along with the next pattern
+    // and value otherwise
+    let match_step = aux_func!(match_step(pattern, value, work, bound_env): 5 => {
+        let t = Symbol("t");
+        let quote = Symbol("quote");
+        let continue = Symbol("continue");
+        let failed = Symbol("failed");
+        match pattern.tag {
+            Expr::Sym => {
+                let is_t = eq_val(pattern, t);
+                if is_t {
+                    let value_is_sym = eq_tag(value, t);
+                    let value_is_t = eq_val(value, t);
+                    let same = and(value_is_sym, value_is_t);
+                    if same {
+                        let (state, pattern, value, work, bound_env) = match_next(work, bound_env);
+                        return (state, pattern, value, work, bound_env)
+                    }
+                    return (failed, pattern, value, work, bound_env)
+                }
+                let bound_env = push_binding(pattern, value, bound_env);
+                let (state, pattern, value, work, bound_env) = match_next(work, bound_env);
+                return (state, pattern, value, work, bound_env)
+            }
+            Expr::Cons => {
+                let (head, rest) = decons2(pattern);
+                let head_is_quote = eq_val(head, quote);
+                let rest_is_cons = eq_tag(rest, pattern);
+                let is_quoted = and(head_is_quote, rest_is_cons);
+                if is_quoted {
+                    let (quoted, _end) = decons2(rest);
+                    let same_tag = eq_tag(quoted, value);
+                    let same_val = eq_val(quoted, value);
+                    let same = and(same_tag, same_val);
+                    if same {
+                        let (state, pattern, value, work, bound_env) = match_next(work, bound_env);
+                        return (state, pattern, value, work, bound_env)
+                    }
+                    return (failed, pattern, value, work, bound_env)
+                }
+                match value.tag {
+                    Expr::Cons => {
+                        let (value_car, value_cdr) = decons2(value);
+                        match rest.tag {
+                            Expr::Nil => {
+                                // the end of a list pattern is checked right away
+                                match value_cdr.tag {
+                                    Expr::Nil => {
+                                        return (continue, head, value_car, work, bound_env)
+                                    }
+                                };
+                                return (failed, pattern, value, work, bound_env)
+                            }
+                        };
+                        let cdr_pair: Expr::Cons = cons2(rest, value_cdr);
+                        let work: Expr::Cons = cons2(cdr_pair, work);
+                        return (continue, head, value_car, work, bound_env)
+                    }
+                };
+                return (failed, pattern, value, work, bound_env)
+            }
+        };
+        // every other pattern is a literal
+        let same_tag = eq_tag(pattern, value);
+        let same_val = eq_val(pattern, value);
+        let same = and(same_tag, same_val);
+        if same {
+            let (state, pattern, value, work, bound_env) = match_next(work, bound_env);
+            return (state, pattern, value, work, bound_env)
+        }
+        return (failed, pattern, value, work, bound_env)
+    });
+    // Returns `t` and the arguments of `head` if it's an `unquote-splicing`
+    // form, or `nil` twice otherwise
+    let unquote_splicing_args = aux_func!(unquote_splicing_args(head): 2 => {
+        let nil = Symbol("nil");
+        let nil = cast(nil, Expr::Nil);
+        match head.tag {
+            Expr::Cons => {
+                let (op, args) = decons2(head);
+                let unquote_splicing = Symbol("unquote-splicing");
+                let op_is_sym = eq_tag(op, unquote_splicing);
+                let op_is_splice = eq_val(op, unquote_splicing);
+                let is_splice = and(op_is_sym, op_is_splice);
+                if is_splice {
+                    let t = Symbol("t");
+                    return (t, args)
+                }
+                return (nil, nil)
+            }
+        };
+        return (nil, nil)
+    });
+    // Expands `head` at `level` and `tail` at `tail_level` into a pair, returning
+    // the template to be expanded next and its continuation. Atoms are kept as
+    // they are, so a pair of atoms is built right away
+    let quasiquote_pair = aux_func!(quasiquote_pair(head, tail, level, tail_level, env, cont): 2 => {
+        let foo: Expr::Nil;
+        match head.tag {
+            Expr::Cons => {
+                let cont: Cont::Quasiquote2 = cons4(tail, tail_level, env, cont);
+                let cont: Cont::Quasiquote = cons4(level, env, cont, foo);
+                return (head, cont)
+            }
+        };
+        match tail.tag {
+            Expr::Cons => {
+                let cont: Cont::Quasiquote3 = cons4(head, cont, foo, foo);
+                let cont: Cont::Quasiquote = cons4(tail_level, env, cont, foo);
+                return (tail, cont)
+            }
+        };
+        let pair: Expr::Cons = cons2(head, tail);
+        return (pair, cont)
+    });
     let choose_cproc_call = choose_cproc_call(cprocs, ivc);
     aux_func!(apply_cont(result, env, cont, ctrl): 4 => {
         match ctrl.value {
@@ -1410,7 +2596,20 @@ fn apply_cont(cprocs: &[(&Symbol, usize)], ivc: bool) -> Func {
                 let foo: Expr::Nil;
                 let char: Expr::Char;
                 let u64: Expr::U64;
-                let err: Cont::Error = HASH_8_ZEROS;
+                // reasons recorded in the error continuation, see `make_thunk`
+                let malformed = String("malformed expression");
+                let arity_mismatch = String("arity mismatch");
+                let not_fun = String("not a function");
+                let car_non_list = String("car of non-list");
+                let cdr_non_list = String("cdr of non-list");
+                let non_numeric = String("non-numeric argument");
+                let div_by_zero = String("division by zero");
+                let invalid_arg = String("invalid argument");
+                let out_of_bounds = String("index out of bounds");
+                let uncaught = String("uncaught throw");
+                let no_match = String("no matching clause");
+                let overflow = String("u64 overflow");
+                let underflow = String("u64 underflow");
                 match cont.tag {
                     Cont::Outermost => {
                         let term: Cont::Terminal = HASH_8_ZEROS;
@@ -1424,7 +2623,7 @@ fn apply_cont(cprocs: &[(&Symbol, usize)], ivc: bool) -> Func {
                                 return (result, empty_env, cont, ret);
                             }
                         };
-                        return (result, env, err, errctrl);
+                        return (result, env, invalid_arg, errctrl);
                     }
                     Cont::Emit => {
                         let (cont, _rest, _foo, _foo) = decons4(cont);
@@ -1441,12 +2640,13 @@ fn apply_cont(cprocs: &[(&Symbol, usize)], ivc: bool) -> Func {
                                         match vars.tag {
                                             Expr::Nil => {
                                                 // Cannot apply non-zero number of arguments to a zero argument function
-                                                return (fun, env, err, errctrl)
+                                                return (fun, env, arity_mismatch, errctrl)
                                             }
                                             Expr::Cons => {
                                                 let (arg, rest_args) = decons2(args);
                                                 let newer_cont: Cont::Call2 = cons4(fun, rest_args, args_env, continuation);
-                                                return (arg, args_env, newer_cont, ret)
+                                                let (arg_env, next) = next_arg(args_env, env);
+                                                return (arg, arg_env, newer_cont, next)
                                             }
                                         }
                                     }
@@ -1456,6 +2656,21 @@ fn apply_cont(cprocs: &[(&Symbol, usize)], ivc: bool) -> Func {
                                                 return (body, fun_env, continuation, ret)
                                             }
                                             Expr::Cons => {
+                                                let (var, rest_vars) = decons2(vars);
+                                                let (state) = rest_state(var);
+                                                match state.value {
+                                                    Symbol("rest") => {
+                                                        let (rest_var, state) = get_rest_var(rest_vars);
+                                                        match state.value {
+                                                            Symbol("ok") => {
+                                                                // no arguments to be collected
+                                                                let ext_env = push_binding(rest_var, nil, fun_env);
+                                                                return (body, ext_env, continuation, ret)
+                                                            }
+                                                        };
+                                                        return (fun, env, malformed, errctrl)
+                                                    }
+                                                };
                                                 // TODO should we not fail here in an analogous way to the non-zero application
                                                 // on a zero argument function case?
                                                 return (fun, env, continuation, ret)
@@ -1464,19 +2679,64 @@ fn apply_cont(cprocs: &[(&Symbol, usize)], ivc: bool) -> Func {
                                     }
                                 }
                             }
+                            Expr::Cont => {
+                                // continuations take exactly one argument
+                                let (args, args_env, continuation, _foo) = decons4(cont);
+                                match args.tag {
+                                    Expr::Cons => {
+                                        let (arg, rest_args) = decons2(args);
+                                        let newer_cont: Cont::Call2 = cons4(fun, rest_args, args_env, continuation);
+                                        let (arg_env, next) = next_arg(args_env, env);
+                                        return (arg, arg_env, newer_cont, next)
+                                    }
+                                };
+                                return (fun, env, arity_mismatch, errctrl)
+                            }
                         };
-                        return (fun, env, err, errctrl)
+                        return (fun, env, not_fun, errctrl)
                     }
                     Cont::Call2 => {
                         let (function, args, args_env, continuation) = decons4(cont);
                         match function.tag {
                             Expr::Fun => {
                                 let (vars, body, fun_env, _foo) = decons4(function);
+                                let args_empty = eq_tag(args, nil);
+                                let cons: Expr::Cons;
+                                let args_is_cons = eq_tag(args, cons);
+                                let args_ok = or(args_empty, args_is_cons);
+                                if !args_ok {
+                                    // the list of arguments doesn't end in `nil`, which makes
+                                    // a call malformed, or the list passed to `apply` invalid
+                                    match args_env.tag {
+                                        Expr::Env => {
+                                            return (args, env, malformed, errctrl)
+                                        }
+                                    };
+                                    return (args, env, invalid_arg, errctrl)
+                                }
                                 // vars must be non-empty, so:
                                 let (var, rest_vars) = decons2(vars);
+                                let (state) = rest_state(var);
+                                match state.value {
+                                    Symbol("rest") => {
+                                        let (rest_var, state) = get_rest_var(rest_vars);
+                                        match state.value {
+                                            Symbol("ok") => {
+                                                // `result` is the first argument collected by
+                                                // `rest_var`, which is left to `Rest` along with
+                                                // the remaining ones. Only `rest_var` is kept
+                                                // from the variables
+                                                let collector: Expr::Fun = cons4(rest_var, body, fun_env, foo);
+                                                let pending: Expr::Cons = cons2(args, nil);
+                                                let cont: Cont::Rest = cons4(collector, pending, args_env, continuation);
+                                                return (result, env, cont, makethunk)
+                                            }
+                                        };
+                                        return (function, env, malformed, errctrl)
+                                    }
+                                };
                                 let ext_env = push_binding(var, result, fun_env);
                                 let rest_vars_empty = eq_tag(rest_vars, nil);
-                                let args_empty = eq_tag(args, nil);
                                 if rest_vars_empty {
                                     if args_empty {
                                         return (body, ext_env, continuation, ret)
@@ -1486,22 +2746,81 @@ fn apply_cont(cprocs: &[(&Symbol, usize)], ivc: bool) -> Func {
                                     return (body, ext_env, cont, ret)
                                 }
                                 let ext_function: Expr::Fun = cons4(rest_vars, body, ext_env, foo);
-                                let (var, _rest_vars) = car_cdr_simple(rest_vars);
+                                let (var, rest_vars) = car_cdr_simple(rest_vars);
                                 match var.tag {
                                     Expr::Sym => {
                                         if args_empty {
+                                            let (state) = rest_state(var);
+                                            match state.value {
+                                                Symbol("rest") => {
+                                                    let (rest_var, state) = get_rest_var(rest_vars);
+                                                    match state.value {
+                                                        Symbol("ok") => {
+                                                            // no arguments to be collected, so the
+                                                            // next iteration binds `rest_var` to `nil`
+                                                            let cont: Cont::Let = cons4(rest_var, ext_env, body, continuation);
+                                                            return (nil, env, cont, makethunk)
+                                                        }
+                                                    };
+                                                    return (ext_function, ext_env, continuation, ret)
+                                                }
+                                            };
                                             // Undersaturated call
                                             return (ext_function, ext_env, continuation, ret)
                                         }
                                         let (arg, rest_args) = decons2(args);
                                         let cont: Cont::Call2 = cons4(ext_function, rest_args, args_env, continuation);
-                                        return (arg, args_env, cont, ret)
+                                        let (arg_env, next) = next_arg(args_env, env);
+                                        return (arg, arg_env, cont, next)
                                     }
                                 };
-                                return (result, env, err, errctrl)
+                                return (result, env, malformed, errctrl)
+                            }
+                            Expr::Cont => {
+                                // the current continuation is discarded in favor of the
+                                // captured one, which receives `result`
+                                let args_empty = eq_tag(args, nil);
+                                if !args_empty {
+                                    return (function, env, arity_mismatch, errctrl)
+                                }
+                                let (captured, _foo) = decons2(function);
+                                return (result, env, captured, makethunk)
+                            }
+                        };
+                        return (result, env, not_fun, errctrl)
+                    }
+                    Cont::Rest => {
+                        // `result` is the next argument collected by the rest variable
+                        let (function, pending, args_env, continuation) = decons4(cont);
+                        let (unevaled_args, collected) = decons2(pending);
+                        let collected: Expr::Cons = cons2(result, collected);
+                        match unevaled_args.tag {
+                            Expr::Cons => {
+                                let (arg, unevaled_args) = decons2(unevaled_args);
+                                let pending: Expr::Cons = cons2(unevaled_args, collected);
+                                let cont: Cont::Rest = cons4(function, pending, args_env, continuation);
+                                let (arg_env, next) = next_arg(args_env, env);
+                                return (arg, arg_env, cont, next)
+                            }
+                        };
+                        // the arguments were collected in reverse order
+                        let cont: Cont::RestReverse = cons4(function, nil, continuation, foo);
+                        return (collected, env, cont, makethunk)
+                    }
+                    Cont::RestReverse => {
+                        // `result` holds the collected arguments yet to be reversed
+                        let (function, reversed, continuation, _foo) = decons4(cont);
+                        let (arg, collected) = decons2(result);
+                        let reversed: Expr::Cons = cons2(arg, reversed);
+                        match collected.tag {
+                            Expr::Nil => {
+                                let (rest_var, body, fun_env, _foo) = decons4(function);
+                                let ext_env = push_binding(rest_var, reversed, fun_env);
+                                return (body, ext_env, continuation, ret)
                             }
                         };
-                        return (result, env, err, errctrl)
+                        let cont: Cont::RestReverse = cons4(function, reversed, continuation, foo);
+                        return (collected, env, cont, makethunk)
                     }
                     Cont::Let => {
                         let (var, saved_env, body, cont) = decons4(cont);
@@ -1509,16 +2828,62 @@ fn apply_cont(cprocs: &[(&Symbol, usize)], ivc: bool) -> Func {
                         return (body, extended_env, cont, ret)
                     }
                     Cont::LetRec => {
-                        let (var, saved_env, body, cont) = decons4(cont);
-                        match result.tag {
-                            Expr::Fun => {
-                                let result = cast(result, Expr::Rec);
-                                let extended_env = push_binding(var, result, saved_env);
-                                return (body, extended_env, cont, ret)
+                        // `result` is the value of `var`, whose binding is followed by `rest`
+                        let (var, rest, members_env, frame) = decons4(cont);
+                        match rest.tag {
+                            Expr::Nil => {
+                                let (_foo, saved_env, body, continuation) = decons4(frame);
+                                let single = eq_val(members_env, saved_env);
+                                if single {
+                                    match result.tag {
+                                        Expr::Fun => {
+                                            let rec = cast(result, Expr::Rec);
+                                            let extended_env = push_binding(var, rec, members_env);
+                                            return (body, extended_env, continuation, ret)
+                                        }
+                                    };
+                                    let extended_env = push_binding(var, result, members_env);
+                                    return (body, extended_env, continuation, ret)
+                                }
+                                // the value of the top binding of a group of more than one
+                                // binding is tagged for `lookup`. A recursive closure only
+                                // needs the tag, and any other value is wrapped in one
+                                // whose last field is a number
+                                match result.tag {
+                                    Expr::Fun => {
+                                        let (member) = letrec_member(result, members_env);
+                                        let top = cast(member, Expr::RecTop);
+                                        let extended_env = push_binding(var, top, members_env);
+                                        return (body, extended_env, continuation, ret)
+                                    }
+                                };
+                                let zero = Num(0);
+                                let top: Expr::RecTop = cons4(result, foo, foo, zero);
+                                let extended_env = push_binding(var, top, members_env);
+                                return (body, extended_env, continuation, ret)
                             }
                         };
-                        let extended_env = push_binding(var, result, saved_env);
-                        return (body, extended_env, cont, ret)
+                        let (member) = letrec_member(result, members_env);
+                        let extended_env = push_binding(var, member, members_env);
+                        let (binding, rest) = decons2(rest);
+                        match binding.tag {
+                            Expr::Cons => {
+                                let (var, vals) = decons2(binding);
+                                match var.tag {
+                                    Expr::Sym => {
+                                        let (val, end) = car_cdr_simple(vals);
+                                        let end_is_nil = eq_tag(end, nil);
+                                        if end_is_nil {
+                                            let cont: Cont::LetRec = cons4(var, rest, extended_env, frame);
+                                            return (val, extended_env, cont, ret)
+                                        }
+                                        return (binding, env, malformed, errctrl)
+                                    }
+                                };
+                                return (binding, env, malformed, errctrl)
+                            }
+                        };
+                        return (binding, env, malformed, errctrl)
                     }
                     Cont::Unop => {
                         let comm: Expr::Comm;
@@ -1548,7 +2913,7 @@ fn apply_cont(cprocs: &[(&Symbol, usize)], ivc: bool) -> Func {
                                         return (car, env, continuation, makethunk)
                                     }
                                 };
-                                return(result, env, err, errctrl)
+                                return(result, env, car_non_list, errctrl)
                             }
                             Op1::Cdr => {
                                 // `car_cdr` semantics
@@ -1569,7 +2934,7 @@ fn apply_cont(cprocs: &[(&Symbol, usize)], ivc: bool) -> Func {
                                         return (cdr, env, continuation, makethunk)
                                     }
                                 };
-                                return(result, env, err, errctrl)
+                                return(result, env, cdr_non_list, errctrl)
                             }
                             Op1::Atom => {
                                 match result.tag {
@@ -1590,7 +2955,7 @@ fn apply_cont(cprocs: &[(&Symbol, usize)], ivc: bool) -> Func {
                                     let (_secret, payload) = open(result);
                                     return(payload, env, continuation, makethunk)
                                 }
-                                return(result, env, err, errctrl)
+                                return(result, env, invalid_arg, errctrl)
                             }
                             Op1::Secret => {
                                 if result_is_num_or_comm {
@@ -1598,90 +2963,339 @@ fn apply_cont(cprocs: &[(&Symbol, usize)], ivc: bool) -> Func {
                                     let (secret, _payload) = open(result);
                                     return(secret, env, continuation, makethunk)
                                 }
-                                return(result, env, err, errctrl)
+                                return(result, env, invalid_arg, errctrl)
                             }
                             Op1::Commit => {
                                 let comm = hide(zero, result);
                                 return(comm, env, continuation, makethunk)
                             }
                             Op1::Num => {
+                                match result.tag {
+                                    Expr::I64 => {
+                                        let (num) = i64_to_num(result);
+                                        return(num, env, continuation, makethunk)
+                                    }
+                                };
                                 let acc_cast = or(result_is_num_or_comm, result_is_char);
                                 let acc_cast = or(acc_cast, result_is_u64);
                                 if acc_cast {
                                     let cast = cast(result, Expr::Num);
                                     return(cast, env, continuation, makethunk)
                                 }
-                                return(result, env, err, errctrl)
+                                return(result, env, non_numeric, errctrl)
                             }
                             Op1::U64 => {
+                                match result.tag {
+                                    Expr::I64 => {
+                                        // Reinterprets the bits of the two's complement
+                                        let cast = cast(result, Expr::U64);
+                                        return(cast, env, continuation, makethunk)
+                                    }
+                                };
                                 let result_is_num_or_u64 = or(result_is_num, result_is_u64);
                                 if result_is_num_or_u64 {
                                     // The limit is 2**64 - 1
-                                    let trunc = truncate(result, 64);
+                                    let num = cast(result, Expr::Num);
+                                    let trunc = truncate(num, 64);
                                     let cast = cast(trunc, Expr::U64);
                                     return(cast, env, continuation, makethunk)
                                 }
-                                return(result, env, err, errctrl)
+                                return(result, env, non_numeric, errctrl)
                             }
                             Op1::Comm => {
                                 if result_is_num_or_comm {
                                     let cast = cast(result, Expr::Comm);
                                     return(cast, env, continuation, makethunk)
                                 }
-                                return(result, env, err, errctrl)
+                                return(result, env, non_numeric, errctrl)
                             }
                             Op1::Char => {
                                 let result_is_num_or_char = or(result_is_num, result_is_char);
                                 if result_is_num_or_char {
                                     // The limit is 2**32 - 1
-                                    let trunc = truncate(result, 32);
+                                    let num = cast(result, Expr::Num);
+                                    let trunc = truncate(num, 32);
                                     let cast = cast(trunc, Expr::Char);
                                     return(cast, env, continuation, makethunk)
                                 }
-                                return(result, env, err, errctrl)
+                                return(result, env, non_numeric, errctrl)
                             }
                             Op1::Eval => {
                                 return(result, empty_env, continuation, ret)
                             }
-                        };
-                        return (result, env, err, errctrl)
-                    }
-                    Cont::Binop => {
-                        let (operator, saved_env, unevaled_args, continuation) = decons4(cont);
-                        let (arg2, rest) = car_cdr_simple(unevaled_args);
-                        match operator.tag {
-                            Op2::Begin => {
-                                match rest.tag {
-                                    Expr::Nil => {
-                                        return (arg2, saved_env, continuation, ret)
+                            Op1::I64 => {
+                                match result.tag {
+                                    Expr::U64 | Expr::I64 => {
+                                        let cast = cast(result, Expr::I64);
+                                        return(cast, env, continuation, makethunk)
+                                    }
+                                    Expr::Num => {
+                                        // Negative numbers whose absolute value fits in 64 bits
+                                        // are mapped to its two's complement. Other numbers are
+                                        // truncated, like `u64` does
+                                        let abs = sub(zero, result);
+                                        let abs_trunc = truncate(abs, 64);
+                                        let is_neg = eq_val(abs, abs_trunc);
+                                        if is_neg {
+                                            let (neg) = neg_i64(abs_trunc);
+                                            let cast = cast(neg, Expr::I64);
+                                            return(cast, env, continuation, makethunk)
+                                        }
+                                        let trunc = truncate(result, 64);
+                                        let cast = cast(trunc, Expr::I64);
+                                        return(cast, env, continuation, makethunk)
                                     }
                                 };
-                                let begin = Symbol("begin");
-                                let begin_again: Expr::Cons = cons2(begin, unevaled_args);
-                                return (begin_again, saved_env, continuation, ctrl)
+                                return(result, env, non_numeric, errctrl)
                             }
-                        };
-                        match rest.tag {
-                            Expr::Nil => {
-                                let cont: Cont::Binop2 = cons4(operator, result, continuation, foo);
-                                return (arg2, saved_env, cont, ret)
+                            Op1::LogNot => {
+                                if result_is_u64 {
+                                    // Flipping all 64 bits is the same as subtracting from 2**64 - 1
+                                    let u64_max = Num(18446744073709551615);
+                                    let num = cast(result, Expr::Num);
+                                    let val = sub(u64_max, num);
+                                    let val = cast(val, Expr::U64);
+                                    return(val, env, continuation, makethunk)
+                                }
+                                return(result, env, non_numeric, errctrl)
                             }
-                        };
-                        return (result, env, err, errctrl)
-                    }
-                    Cont::Binop2 => {
-                        let size_u64 = Num(18446744073709551616);
-                        let (operator, evaled_arg, continuation, _foo) = decons4(cont);
-                        let (args_num_type) = args_num_type(evaled_arg, result);
-                        let args_num_type_eq_nil = eq_tag(args_num_type, nil);
-                        match operator.tag {
+                            Op1::VectorLength => {
+                                match result.tag {
+                                    Expr::Vector => {
+                                        let (len, _cap, _root, _foo) = decons4(result);
+                                        return(len, env, continuation, makethunk)
+                                    }
+                                };
+                                return(result, env, invalid_arg, errctrl)
+                            }
+                            Op1::StringLength => {
+                                match result.tag {
+                                    Expr::Str => {
+                                        // the characters are counted one per iteration
+                                        let cont: Cont::StrScan = cons4(operator, nil, zero, continuation);
+                                        return (result, env, cont, ret)
+                                    }
+                                };
+                                return(result, env, invalid_arg, errctrl)
+                            }
+                            Op1::StringToList => {
+                                match result.tag {
+                                    Expr::Str => {
+                                        let cont: Cont::StrScan = cons4(operator, nil, nil, continuation);
+                                        return (result, env, cont, ret)
+                                    }
+                                };
+                                return(result, env, invalid_arg, errctrl)
+                            }
+                            Op1::NumToString => {
+                                match result.tag {
+                                    Expr::Num => {
+                                        // like the printer, numbers below 2^64 are written in
+                                        // decimal and the others as the 64 hexadecimal digits
+                                        // of their field element
+                                        let low = truncate(result, 64);
+                                        let fits = eq_val(low, result);
+                                        if fits {
+                                            let cont: Cont::Digits = cons4(empty_str, nil, continuation, foo);
+                                            return (result, env, cont, ret)
+                                        }
+                                        let count = Num(64);
+                                        let cont: Cont::Digits = cons4(empty_str, count, continuation, foo);
+                                        return (result, env, cont, ret)
+                                    }
+                                };
+                                return(result, env, non_numeric, errctrl)
+                            }
+                            Op1::StringToNum => {
+                                match result.tag {
+                                    Expr::Str => {
+                                        let is_empty = eq_val(result, empty_str);
+                                        if is_empty {
+                                            return(result, env, invalid_arg, errctrl)
+                                        }
+                                        // like the reader, an optional minus sign and base prefix
+                                        // come before the digits, which are read one per iteration,
+                                        // and a `u64`, `i64` or `s64` suffix can follow them. Unlike it,
+                                        // fractions aren't read and the whole string has to be the
+                                        // number, without whitespace or anything after it
+                                        let (negative, unsigned) = num_sign(result);
+                                        let (radix, digits) = num_prefix(unsigned);
+                                        let state: Expr::Cons = cons2(radix, negative);
+                                        let cont: Cont::StrScan = cons4(operator, state, nil, continuation);
+                                        return (digits, env, cont, ret)
+                                    }
+                                };
+                                return(result, env, invalid_arg, errctrl)
+                            }
+                            Op1::U64ToString => {
+                                match result.tag {
+                                    Expr::U64 => {
+                                        // the digits go before the suffix, like the printer writes them
+                                        let suffix = String("u64");
+                                        let num = cast(result, Expr::Num);
+                                        let cont: Cont::Digits = cons4(suffix, nil, continuation, foo);
+                                        return (num, env, cont, ret)
+                                    }
+                                };
+                                return(result, env, non_numeric, errctrl)
+                            }
+                            Op1::CharToNum => {
+                                match result.tag {
+                                    Expr::Char => {
+                                        let cast = cast(result, Expr::Num);
+                                        return(cast, env, continuation, makethunk)
+                                    }
+                                };
+                                return(result, env, invalid_arg, errctrl)
+                            }
+                            Op1::SymbolToString => {
+                                match result.tag {
+                                    Expr::Sym | Expr::Key | Expr::Nil => {
+                                        // symbols are hashed paths, from the last limb to the
+                                        // root, which has a zero hash and no name
+                                        let is_root = eq_val(result, zero);
+                                        if is_root {
+                                            return(result, env, invalid_arg, errctrl)
+                                        }
+                                        let (name, _package) = decons2(result);
+                                        return(name, env, continuation, makethunk)
+                                    }
+                                };
+                                return(result, env, invalid_arg, errctrl)
+                            }
+                            Op1::SymbolPackage => {
+                                match result.tag {
+                                    Expr::Sym | Expr::Key | Expr::Nil => {
+                                        let is_root = eq_val(result, zero);
+                                        if is_root {
+                                            return(result, env, invalid_arg, errctrl)
+                                        }
+                                        let (_name, package) = decons2(result);
+                                        return(package, env, continuation, makethunk)
+                                    }
+                                };
+                                return(result, env, invalid_arg, errctrl)
+                            }
+                            Op1::IsKeyword => {
+                                match result.tag {
+                                    Expr::Key => {
+                                        return(t, env, continuation, makethunk)
+                                    }
+                                };
+                                return(nil, env, continuation, makethunk)
+                            }
+                            Op1::IsVector => {
+                                match result.tag {
+                                    Expr::Vector => {
+                                        return(t, env, continuation, makethunk)
+                                    }
+                                };
+                                return(nil, env, continuation, makethunk)
+                            }
+                            Op1::EnvToList => {
+                                match result.tag {
+                                    Expr::Env => {
+                                        let is_empty = eq_val(result, zero);
+                                        if is_empty {
+                                            return(nil, env, continuation, makethunk)
+                                        }
+                                        let cont: Cont::EnvScan = cons4(nil, continuation, foo, foo);
+                                        return(result, env, cont, ret)
+                                    }
+                                };
+                                return(result, env, invalid_arg, errctrl)
+                            }
+                            Op1::Inverse => {
+                                match result.tag {
+                                    Expr::Num => {
+                                        let is_zero = eq_val(result, zero);
+                                        if is_zero {
+                                            return(result, env, div_by_zero, errctrl)
+                                        }
+                                        let inv = inv(result);
+                                        return(inv, env, continuation, makethunk)
+                                    }
+                                };
+                                return(result, env, non_numeric, errctrl)
+                            }
+                        };
+                        return (result, env, invalid_arg, errctrl)
+                    }
+                    Cont::Binop => {
+                        let (operator, saved_env, unevaled_args, continuation) = decons4(cont);
+                        let (arg2, rest) = car_cdr_simple(unevaled_args);
+                        match operator.tag {
+                            Op2::Begin => {
+                                match rest.tag {
+                                    Expr::Nil => {
+                                        return (arg2, saved_env, continuation, ret)
+                                    }
+                                };
+                                let begin = Symbol("begin");
+                                let begin_again: Expr::Cons = cons2(begin, unevaled_args);
+                                return (begin_again, saved_env, continuation, ctrl)
+                            }
+                            Op2::Catch => {
+                                match rest.tag {
+                                    Expr::Nil => {
+                                        // `result` is the evaluated tag and the body is evaluated
+                                        // with the handler on top of the continuation
+                                        let cont: Cont::Catch = cons4(result, continuation, foo, foo);
+                                        return (arg2, saved_env, cont, ret)
+                                    }
+                                };
+                                return (result, env, arity_mismatch, errctrl)
+                            }
+                            Op2::StringToSymbol => {
+                                match unevaled_args.tag {
+                                    Expr::Nil => {
+                                        match result.tag {
+                                            Expr::Str => {
+                                                // without a package, the symbol is interned in
+                                                // the user package, like the ones read by the REPL
+                                                let user = Symbol("user");
+                                                let sym: Expr::Sym = cons2(result, user);
+                                                return (sym, env, continuation, makethunk)
+                                            }
+                                        };
+                                        return (result, env, invalid_arg, errctrl)
+                                    }
+                                };
+                                match rest.tag {
+                                    Expr::Nil => {
+                                        let cont: Cont::Binop2 = cons4(operator, result, continuation, foo);
+                                        return (arg2, saved_env, cont, ret)
+                                    }
+                                };
+                                return (result, env, arity_mismatch, errctrl)
+                            }
+                        };
+                        match rest.tag {
+                            Expr::Nil => {
+                                let cont: Cont::Binop2 = cons4(operator, result, continuation, foo);
+                                return (arg2, saved_env, cont, ret)
+                            }
+                        };
+                        return (result, env, arity_mismatch, errctrl)
+                    }
+                    Cont::Binop2 => {
+                        let size_u64 = Num(18446744073709551616);
+                        let (operator, evaled_arg, continuation, _foo) = decons4(cont);
+                        let (args_num_type, offending) = args_num_type(evaled_arg, result);
+                        // the arguments of the numeric operations, which are only used
+                        // once `args_num_type` tells that they're numbers
+                        let num1 = cast(evaled_arg, Expr::Num);
+                        let num2 = cast(result, Expr::Num);
+                        let (non_u64_arg) = non_u64_arg(evaled_arg, result);
+                        let args_num_type_eq_nil = eq_tag(args_num_type, nil);
+                        match operator.tag {
                             Op2::Eval => {
                                 match result.tag {
                                     Expr::Env => {
                                         return (evaled_arg, result, continuation, ret)
                                     }
                                 };
-                                return (result, env, err, errctrl)
+                                return (result, env, invalid_arg, errctrl)
                             }
                             Op2::Cons => {
                                 let val: Expr::Cons = cons2(evaled_arg, result);
@@ -1695,7 +3309,7 @@ fn apply_cont(cprocs: &[(&Symbol, usize)], ivc: bool) -> Func {
                                     let val: Expr::Str = cons2(evaled_arg, result);
                                     return (val, env, continuation, makethunk)
                                 }
-                                return (result, env, err, errctrl)
+                                return (result, env, invalid_arg, errctrl)
                             }
                             Op2::Hide => {
                                 match evaled_arg.tag {
@@ -1704,7 +3318,7 @@ fn apply_cont(cprocs: &[(&Symbol, usize)], ivc: bool) -> Func {
                                         return(hidden, env, continuation, makethunk)
                                     }
                                 };
-                                return (result, env, err, errctrl)
+                                return (evaled_arg, env, non_numeric, errctrl)
                             }
                             Op2::Equal => {
                                 let eq_tag = eq_tag(evaled_arg, result);
@@ -1718,14 +3332,14 @@ fn apply_cont(cprocs: &[(&Symbol, usize)], ivc: bool) -> Func {
                             Op2::Sum => {
                                 match args_num_type.tag {
                                     Expr::Nil => {
-                                        return (result, env, err, errctrl)
+                                        return (offending, env, non_numeric, errctrl)
                                     }
                                     Expr::Num => {
-                                        let val = add(evaled_arg, result);
+                                        let val = add(num1, num2);
                                         return (val, env, continuation, makethunk)
                                     }
                                     Expr::U64 => {
-                                        let val = add(evaled_arg, result);
+                                        let val = add(num1, num2);
                                         let not_overflow = lt(val, size_u64);
                                         if not_overflow {
                                             let val = cast(val, Expr::U64);
@@ -1735,22 +3349,74 @@ fn apply_cont(cprocs: &[(&Symbol, usize)], ivc: bool) -> Func {
                                         let val = cast(val, Expr::U64);
                                         return (val, env, continuation, makethunk)
                                     }
+                                    Expr::I64 => {
+                                        // Two's complement addition wraps around 2^64
+                                        let val = add(num1, num2);
+                                        let val = truncate(val, 64);
+                                        let val = cast(val, Expr::I64);
+                                        return (val, env, continuation, makethunk)
+                                    }
                                 }
                             }
+                            Op2::CheckedSum => {
+                                match args_num_type.tag {
+                                    Expr::U64 => {
+                                        let val = add(num1, num2);
+                                        let not_overflow = lt(val, size_u64);
+                                        if not_overflow {
+                                            let val = cast(val, Expr::U64);
+                                            return (val, env, continuation, makethunk)
+                                        }
+                                        return (result, env, overflow, errctrl)
+                                    }
+                                };
+                                return (non_u64_arg, env, non_numeric, errctrl)
+                            }
+                            Op2::CheckedDiff => {
+                                match args_num_type.tag {
+                                    Expr::U64 => {
+                                        let is_underflow = lt(num1, num2);
+                                        if is_underflow {
+                                            return (result, env, underflow, errctrl)
+                                        }
+                                        let val = sub(num1, num2);
+                                        let val = cast(val, Expr::U64);
+                                        return (val, env, continuation, makethunk)
+                                    }
+                                };
+                                return (non_u64_arg, env, non_numeric, errctrl)
+                            }
+                            Op2::CheckedProduct => {
+                                match args_num_type.tag {
+                                    Expr::U64 => {
+                                        // The product of two u64s fits in 128 bits, so it
+                                        // overflows iff truncating it changes it
+                                        let val = mul(num1, num2);
+                                        let trunc = truncate(val, 64);
+                                        let fits = eq_val(val, trunc);
+                                        if fits {
+                                            let val = cast(val, Expr::U64);
+                                            return (val, env, continuation, makethunk)
+                                        }
+                                        return (result, env, overflow, errctrl)
+                                    }
+                                };
+                                return (non_u64_arg, env, non_numeric, errctrl)
+                            }
                             Op2::Diff => {
                                 match args_num_type.tag {
                                     Expr::Nil => {
-                                        return (result, env, err, errctrl)
+                                        return (offending, env, non_numeric, errctrl)
                                     }
                                     Expr::Num => {
-                                        let val = sub(evaled_arg, result);
+                                        let val = sub(num1, num2);
                                         return (val, env, continuation, makethunk)
                                     }
                                     Expr::U64 => {
                                         // Subtraction in U64 is almost the same as subtraction
                                         // in the field. If the difference is negative, we need
                                         // to add 2^64 to get back to U64 domain.
-                                        let val = sub(evaled_arg, result);
+                                        let val = sub(num1, num2);
                                         let is_neg = lt(val, zero);
                                         let not_neg = not(is_neg);
                                         if not_neg {
@@ -1761,59 +3427,88 @@ fn apply_cont(cprocs: &[(&Symbol, usize)], ivc: bool) -> Func {
                                         let val = cast(val, Expr::U64);
                                         return (val, env, continuation, makethunk)
                                     }
+                                    Expr::I64 => {
+                                        // Adding 2^64 keeps the difference non-negative
+                                        // before wrapping around 2^64
+                                        let val = sub(num1, num2);
+                                        let val = add(val, size_u64);
+                                        let val = truncate(val, 64);
+                                        let val = cast(val, Expr::I64);
+                                        return (val, env, continuation, makethunk)
+                                    }
                                 }
                             }
                             Op2::Product => {
                                 match args_num_type.tag {
                                     Expr::Nil => {
-                                        return (result, env, err, errctrl)
+                                        return (offending, env, non_numeric, errctrl)
                                     }
                                     Expr::Num => {
-                                        let val = mul(evaled_arg, result);
+                                        let val = mul(num1, num2);
                                         return (val, env, continuation, makethunk)
                                     }
                                     Expr::U64 => {
-                                        let val = mul(evaled_arg, result);
+                                        let val = mul(num1, num2);
                                         // The limit is 2**64 - 1
                                         let trunc = truncate(val, 64);
                                         let cast = cast(trunc, Expr::U64);
                                         return (cast, env, continuation, makethunk)
                                     }
+                                    Expr::I64 => {
+                                        let val = mul(num1, num2);
+                                        let trunc = truncate(val, 64);
+                                        let cast = cast(trunc, Expr::I64);
+                                        return (cast, env, continuation, makethunk)
+                                    }
                                 }
                             }
                             Op2::Quotient => {
+                                if args_num_type_eq_nil {
+                                    return (offending, env, non_numeric, errctrl)
+                                }
                                 let is_z = eq_val(result, zero);
-                                let acc_err = or(is_z, args_num_type_eq_nil);
-                                if acc_err {
-                                    return (result, env, err, errctrl)
+                                if is_z {
+                                    return (result, env, div_by_zero, errctrl)
                                 }
                                 match args_num_type.tag {
                                     Expr::Num => {
-                                        let val = div(evaled_arg, result);
+                                        let val = div(num1, num2);
                                         return (val, env, continuation, makethunk)
                                     }
-                                    Expr::U64 => {
-                                        let (div, _rem) = div_rem64(evaled_arg, result);
-                                        let div = cast(div, Expr::U64);
+                                };
+                                let (div, _rem) = div_rem_int(num1, num2, args_num_type);
+                                match args_num_type.tag {
+                                    Expr::I64 => {
+                                        let div = cast(div, Expr::I64);
                                         return (div, env, continuation, makethunk)
                                     }
-                                }
+                                };
+                                let div = cast(div, Expr::U64);
+                                return (div, env, continuation, makethunk)
                             }
                             Op2::Modulo => {
+                                match args_num_type.tag {
+                                    Expr::Nil | Expr::Num => {
+                                        return (non_u64_arg, env, non_numeric, errctrl)
+                                    }
+                                };
                                 let is_z = eq_val(result, zero);
-                                let is_not_z = not(is_z);
-                                let args_num_type_is_num = eq_tag(args_num_type, u64);
-                                let acc_ok = and(is_not_z, args_num_type_is_num);
-                                if acc_ok {
-                                    let (_div, rem) = div_rem64(evaled_arg, result);
-                                    let rem = cast(rem, Expr::U64);
-                                    return (rem, env, continuation, makethunk)
+                                if is_z {
+                                    return (result, env, div_by_zero, errctrl)
                                 }
-                                return (result, env, err, errctrl)
+                                let (_div, rem) = div_rem_int(num1, num2, args_num_type);
+                                match args_num_type.tag {
+                                    Expr::I64 => {
+                                        let rem = cast(rem, Expr::I64);
+                                        return (rem, env, continuation, makethunk)
+                                    }
+                                };
+                                let rem = cast(rem, Expr::U64);
+                                return (rem, env, continuation, makethunk)
                             }
                             Op2::NumEqual => {
                                 if args_num_type_eq_nil {
-                                    return (result, env, err, errctrl)
+                                    return (offending, env, non_numeric, errctrl)
                                 }
                                 let eq = eq_val(evaled_arg, result);
                                 if eq {
@@ -1823,49 +3518,376 @@ fn apply_cont(cprocs: &[(&Symbol, usize)], ivc: bool) -> Func {
                             }
                             Op2::Less => {
                                 if args_num_type_eq_nil {
-                                    return (result, env, err, errctrl)
+                                    return (offending, env, non_numeric, errctrl)
                                 }
-                                let val = lt(evaled_arg, result);
-                                if val {
-                                    return (t, env, continuation, makethunk)
-                                }
-                                return (nil, env, continuation, makethunk)
+                                let (less) = less_than(num1, num2, args_num_type);
+                                return (less, env, continuation, makethunk)
                             }
                             Op2::Greater => {
                                 if args_num_type_eq_nil {
-                                    return (result, env, err, errctrl)
-                                }
-                                let val = lt(result, evaled_arg);
-                                if val {
-                                    return (t, env, continuation, makethunk)
+                                    return (offending, env, non_numeric, errctrl)
                                 }
-                                return (nil, env, continuation, makethunk)
+                                let (less) = less_than(num2, num1, args_num_type);
+                                return (less, env, continuation, makethunk)
                             }
                             Op2::LessEqual => {
                                 if args_num_type_eq_nil {
-                                    return (result, env, err, errctrl)
-                                }
-                                let val = lt(result, evaled_arg);
-                                if val {
-                                    return (nil, env, continuation, makethunk)
+                                    return (offending, env, non_numeric, errctrl)
                                 }
-                                return (t, env, continuation, makethunk)
+                                let (less) = less_than(num2, num1, args_num_type);
+                                match less.tag {
+                                    Expr::Nil => {
+                                        return (t, env, continuation, makethunk)
+                                    }
+                                };
+                                return (nil, env, continuation, makethunk)
                             }
                             Op2::GreaterEqual => {
                                 if args_num_type_eq_nil {
-                                    return (result, env, err, errctrl)
+                                    return (offending, env, non_numeric, errctrl)
                                 }
-                                let val = lt(evaled_arg, result);
-                                if val {
-                                    return (nil, env, continuation, makethunk)
+                                let (less) = less_than(num1, num2, args_num_type);
+                                match less.tag {
+                                    Expr::Nil => {
+                                        return (t, env, continuation, makethunk)
+                                    }
+                                };
+                                return (nil, env, continuation, makethunk)
+                            }
+                            Op2::LogAnd => {
+                                match args_num_type.tag {
+                                    Expr::U64 => {
+                                        let val = bit_and(num1, num2);
+                                        let val = cast(val, Expr::U64);
+                                        return (val, env, continuation, makethunk)
+                                    }
+                                };
+                                return (non_u64_arg, env, non_numeric, errctrl)
+                            }
+                            Op2::LogOr => {
+                                match args_num_type.tag {
+                                    Expr::U64 => {
+                                        let val = bit_or(num1, num2);
+                                        let val = cast(val, Expr::U64);
+                                        return (val, env, continuation, makethunk)
+                                    }
+                                };
+                                return (non_u64_arg, env, non_numeric, errctrl)
+                            }
+                            Op2::LogXor => {
+                                match args_num_type.tag {
+                                    Expr::U64 => {
+                                        let val = bit_xor(num1, num2);
+                                        let val = cast(val, Expr::U64);
+                                        return (val, env, continuation, makethunk)
+                                    }
+                                };
+                                return (non_u64_arg, env, non_numeric, errctrl)
+                            }
+                            Op2::Shl => {
+                                match args_num_type.tag {
+                                    Expr::U64 => {
+                                        let val = shl(num1, num2);
+                                        let val = cast(val, Expr::U64);
+                                        return (val, env, continuation, makethunk)
+                                    }
+                                };
+                                return (non_u64_arg, env, non_numeric, errctrl)
+                            }
+                            Op2::Shr => {
+                                match args_num_type.tag {
+                                    Expr::U64 => {
+                                        let val = shr(num1, num2);
+                                        let val = cast(val, Expr::U64);
+                                        return (val, env, continuation, makethunk)
+                                    }
+                                };
+                                return (non_u64_arg, env, non_numeric, errctrl)
+                            }
+                            Op2::VectorRef => {
+                                match evaled_arg.tag {
+                                    Expr::Vector => {
+                                        match result.tag {
+                                            Expr::Num | Expr::U64 => {
+                                                let (len, cap, root, _foo) = decons4(evaled_arg);
+                                                let len = cast(len, Expr::Num);
+                                                let idx = cast(result, Expr::Num);
+                                                let idx_low = truncate(idx, 64);
+                                                let is_u64 = eq_val(idx_low, idx);
+                                                if !is_u64 {
+                                                    return (result, env, out_of_bounds, errctrl)
+                                                }
+                                                // `len - idx - 1` only fits in 64 bits if `idx` is
+                                                // below `len`
+                                                let one = Num(1);
+                                                let gap = sub(len, idx);
+                                                let gap = sub(gap, one);
+                                                let gap_low = truncate(gap, 64);
+                                                let in_bounds = eq_val(gap_low, gap);
+                                                if !in_bounds {
+                                                    return (result, env, out_of_bounds, errctrl)
+                                                }
+                                                let cap_is_one = eq_val(cap, one);
+                                                if cap_is_one {
+                                                    return (root, env, continuation, makethunk)
+                                                }
+                                                // walk down the tree, one level per iteration
+                                                let (offset) = vector_offset(idx, cap);
+                                                let cont: Cont::VectorRef = cons4(root, cap, continuation, foo);
+                                                return (offset, env, cont, ret)
+                                            }
+                                        };
+                                        return (result, env, non_numeric, errctrl)
+                                    }
+                                };
+                                return (result, env, invalid_arg, errctrl)
+                            }
+                            Op2::Throw => {
+                                // `evaled_arg` is the tag and `result` is the thrown value.
+                                // Continuations are unwound one per iteration until reaching
+                                // a `Catch` whose tag equals the thrown one
+                                let (parent) = cont_parent(continuation);
+                                let parent_is_nil = eq_tag(parent, nil);
+                                if parent_is_nil {
+                                    return (evaled_arg, env, uncaught, errctrl)
                                 }
-                                return (t, env, continuation, makethunk)
+                                match continuation.tag {
+                                    Cont::Catch => {
+                                        let (catch_tag, _parent, _foo, _foo) = decons4(continuation);
+                                        let eq_tag = eq_tag(evaled_arg, catch_tag);
+                                        let eq_val = eq_val(evaled_arg, catch_tag);
+                                        let eq = and(eq_tag, eq_val);
+                                        if eq {
+                                            return (result, env, parent, makethunk)
+                                        }
+                                        let cont: Cont::Binop2 = cons4(operator, evaled_arg, parent, foo);
+                                        return (result, env, cont, makethunk)
+                                    }
+                                };
+                                let cont: Cont::Binop2 = cons4(operator, evaled_arg, parent, foo);
+                                return (result, env, cont, makethunk)
+                            }
+                            Op2::Apply => {
+                                // `evaled_arg` is the function and `result` is the list of
+                                // arguments, which are passed as in a regular call but
+                                // without being evaluated again, see `next_arg`
+                                match result.tag {
+                                    Expr::Nil | Expr::Cons => {
+                                        let cont: Cont::Call = cons4(result, nil, continuation, foo);
+                                        return (evaled_arg, env, cont, makethunk)
+                                    }
+                                };
+                                return (result, env, invalid_arg, errctrl)
+                            }
+                            Op2::EnvLookup => {
+                                match evaled_arg.tag {
+                                    Expr::Sym => {
+                                        match result.tag {
+                                            Expr::Env => {
+                                                // the symbol is evaluated in `result`
+                                                return (evaled_arg, result, continuation, ret)
+                                            }
+                                        };
+                                        return (result, env, invalid_arg, errctrl)
+                                    }
+                                };
+                                return (evaled_arg, env, invalid_arg, errctrl)
+                            }
+                            Op2::Expt => {
+                                match evaled_arg.tag {
+                                    Expr::Num => {
+                                        match result.tag {
+                                            Expr::Num | Expr::U64 => {
+                                                // the exponent must fit in 64 bits, whose
+                                                // decomposition `pow` takes from its own slot
+                                                let exp = cast(result, Expr::Num);
+                                                let trunc = truncate(exp, 64);
+                                                let fits = eq_val(exp, trunc);
+                                                if !fits {
+                                                    return (result, env, invalid_arg, errctrl)
+                                                }
+                                                let pow = pow(evaled_arg, exp);
+                                                return (pow, env, continuation, makethunk)
+                                            }
+                                        };
+                                        return (result, env, non_numeric, errctrl)
+                                    }
+                                };
+                                return (evaled_arg, env, non_numeric, errctrl)
+                            }
+                            Op2::StringToSymbol => {
+                                match evaled_arg.tag {
+                                    Expr::Str => {
+                                        match result.tag {
+                                            Expr::Sym => {
+                                                // tagged like `Store::intern_symbol` does: `nil` of
+                                                // the lurk package is `Expr::Nil` and the names of
+                                                // the root package are keywords
+                                                let sym: Expr::Sym = cons2(evaled_arg, result);
+                                                let is_nil = eq_val(sym, nil);
+                                                if is_nil {
+                                                    return (nil, env, continuation, makethunk)
+                                                }
+                                                let is_root = eq_val(result, zero);
+                                                if is_root {
+                                                    let key = cast(sym, Expr::Key);
+                                                    return (key, env, continuation, makethunk)
+                                                }
+                                                return (sym, env, continuation, makethunk)
+                                            }
+                                        };
+                                        return (result, env, invalid_arg, errctrl)
+                                    }
+                                };
+                                return (evaled_arg, env, invalid_arg, errctrl)
+                            }
+                            Op2::StringAppend => {
+                                let evaled_arg_is_str = eq_tag(evaled_arg, empty_str);
+                                let result_is_str = eq_tag(result, empty_str);
+                                let both_str = and(evaled_arg_is_str, result_is_str);
+                                if !both_str {
+                                    return (result, env, invalid_arg, errctrl)
+                                }
+                                // the characters of the first string are collected in
+                                // reverse and then pushed onto the second one
+                                let cont: Cont::StrScan = cons4(operator, result, nil, continuation);
+                                return (evaled_arg, env, cont, ret)
+                            }
+                            Op2::StringLess => {
+                                let evaled_arg_is_str = eq_tag(evaled_arg, empty_str);
+                                let result_is_str = eq_tag(result, empty_str);
+                                let both_str = and(evaled_arg_is_str, result_is_str);
+                                if !both_str {
+                                    return (result, env, invalid_arg, errctrl)
+                                }
+                                // both strings are walked together until they differ
+                                let cont: Cont::StrScan = cons4(operator, result, nil, continuation);
+                                return (evaled_arg, env, cont, ret)
                             }
                         };
-                        return (result, env, err, errctrl)
+                        return (result, env, invalid_arg, errctrl)
+                    }
+                    Cont::Ternop => {
+                        // `result` is the first argument. The `Ternop` continuation
+                        // is kept for its operator and continuation
+                        let (_operator, saved_env, unevaled_args, _continuation) = decons4(cont);
+                        let (arg2, more) = decons2(unevaled_args);
+                        let (arg3, _end) = decons2(more);
+                        let cont: Cont::Ternop2 = cons4(result, arg3, saved_env, cont);
+                        return (arg2, saved_env, cont, ret)
+                    }
+                    Cont::Ternop2 => {
+                        let (arg1, arg3, saved_env, ternop) = decons4(cont);
+                        let cont: Cont::Ternop3 = cons4(arg1, result, ternop, foo);
+                        return (arg3, saved_env, cont, ret)
+                    }
+                    Cont::Ternop3 => {
+                        // `result` is the third argument
+                        let (arg1, arg2, ternop, _foo) = decons4(cont);
+                        let (operator, _saved_env, _unevaled_args, continuation) = decons4(ternop);
+                        match operator.tag {
+                            Op3::VectorSet => {
+                                match arg1.tag {
+                                    Expr::Vector => {
+                                        match arg2.tag {
+                                            Expr::Num | Expr::U64 => {
+                                                let (len, cap, _root, _foo) = decons4(arg1);
+                                                let len = cast(len, Expr::Num);
+                                                let idx = cast(arg2, Expr::Num);
+                                                let idx_low = truncate(idx, 64);
+                                                let is_u64 = eq_val(idx_low, idx);
+                                                if !is_u64 {
+                                                    return (arg2, env, out_of_bounds, errctrl)
+                                                }
+                                                // `len - idx - 1` only fits in 64 bits if `idx` is
+                                                // below `len`
+                                                let one = Num(1);
+                                                let gap = sub(len, idx);
+                                                let gap = sub(gap, one);
+                                                let gap_low = truncate(gap, 64);
+                                                let in_bounds = eq_val(gap_low, gap);
+                                                if !in_bounds {
+                                                    return (arg2, env, out_of_bounds, errctrl)
+                                                }
+                                                let cap_is_one = eq_val(cap, one);
+                                                if cap_is_one {
+                                                    let vec: Expr::Vector = cons4(len, cap, result, foo);
+                                                    return (vec, env, continuation, makethunk)
+                                                }
+                                                // walk down the tree, one level per iteration, and
+                                                // then rebuild the path back up. The walk starts at
+                                                // the header, as a node 4 times the capacity of the
+                                                // root, which sits at position 2
+                                                let four = Num(4);
+                                                let cap = cast(cap, Expr::Num);
+                                                let cap = mul(cap, four);
+                                                let (offset) = vector_offset(idx, cap);
+                                                let root_pos = Num(9223372036854775808);
+                                                let offset = add(offset, root_pos);
+                                                let cont: Cont::VectorSet = cons4(arg1, cap, result, continuation);
+                                                return (offset, env, cont, ret)
+                                            }
+                                        };
+                                        return (arg2, env, non_numeric, errctrl)
+                                    }
+                                };
+                                return (arg1, env, invalid_arg, errctrl)
+                            }
+                            Op3::Substring => {
+                                match arg1.tag {
+                                    Expr::Str => {
+                                        match arg2.tag {
+                                            Expr::Num | Expr::U64 => {
+                                                match result.tag {
+                                                    Expr::Num | Expr::U64 => {
+                                                        let start = cast(arg2, Expr::Num);
+                                                        let end = cast(result, Expr::Num);
+                                                        let start_low = truncate(start, 64);
+                                                        let is_u64 = eq_val(start_low, start);
+                                                        if !is_u64 {
+                                                            return (arg2, env, out_of_bounds, errctrl)
+                                                        }
+                                                        // `end - start` only fits in 64 bits if `end`
+                                                        // isn't below `start`
+                                                        let count = sub(end, start);
+                                                        let count_low = truncate(count, 64);
+                                                        let is_ordered = eq_val(count_low, count);
+                                                        if !is_ordered {
+                                                            return (result, env, out_of_bounds, errctrl)
+                                                        }
+                                                        // `start` characters are skipped and the next
+                                                        // `count` are collected in reverse
+                                                        let bounds: Expr::Cons = cons2(start, count);
+                                                        let cont: Cont::StrScan = cons4(operator, bounds, nil, continuation);
+                                                        return (arg1, env, cont, ret)
+                                                    }
+                                                };
+                                                return (result, env, non_numeric, errctrl)
+                                            }
+                                        };
+                                        return (arg2, env, non_numeric, errctrl)
+                                    }
+                                };
+                                return (arg1, env, invalid_arg, errctrl)
+                            }
+                            Op3::EnvExtend => {
+                                match arg1.tag {
+                                    Expr::Sym => {
+                                        match result.tag {
+                                            Expr::Env => {
+                                                let extended = push_binding(arg1, arg2, result);
+                                                return (extended, env, continuation, makethunk)
+                                            }
+                                        };
+                                        return (result, env, invalid_arg, errctrl)
+                                    }
+                                };
+                                return (arg1, env, invalid_arg, errctrl)
+                            }
+                        }
                     }
                     Cont::If => {
-                        let (unevaled_args, args_env, continuation, _foo) = decons4(cont);
+                        let (unevaled_args, args_env, continuation, if_expr) = decons4(cont);
                         let (arg1, more) = car_cdr_simple(unevaled_args);
                         let (arg2, end) = car_cdr_simple(more);
                         match end.tag {
@@ -1878,7 +3900,601 @@ fn apply_cont(cprocs: &[(&Symbol, usize)], ivc: bool) -> Func {
                                 return (arg1, args_env, continuation, ret)
                             }
                         };
-                        return (arg1, env, err, errctrl)
+                        return (if_expr, env, malformed, errctrl)
+                    }
+                    Cont::Cond => {
+                        let (body, clauses, saved_env, continuation) = decons4(cont);
+                        match result.tag {
+                            Expr::Nil => {
+                                // try the next clause, if any
+                                match clauses.tag {
+                                    Expr::Nil => {
+                                        return (result, env, continuation, makethunk)
+                                    }
+                                    Expr::Cons => {
+                                        let (clause, clauses) = decons2(clauses);
+                                        match clause.tag {
+                                            Expr::Cons => {
+                                                let (condition, body) = decons2(clause);
+                                                let cont: Cont::Cond = cons4(body, clauses, saved_env, continuation);
+                                                return (condition, saved_env, cont, ret)
+                                            }
+                                        };
+                                        return (clause, env, malformed, errctrl)
+                                    }
+                                };
+                                return (clauses, env, malformed, errctrl)
+                            }
+                        };
+                        match body.tag {
+                            Expr::Nil => {
+                                // a clause without body returns the value of its condition
+                                return (result, env, continuation, makethunk)
+                            }
+                            Expr::Cons => {
+                                let (body) = begin_body(body);
+                                return (body, saved_env, continuation, ret)
+                            }
+                        };
+                        return (body, env, malformed, errctrl)
+                    }
+                    Cont::And => {
+                        let (unevaled_args, saved_env, continuation, _foo) = decons4(cont);
+                        match result.tag {
+                            Expr::Nil => {
+                                return (result, env, continuation, makethunk)
+                            }
+                        };
+                        let (arg, more) = decons2(unevaled_args);
+                        match more.tag {
+                            Expr::Nil => {
+                                return (arg, saved_env, continuation, ret)
+                            }
+                            Expr::Cons => {
+                                let cont: Cont::And = cons4(more, saved_env, continuation, foo);
+                                return (arg, saved_env, cont, ret)
+                            }
+                        };
+                        return (more, env, malformed, errctrl)
+                    }
+                    Cont::Or => {
+                        let (unevaled_args, saved_env, continuation, _foo) = decons4(cont);
+                        let result_is_nil = eq_tag(result, nil);
+                        if !result_is_nil {
+                            return (result, env, continuation, makethunk)
+                        }
+                        let (arg, more) = decons2(unevaled_args);
+                        match more.tag {
+                            Expr::Nil => {
+                                return (arg, saved_env, continuation, ret)
+                            }
+                            Expr::Cons => {
+                                let cont: Cont::Or = cons4(more, saved_env, continuation, foo);
+                                return (arg, saved_env, cont, ret)
+                            }
+                        };
+                        return (more, env, malformed, errctrl)
+                    }
+                    Cont::StrScan => {
+                        // `result` is what's left of the string being scanned, one
+                        // character per iteration
+                        let (operator, state, acc, continuation) = decons4(cont);
+                        let is_empty = eq_val(result, empty_str);
+                        let one = Num(1);
+                        match operator.tag {
+                            Op1::StringLength => {
+                                if is_empty {
+                                    return (acc, env, continuation, makethunk)
+                                }
+                                let (_c, rest) = decons2(result);
+                                let acc = cast(acc, Expr::Num);
+                                let acc = add(acc, one);
+                                let cont: Cont::StrScan = cons4(operator, state, acc, continuation);
+                                return (rest, env, cont, ret)
+                            }
+                            Op1::StringToNum => {
+                                // `state` holds the base and whether the number is negative,
+                                // and `acc` is the value of the digits read so far, or `nil`
+                                // before the first one
+                                let (radix, negative) = decons2(state);
+                                let radix = cast(radix, Expr::Num);
+                                let acc_is_nil = eq_tag(acc, nil);
+                                let num = cast(acc, Expr::Num);
+                                if is_empty {
+                                    if acc_is_nil {
+                                        return (result, env, invalid_arg, errctrl)
+                                    }
+                                    match negative.tag {
+                                        Expr::Nil => {
+                                            return (num, env, continuation, makethunk)
+                                        }
+                                    };
+                                    let neg = sub(zero, num);
+                                    return (neg, env, continuation, makethunk)
+                                }
+                                let (c, rest) = decons2(result);
+                                let c = cast(c, Expr::Num);
+                                let (digit) = char_digit(c);
+                                match digit.tag {
+                                    Expr::Num => {
+                                        // the digit is below the base when `radix - 1 - digit`,
+                                        // which lies between -14 and 15, fits in four bits
+                                        let gap = sub(radix, digit);
+                                        let gap = sub(gap, one);
+                                        let gap_low = truncate(gap, 4);
+                                        let is_digit = eq_val(gap_low, gap);
+                                        if !is_digit {
+                                            return (result, env, invalid_arg, errctrl)
+                                        }
+                                        if acc_is_nil {
+                                            let acc = cast(digit, Expr::U64);
+                                            let cont: Cont::StrScan = cons4(operator, state, acc, continuation);
+                                            return (rest, env, cont, ret)
+                                        }
+                                        // like in the reader, numbers wrap around the field's
+                                        // modulus, but they can't be 64-bit integers then. The
+                                        // value is tagged U64 while it fits in 64 bits, which
+                                        // it stops doing before it can wrap around since the
+                                        // base is at most 16, and Num afterwards
+                                        let next = mul(num, radix);
+                                        let next = add(next, digit);
+                                        match acc.tag {
+                                            Expr::U64 => {
+                                                let next_low = truncate(next, 64);
+                                                let fits = eq_val(next_low, next);
+                                                if fits {
+                                                    let next = cast(next, Expr::U64);
+                                                    let cont: Cont::StrScan = cons4(operator, state, next, continuation);
+                                                    return (rest, env, cont, ret)
+                                                }
+                                                let cont: Cont::StrScan = cons4(operator, state, next, continuation);
+                                                return (rest, env, cont, ret)
+                                            }
+                                        };
+                                        let cont: Cont::StrScan = cons4(operator, state, next, continuation);
+                                        return (rest, env, cont, ret)
+                                    }
+                                };
+                                // underscores and suffixes can only follow a digit
+                                if acc_is_nil {
+                                    return (result, env, invalid_arg, errctrl)
+                                }
+                                let underscore = Num(95);
+                                let is_underscore = eq_val(c, underscore);
+                                if is_underscore {
+                                    let cont: Cont::StrScan = cons4(operator, state, acc, continuation);
+                                    return (rest, env, cont, ret)
+                                }
+                                let bits = String("64");
+                                let is_64 = eq_val(rest, bits);
+                                let fits = eq_tag(acc, u64);
+                                let fits_64 = and(is_64, fits);
+                                if !fits_64 {
+                                    return (result, env, invalid_arg, errctrl)
+                                }
+                                let u = Num(117);
+                                let is_u = eq_val(c, u);
+                                if is_u {
+                                    match negative.tag {
+                                        Expr::Nil => {
+                                            return (acc, env, continuation, makethunk)
+                                        }
+                                    };
+                                    return (result, env, invalid_arg, errctrl)
+                                }
+                                let i = Num(105);
+                                let is_i = eq_val(c, i);
+                                let s = Num(115);
+                                let is_s = eq_val(c, s);
+                                let is_signed = or(is_i, is_s);
+                                if is_signed {
+                                    // `num` is the magnitude, which reaches 2^63 for the
+                                    // smallest I64. Like in the reader, an `i64` suffix gives
+                                    // the two's complement as a U64 and `s64` gives an I64
+                                    let low = truncate(num, 63);
+                                    let fits = eq_val(low, num);
+                                    match negative.tag {
+                                        Expr::Nil => {
+                                            if fits {
+                                                if is_i {
+                                                    return (acc, env, continuation, makethunk)
+                                                }
+                                                let acc = cast(acc, Expr::I64);
+                                                return (acc, env, continuation, makethunk)
+                                            }
+                                            return (result, env, invalid_arg, errctrl)
+                                        }
+                                    };
+                                    let size_i64 = Num(9223372036854775808);
+                                    let is_min = eq_val(num, size_i64);
+                                    let fits = or(fits, is_min);
+                                    if fits {
+                                        let (neg) = neg_i64(num);
+                                        if is_i {
+                                            let neg = cast(neg, Expr::U64);
+                                            return (neg, env, continuation, makethunk)
+                                        }
+                                        let neg = cast(neg, Expr::I64);
+                                        return (neg, env, continuation, makethunk)
+                                    }
+                                    return (result, env, invalid_arg, errctrl)
+                                }
+                                return (result, env, invalid_arg, errctrl)
+                            }
+                            Op1::StringToList => {
+                                if is_empty {
+                                    let (list, cont) = str_build_step(acc, nil, continuation);
+                                    return (list, env, cont, makethunk)
+                                }
+                                let (c, rest) = decons2(result);
+                                let acc: Expr::Cons = cons2(c, acc);
+                                let cont: Cont::StrScan = cons4(operator, state, acc, continuation);
+                                return (rest, env, cont, ret)
+                            }
+                        };
+                        match operator.tag {
+                            Op2::StringAppend => {
+                                // `state` is the string to append to
+                                if is_empty {
+                                    let (string, cont) = str_build_step(acc, state, continuation);
+                                    return (string, env, cont, makethunk)
+                                }
+                                let (c, rest) = decons2(result);
+                                let acc: Expr::Cons = cons2(c, acc);
+                                let cont: Cont::StrScan = cons4(operator, state, acc, continuation);
+                                return (rest, env, cont, ret)
+                            }
+                            Op2::StringLess => {
+                                // `state` is what's left of the second string
+                                let state_is_empty = eq_val(state, empty_str);
+                                if state_is_empty {
+                                    return (nil, env, continuation, makethunk)
+                                }
+                                if is_empty {
+                                    return (t, env, continuation, makethunk)
+                                }
+                                let (c, rest) = decons2(result);
+                                let (other_c, other_rest) = decons2(state);
+                                let c = cast(c, Expr::Num);
+                                let other_c = cast(other_c, Expr::Num);
+                                let is_eq = eq_val(c, other_c);
+                                if !is_eq {
+                                    let is_less = lt(c, other_c);
+                                    if is_less {
+                                        return (t, env, continuation, makethunk)
+                                    }
+                                    return (nil, env, continuation, makethunk)
+                                }
+                                let cont: Cont::StrScan = cons4(operator, other_rest, acc, continuation);
+                                return (rest, env, cont, ret)
+                            }
+                        };
+                        match operator.tag {
+                            Op3::Substring => {
+                                // `state` holds the number of characters left to skip
+                                // and to collect
+                                let (skip, count) = decons2(state);
+                                let skip = cast(skip, Expr::Num);
+                                let count = cast(count, Expr::Num);
+                                let skip_is_zero = eq_val(skip, zero);
+                                let count_is_zero = eq_val(count, zero);
+                                let done = and(skip_is_zero, count_is_zero);
+                                if done {
+                                    let (string, cont) = str_build_step(acc, empty_str, continuation);
+                                    return (string, env, cont, makethunk)
+                                }
+                                if is_empty {
+                                    return (state, env, out_of_bounds, errctrl)
+                                }
+                                let (c, rest) = decons2(result);
+                                if skip_is_zero {
+                                    let count = sub(count, one);
+                                    let acc: Expr::Cons = cons2(c, acc);
+                                    let state: Expr::Cons = cons2(skip, count);
+                                    let cont: Cont::StrScan = cons4(operator, state, acc, continuation);
+                                    return (rest, env, cont, ret)
+                                }
+                                let skip = sub(skip, one);
+                                let state: Expr::Cons = cons2(skip, count);
+                                let cont: Cont::StrScan = cons4(operator, state, acc, continuation);
+                                return (rest, env, cont, ret)
+                            }
+                        };
+                        return (result, env, invalid_arg, errctrl)
+                    }
+                    Cont::Digits => {
+                        // `result` is what's left of the number being printed, whose
+                        // digits are pushed onto `string` from the least significant one.
+                        // `count` is the number of hexadecimal digits left to push, or
+                        // `nil` for decimal ones
+                        let (string, count, continuation, _foo) = decons4(cont);
+                        let num = cast(result, Expr::Num);
+                        match count.tag {
+                            Expr::Num => {
+                                let sixteen = Num(16);
+                                let one = Num(1);
+                                let digit = truncate(num, 4);
+                                let rest = sub(num, digit);
+                                let rest = div(rest, sixteen);
+                                let (c) = hex_char(digit);
+                                let string: Expr::Str = cons2(c, string);
+                                let count = sub(count, one);
+                                let count_is_zero = eq_val(count, zero);
+                                if !count_is_zero {
+                                    let cont: Cont::Digits = cons4(string, count, continuation, foo);
+                                    return (rest, env, cont, ret)
+                                }
+                                let zero_char = Num(48);
+                                let zero_char = cast(zero_char, Expr::Char);
+                                let x = Num(120);
+                                let x = cast(x, Expr::Char);
+                                let string: Expr::Str = cons2(x, string);
+                                let string: Expr::Str = cons2(zero_char, string);
+                                return (string, env, continuation, makethunk)
+                            }
+                        };
+                        let ten = Num(10);
+                        let zero_char = Num(48);
+                        let (rest, digit) = div_rem64(num, ten);
+                        let c = add(digit, zero_char);
+                        let c = cast(c, Expr::Char);
+                        let string: Expr::Str = cons2(c, string);
+                        let rest_is_zero = eq_val(rest, zero);
+                        if !rest_is_zero {
+                            let cont: Cont::Digits = cons4(string, count, continuation, foo);
+                            return (rest, env, cont, ret)
+                        }
+                        return (string, env, continuation, makethunk)
+                    }
+                    Cont::EnvScan => {
+                        // `result` is what's left of the environment being listed,
+                        // whose bindings are collected in reverse, one per iteration
+                        let (collected, continuation, _foo, _foo) = decons4(cont);
+                        let (var, val, rest_env) = pop_binding(result);
+                        let is_empty = eq_val(rest_env, zero);
+                        // the value of the top binding of a `letrec` group is
+                        // unwrapped and scanned again, see `lookup`
+                        match val.tag {
+                            Expr::RecTop => {
+                                let (wrapped, _body, _closed_env, member) = decons4(val);
+                                let cont: Cont::EnvScan = cons4(collected, continuation, foo, foo);
+                                match member.tag {
+                                    Expr::Nil | Expr::Sym => {
+                                        let rec = cast(val, Expr::Rec);
+                                        let result = push_binding(var, rec, rest_env);
+                                        return (result, env, cont, ret)
+                                    }
+                                };
+                                let result = push_binding(var, wrapped, rest_env);
+                                return (result, env, cont, ret)
+                            }
+                        };
+                        let binding: Expr::Cons = cons2(var, val);
+                        let collected: Expr::Cons = cons2(binding, collected);
+                        if is_empty {
+                            // the bindings are put back in order by `StrBuild` in the
+                            // next iteration
+                            let cont: Cont::StrBuild = cons4(collected, continuation, foo, foo);
+                            return (nil, env, cont, makethunk)
+                        }
+                        let cont: Cont::EnvScan = cons4(collected, continuation, foo, foo);
+                        return (rest_env, env, cont, ret)
+                    }
+                    Cont::StrBuild => {
+                        // `result` is the string or list built so far, onto which the
+                        // collected characters are pushed back in order
+                        let (reversed, continuation, _foo, _foo) = decons4(cont);
+                        let (result, cont) = str_build_step(reversed, result, continuation);
+                        return (result, env, cont, makethunk)
+                    }
+                    Cont::Quasiquote => {
+                        // `result` is a template at the given `level`, which nested
+                        // quasiquotes raise and unquotes lower. Only the forms
+                        // unquoted at level 1 are evaluated
+                        let (level, saved_env, continuation, _foo) = decons4(cont);
+                        match result.tag {
+                            Expr::Cons => {
+                                let (head, tail) = decons2(result);
+                                let level = cast(level, Expr::Num);
+                                let one = Num(1);
+                                let at_top = eq_val(level, one);
+                                let (is_splice, spliced) = unquote_splicing_args(head);
+                                let is_splice = eq_tag(is_splice, t);
+                                let splices = and(is_splice, at_top);
+                                if splices {
+                                    let (form, end) = car_cdr_simple(spliced);
+                                    match end.tag {
+                                        Expr::Nil => {
+                                            // the elements of the spliced list are consed
+                                            // onto the expansion of `tail`
+                                            let cont: Cont::Splice = cons4(tail, saved_env, continuation, level);
+                                            return (form, saved_env, cont, ret)
+                                        }
+                                    };
+                                    return (head, env, malformed, errctrl)
+                                }
+                                let unquote = Symbol("unquote");
+                                let head_is_sym = eq_tag(head, unquote);
+                                let head_is_unquote = eq_val(head, unquote);
+                                let is_unquote = and(head_is_sym, head_is_unquote);
+                                let evaluates = and(is_unquote, at_top);
+                                if evaluates {
+                                    let (form, end) = car_cdr_simple(tail);
+                                    match end.tag {
+                                        Expr::Nil => {
+                                            return (form, saved_env, continuation, ret)
+                                        }
+                                    };
+                                    return (result, env, malformed, errctrl)
+                                }
+                                let unquote_splicing = Symbol("unquote-splicing");
+                                let head_is_splicing = eq_val(head, unquote_splicing);
+                                let is_splicing = and(head_is_sym, head_is_splicing);
+                                // there's no list to splice into
+                                let misplaced = and(is_splicing, at_top);
+                                if misplaced {
+                                    return (result, env, malformed, errctrl)
+                                }
+                                let lowers = or(is_unquote, is_splicing);
+                                if lowers {
+                                    let tail_level = sub(level, one);
+                                    let (template, cont) = quasiquote_pair(head, tail, level, tail_level, saved_env, continuation);
+                                    return (template, saved_env, cont, makethunk)
+                                }
+                                let quasiquote = Symbol("quasiquote");
+                                let head_is_quasiquote = eq_val(head, quasiquote);
+                                let is_quasiquote = and(head_is_sym, head_is_quasiquote);
+                                if is_quasiquote {
+                                    let tail_level = add(level, one);
+                                    let (template, cont) = quasiquote_pair(head, tail, level, tail_level, saved_env, continuation);
+                                    return (template, saved_env, cont, makethunk)
+                                }
+                                let (template, cont) = quasiquote_pair(head, tail, level, level, saved_env, continuation);
+                                return (template, saved_env, cont, makethunk)
+                            }
+                        };
+                        // atoms are quoted as they are
+                        return (result, env, continuation, makethunk)
+                    }
+                    Cont::Quasiquote2 => {
+                        // `result` is the expansion of the head of a pair
+                        let (tail, tail_level, saved_env, continuation) = decons4(cont);
+                        match tail.tag {
+                            Expr::Cons => {
+                                let cont: Cont::Quasiquote3 = cons4(result, continuation, foo, foo);
+                                let cont: Cont::Quasiquote = cons4(tail_level, saved_env, cont, foo);
+                                return (tail, saved_env, cont, makethunk)
+                            }
+                        };
+                        let pair: Expr::Cons = cons2(result, tail);
+                        return (pair, env, continuation, makethunk)
+                    }
+                    Cont::Quasiquote3 => {
+                        // `result` is the expansion of the tail of a pair
+                        let (head, continuation, _foo, _foo) = decons4(cont);
+                        let pair: Expr::Cons = cons2(head, result);
+                        return (pair, env, continuation, makethunk)
+                    }
+                    Cont::Splice => {
+                        // `result` is what's left of the spliced list
+                        let (tail, saved_env, continuation, level) = decons4(cont);
+                        match result.tag {
+                            Expr::Nil => {
+                                let cont: Cont::Quasiquote = cons4(level, saved_env, continuation, foo);
+                                return (tail, saved_env, cont, makethunk)
+                            }
+                            Expr::Cons => {
+                                let (first, rest) = decons2(result);
+                                let cont: Cont::Quasiquote3 = cons4(first, continuation, foo, foo);
+                                let cont: Cont::Splice = cons4(tail, saved_env, cont, level);
+                                return (rest, saved_env, cont, makethunk)
+                            }
+                        };
+                        return (result, env, invalid_arg, errctrl)
+                    }
+                    Cont::Receive => {
+                        // a single value that wasn't produced by `values`
+                        let (formals, saved_env, body, continuation) = decons4(cont);
+                        let (var, more) = car_cdr_simple(formals);
+                        let formals_is_nil = eq_tag(formals, nil);
+                        if formals_is_nil {
+                            return (result, env, arity_mismatch, errctrl)
+                        }
+                        let more_is_nil = eq_tag(more, nil);
+                        if !more_is_nil {
+                            return (result, env, arity_mismatch, errctrl)
+                        }
+                        match var.tag {
+                            Expr::Sym => {
+                                let extended_env = push_binding(var, result, saved_env);
+                                return (body, extended_env, continuation, ret)
+                            }
+                        };
+                        return (var, env, malformed, errctrl)
+                    }
+                    Cont::Values => {
+                        // `result` is bound to the first formal of the `Receive`
+                        // continuation, which is followed by another one for the
+                        // remaining formals and the extended environment. The other
+                        // arguments of `values` are evaluated in `values_env`
+                        let (more, values_env, receive, _foo) = decons4(cont);
+                        let (formals, saved_env, body, continuation) = decons4(receive);
+                        let (var, formals_rest) = car_cdr_simple(formals);
+                        let formals_is_nil = eq_tag(formals, nil);
+                        if formals_is_nil {
+                            return (result, env, arity_mismatch, errctrl)
+                        }
+                        match var.tag {
+                            Expr::Sym => {
+                                let extended_env = push_binding(var, result, saved_env);
+                                match more.tag {
+                                    Expr::Nil => {
+                                        match formals_rest.tag {
+                                            Expr::Nil => {
+                                                return (body, extended_env, continuation, ret)
+                                            }
+                                        };
+                                        return (result, env, arity_mismatch, errctrl)
+                                    }
+                                };
+                                let (arg, more) = car_cdr_simple(more);
+                                let receive: Cont::Receive = cons4(formals_rest, extended_env, body, continuation);
+                                let cont: Cont::Values = cons4(more, values_env, receive, foo);
+                                return (arg, values_env, cont, ret)
+                            }
+                        };
+                        return (var, env, malformed, errctrl)
+                    }
+                    Cont::Match => {
+                        // `result` is the scrutinee, which is matched against the
+                        // pattern of the first clause left
+                        let (clauses, saved_env, continuation, _foo) = decons4(cont);
+                        let (clause, rest_clauses) = decons2(clauses);
+                        match clause.tag {
+                            Expr::Cons => {
+                                let (pattern, body) = decons2(clause);
+                                match body.tag {
+                                    Expr::Cons => {
+                                        // the other clauses and the scrutinee are kept in
+                                        // the continuation in case the pattern doesn't fit.
+                                        // The next iterations match the pattern, passing
+                                        // the body as `result` and extending `env` with
+                                        // the variables the pattern binds
+                                        let (body) = begin_body(body);
+                                        let match_cont: Cont::Match = cons4(rest_clauses, saved_env, continuation, result);
+                                        let cont: Cont::MatchPattern = cons4(pattern, result, nil, match_cont);
+                                        return (body, saved_env, cont, makethunk)
+                                    }
+                                };
+                                return (clause, saved_env, malformed, errctrl)
+                            }
+                        };
+                        return (clause, saved_env, malformed, errctrl)
+                    }
+                    Cont::MatchPattern => {
+                        // `result` is the body of the clause
+                        let (pattern, value, work, match_cont) = decons4(cont);
+                        let (state, pattern, value, work, bound_env) = match_step(pattern, value, work, env);
+                        match state.value {
+                            Symbol("failed") => {
+                                let (rest_clauses, saved_env, _continuation, scrutinee) = decons4(match_cont);
+                                match rest_clauses.tag {
+                                    Expr::Nil => {
+                                        return (scrutinee, saved_env, no_match, errctrl)
+                                    }
+                                };
+                                // the next clause is started by the next iteration
+                                return (scrutinee, saved_env, match_cont, makethunk)
+                            }
+                            Symbol("done") => {
+                                // the whole pattern matched, so the clause's body
+                                // is evaluated with the variables it bound
+                                let (_rest_clauses, _saved_env, continuation, _scrutinee) = decons4(match_cont);
+                                return (result, bound_env, continuation, ret)
+                            }
+                        };
+                        let cont: Cont::MatchPattern = cons4(pattern, value, work, match_cont);
+                        return (result, bound_env, cont, makethunk)
                     }
                     Cont::Cproc => {
                         let (cproc_name, args, saved_env, cont) = decons4(cont);
@@ -1905,6 +4521,151 @@ fn apply_cont(cprocs: &[(&Symbol, usize)], ivc: bool) -> Func {
                             }
                         }
                     }
+                    Cont::Vector => {
+                        let (args, count, saved_env, continuation) = decons4(cont);
+                        let (unevaled_args, evaled_args) = decons2(args);
+                        // accumulate the evaluated arg (`result`)
+                        let evaled_args: Expr::Cons = cons2(result, evaled_args);
+                        let one = Num(1);
+                        let count = cast(count, Expr::Num);
+                        let count = add(count, one);
+                        match unevaled_args.tag {
+                            Expr::Nil => {
+                                // nothing else to evaluate
+                                let count_is_one = eq_val(count, one);
+                                if count_is_one {
+                                    let vec: Expr::Vector = cons4(count, one, result, foo);
+                                    return (vec, env, continuation, makethunk)
+                                }
+                                // the evaluated args are the nodes of capacity 1 built
+                                // for the leaves, in reverse order, as if the level
+                                // above them was just completed
+                                let header: Expr::Vector = cons4(count, one, t, foo);
+                                let cont: Cont::VectorBuild = cons4(nil, evaled_args, header, continuation);
+                                return (count, env, cont, ret)
+                            }
+                            Expr::Cons => {
+                                // pop the next argument that needs to be evaluated
+                                let (arg, unevaled_args) = decons2(unevaled_args);
+                                let args: Expr::Cons = cons2(unevaled_args, evaled_args);
+                                let cont: Cont::Vector = cons4(args, count, saved_env, continuation);
+                                return (arg, saved_env, cont, ret)
+                            }
+                        };
+                        return (result, env, malformed, errctrl)
+                    }
+                    Cont::VectorBuild => {
+                        // The tree is built one level per pass over the `pending` elements
+                        // of the level below, taking two iterations to group 4 of them into
+                        // a node of the capacity in the header. While building, the root
+                        // slot of the header tells whether the pending elements are in order
+                        let (pending, built, header, continuation) = decons4(cont);
+                        match result.tag {
+                            Expr::Num => {
+                                // `result` is the number of nodes built so far
+                                let (len, cap, forward, _foo) = decons4(header);
+                                match pending.tag {
+                                    Expr::Nil => {
+                                        // the level is complete
+                                        let one = Num(1);
+                                        let count_is_one = eq_val(result, one);
+                                        if count_is_one {
+                                            let (node, _rest) = decons2(built);
+                                            let vec: Expr::Vector = cons4(len, cap, node, foo);
+                                            return (vec, env, continuation, makethunk)
+                                        }
+                                        let four = Num(4);
+                                        let cap = cast(cap, Expr::Num);
+                                        let cap = mul(cap, four);
+                                        // the built nodes are in the opposite order of the
+                                        // pending elements they were made from
+                                        match forward.tag {
+                                            Expr::Nil => {
+                                                let header: Expr::Vector = cons4(len, cap, t, foo);
+                                                let cont: Cont::VectorBuild = cons4(built, nil, header, continuation);
+                                                return (zero, env, cont, ret)
+                                            }
+                                        };
+                                        let (pending) = vector_pad(built, result);
+                                        let header: Expr::Vector = cons4(len, cap, nil, foo);
+                                        let cont: Cont::VectorBuild = cons4(pending, nil, header, continuation);
+                                        return (zero, env, cont, ret)
+                                    }
+                                };
+                                // take the first half of a node, which is passed as `result`
+                                // to the next iteration
+                                let (a, pending) = car_cdr_simple(pending);
+                                let (b, pending) = car_cdr_simple(pending);
+                                let half: Expr::Vector = cons4(a, b, forward, result);
+                                let cont: Cont::VectorBuild = cons4(pending, built, header, continuation);
+                                return (half, env, cont, ret)
+                            }
+                        };
+                        // `result` is the first half of a node
+                        let (a, b, forward, count) = decons4(result);
+                        let (c, pending) = car_cdr_simple(pending);
+                        let (d, pending) = car_cdr_simple(pending);
+                        let one = Num(1);
+                        let count = cast(count, Expr::Num);
+                        let count = add(count, one);
+                        match forward.tag {
+                            Expr::Nil => {
+                                let node: Expr::Vector = cons4(d, c, b, a);
+                                let built: Expr::Cons = cons2(node, built);
+                                let cont: Cont::VectorBuild = cons4(pending, built, header, continuation);
+                                return (count, env, cont, ret)
+                            }
+                        };
+                        let node: Expr::Vector = cons4(a, b, c, d);
+                        let built: Expr::Cons = cons2(node, built);
+                        let cont: Cont::VectorBuild = cons4(pending, built, header, continuation);
+                        return (count, env, cont, ret)
+                    }
+                    Cont::VectorRef => {
+                        // `result` is the offset of the index relative to `node`
+                        let (node, cap, continuation, _foo) = decons4(cont);
+                        let four = Num(4);
+                        let cap = cast(cap, Expr::Num);
+                        let cap = div(cap, four);
+                        let (pos, offset) = vector_digit(result);
+                        let (child) = vector_child(node, pos);
+                        let one = Num(1);
+                        let cap_is_one = eq_val(cap, one);
+                        if cap_is_one {
+                            return (child, env, continuation, makethunk)
+                        }
+                        let cont: Cont::VectorRef = cons4(child, cap, continuation, foo);
+                        return (offset, env, cont, ret)
+                    }
+                    Cont::VectorSet => {
+                        // `result` is the offset of the index relative to `node`
+                        let (node, cap, val, continuation) = decons4(cont);
+                        let four = Num(4);
+                        let cap = cast(cap, Expr::Num);
+                        let cap = div(cap, four);
+                        let (pos, offset) = vector_digit(result);
+                        let one = Num(1);
+                        let cap_is_one = eq_val(cap, one);
+                        if cap_is_one {
+                            let (node) = vector_replace(node, pos, val);
+                            return (node, env, continuation, makethunk)
+                        }
+                        let (child) = vector_child(node, pos);
+                        let cont: Cont::VectorNode = cons4(pos, node, continuation, foo);
+                        let cont: Cont::VectorSet = cons4(child, cap, val, cont);
+                        return (offset, env, cont, ret)
+                    }
+                    Cont::VectorNode => {
+                        // `result` is the updated child of `parent` at position `pos`
+                        let (pos, parent, continuation, _foo) = decons4(cont);
+                        let (node) = vector_replace(parent, pos, result);
+                        return (node, env, continuation, makethunk)
+                    }
+                    Cont::Catch => {
+                        // the body returned without throwing
+                        let (_tag, continuation, _foo, _foo) = decons4(cont);
+                        return (result, env, continuation, makethunk)
+                    }
                 }
             }
         };
@@ -1912,9 +4673,42 @@ fn apply_cont(cprocs: &[(&Symbol, usize)], ivc: bool) -> Func {
     })
 }
 
+/// Besides wrapping values in thunks, `make_thunk` builds the error continuation
+/// when `ctrl` is `error`. In that case the `cont` argument is the reason of the
+/// error (a string) and `expr` is the offending expression, both of which are
+/// recorded in the resulting `Cont::Error`.
+///
+/// Reasons follow one rule: a built-in form whose shape is wrong (bindings,
+/// parameters, or the number of parts of a form like `quote` or `if`) is a
+/// "malformed expression", while an operator, function or continuation applied
+/// to the wrong number of arguments is an "arity mismatch". An argument that
+/// must be a number and isn't one is reported as a "non-numeric argument",
+/// which is turned here into an "invalid argument" if it's a number of a kind
+/// the operation doesn't take. Any other argument of the wrong type or out of
+/// the domain of the operation is an "invalid argument".
 fn make_thunk() -> Func {
+    let error_reason = aux_func!(error_reason(reason, expr): 1 => {
+        let non_numeric = String("non-numeric argument");
+        let is_non_numeric = eq_val(reason, non_numeric);
+        if is_non_numeric {
+            match expr.tag {
+                Expr::Num | Expr::U64 | Expr::I64 => {
+                    let invalid_arg = String("invalid argument");
+                    return (invalid_arg)
+                }
+            };
+            return (reason)
+        }
+        return (reason)
+    });
     aux_func!(make_thunk(expr, env, cont, ctrl): 3 => {
         match ctrl.value {
+            Symbol("error") => {
+                let reason = cast(cont, Expr::Str);
+                let (reason) = error_reason(reason, expr);
+                let err: Cont::Error = cons2(reason, expr);
+                return (expr, env, err)
+            }
             Symbol("make-thunk") => {
                 // We erase the environment as to not leak any information about internal variables.
                 let empty_env: Expr::Env;
@@ -1940,7 +4734,10 @@ fn make_thunk() -> Func {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{lang::Lang, lem::store::Store};
+    use crate::{
+        lang::Lang,
+        lem::{optimize::Pass, store::Store},
+    };
     use bellpepper_core::{test_cs::TestConstraintSystem, Comparable};
     use expect_test::{expect, Expect};
     use halo2curves::bn256::Fr;
@@ -1963,8 +4760,35 @@ mod tests {
         expect_eq(func.slots_count.commitment, expect!["1"]);
         expect_eq(func.slots_count.bit_decomp, expect!["3"]);
         expect_eq(cs.num_inputs(), expect!["1"]);
-        expect_eq(cs.aux().len(), expect!["9119"]);
-        expect_eq(cs.num_constraints(), expect!["11141"]);
+        expect_eq(cs.aux().len(), expect!["12614"]);
+        expect_eq(cs.num_constraints(), expect!["21425"]);
         assert_eq!(func.num_constraints(&store), cs.num_constraints());
+        assert_eq!(func.num_aux(&store), cs.aux().len());
+    }
+
+    #[test]
+    fn test_optimized_counts() {
+        let store = Store::default();
+        let lang: Lang<Fr> = Lang::new();
+        let num_constraints = |func: &Func| {
+            let frame = Frame::blank(func, 0, &store);
+            let mut cs = TestConstraintSystem::<Fr>::new();
+            func.synthesize_frame_aux(&mut cs, &store, &frame, &lang)
+                .unwrap();
+            assert_eq!(func.num_constraints(&store), cs.num_constraints());
+            assert_eq!(func.num_aux(&store), cs.aux().len());
+            cs.num_constraints()
+        };
+        let optimized = eval_step().optimize(&Pass::ALL);
+        let unoptimized_constraints = num_constraints(eval_step());
+        let optimized_constraints = num_constraints(&optimized);
+        expect!["21425"].assert_eq(&unoptimized_constraints.to_string());
+        expect!["21425"].assert_eq(&optimized_constraints.to_string());
+        assert!(optimized_constraints <= unoptimized_constraints);
+
+        // the NIVC step function is estimated just as well
+        let nivc = make_eval_step_from_config(&EvalConfig::new_nivc(&lang));
+        num_constraints(&nivc);
+        num_constraints(&nivc.optimize(&Pass::ALL));
     }
 }
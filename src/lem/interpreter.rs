@@ -270,6 +270,19 @@ impl Block {
                     };
                     bindings.insert_ptr(tgt.clone(), c);
                 }
+                Op::Inv(tgt, a) => {
+                    let a = *bindings.get_ptr(a)?.val();
+                    let c = if let IVal::Atom(f) = a {
+                        let f = store.expect_f(f);
+                        if f == &F::ZERO {
+                            bail!("Can't invert zero")
+                        }
+                        store.intern_atom(Tag::Expr(Num), f.invert().expect("not zero"))
+                    } else {
+                        bail!("`Inv` only works on numbers")
+                    };
+                    bindings.insert_ptr(tgt.clone(), c);
+                }
                 Op::Lt(tgt, a, b) => {
                     let a = *bindings.get_ptr(a)?.val();
                     let b = *bindings.get_ptr(b)?.val();
@@ -329,6 +342,82 @@ impl Block {
                     bindings.insert_ptr(tgt[0].clone(), c1);
                     bindings.insert_ptr(tgt[1].clone(), c2);
                 }
+                Op::BitAnd(tgt, a, b) | Op::BitOr(tgt, a, b) | Op::BitXor(tgt, a, b) => {
+                    let a = *bindings.get_ptr(a)?.val();
+                    let b = *bindings.get_ptr(b)?.val();
+                    let c = if let (IVal::Atom(f_idx), IVal::Atom(g_idx)) = (a, b) {
+                        hints.bit_decomp.push(Some(SlotData {
+                            vals: vec![Val::Num(IVal::Atom(f_idx))],
+                        }));
+                        hints.bit_decomp.push(Some(SlotData {
+                            vals: vec![Val::Num(IVal::Atom(g_idx))],
+                        }));
+                        let f = store.expect_f(f_idx).to_u64_unchecked();
+                        let g = store.expect_f(g_idx).to_u64_unchecked();
+                        let c = match op {
+                            Op::BitAnd(..) => f & g,
+                            Op::BitOr(..) => f | g,
+                            _ => f ^ g,
+                        };
+                        store.intern_atom(Tag::Expr(Num), F::from_u64(c))
+                    } else {
+                        bail!("Bitwise operations only work on atoms")
+                    };
+                    bindings.insert_ptr(tgt.clone(), c);
+                }
+                Op::Shl(tgt, a, b) | Op::Shr(tgt, a, b) => {
+                    let a = *bindings.get_ptr(a)?.val();
+                    let b = *bindings.get_ptr(b)?.val();
+                    let c = if let (IVal::Atom(f_idx), IVal::Atom(g_idx)) = (a, b) {
+                        let f = store.expect_f(f_idx).to_u64_unchecked();
+                        let g = *store.expect_f(g_idx);
+                        // the circuit shifts by the 6 least significant bits of `b` by
+                        // multiplying `a` by a power of two and then selecting 64 bits
+                        // of the product, which must fit in 128 bits
+                        let low = g.to_u64_unchecked() & 63;
+                        let shl = matches!(op, Op::Shl(..));
+                        let product = if shl {
+                            u128::from(f) << low
+                        } else {
+                            u128::from(f) << (64 - low)
+                        };
+                        hints.bit_decomp.push(Some(SlotData {
+                            vals: vec![Val::Num(IVal::Atom(g_idx))],
+                        }));
+                        hints.bit_decomp.push(Some(SlotData {
+                            vals: vec![Val::Num(IVal::Atom(store.intern_f(F::from_u128(product))))],
+                        }));
+                        let c = match g.to_u64() {
+                            Some(g) if g < 64 => {
+                                if shl {
+                                    f << g
+                                } else {
+                                    f >> g
+                                }
+                            }
+                            _ => 0,
+                        };
+                        store.intern_atom(Tag::Expr(Num), F::from_u64(c))
+                    } else {
+                        bail!("Shift operations only work on atoms")
+                    };
+                    bindings.insert_ptr(tgt.clone(), c);
+                }
+                Op::Pow(tgt, a, b) => {
+                    let a = *bindings.get_ptr(a)?.val();
+                    let b = *bindings.get_ptr(b)?.val();
+                    let c = if let (IVal::Atom(f_idx), IVal::Atom(g_idx)) = (a, b) {
+                        hints.bit_decomp.push(Some(SlotData {
+                            vals: vec![Val::Num(IVal::Atom(g_idx))],
+                        }));
+                        let f = *store.expect_f(f_idx);
+                        let g = store.expect_f(g_idx).to_u64_unchecked();
+                        store.intern_atom(Tag::Expr(Num), f.pow_vartime([g]))
+                    } else {
+                        bail!("`Pow` only works on atoms")
+                    };
+                    bindings.insert_ptr(tgt.clone(), c);
+                }
                 Op::Emit(a) => {
                     ch_terminal.send(bindings.get_ptr(a)?)?;
                 }
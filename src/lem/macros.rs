@@ -41,6 +41,9 @@ macro_rules! tag {
     ( Op2::$tag:ident ) => {
         $crate::lem::tag::Tag::Op2($crate::tag::Op2::$tag)
     };
+    ( Op3::$tag:ident ) => {
+        $crate::lem::tag::Tag::Op3($crate::tag::Op3::$tag)
+    };
 }
 
 #[macro_export]
@@ -93,6 +96,12 @@ macro_rules! op {
             $crate::var!($a),
         )
     };
+    ( let $tgt:ident = inv($a:ident) ) => {
+        $crate::lem::Op::Inv(
+            $crate::var!($tgt),
+            $crate::var!($a),
+        )
+    };
     ( let $tgt:ident = and($a:ident, $b:ident) ) => {
         $crate::lem::Op::And(
             $crate::var!($tgt),
@@ -156,6 +165,48 @@ macro_rules! op {
             $crate::var!($b),
         )
     };
+    ( let $tgt:ident = bit_and($a:ident, $b:ident) ) => {
+        $crate::lem::Op::BitAnd(
+            $crate::var!($tgt),
+            $crate::var!($a),
+            $crate::var!($b),
+        )
+    };
+    ( let $tgt:ident = bit_or($a:ident, $b:ident) ) => {
+        $crate::lem::Op::BitOr(
+            $crate::var!($tgt),
+            $crate::var!($a),
+            $crate::var!($b),
+        )
+    };
+    ( let $tgt:ident = bit_xor($a:ident, $b:ident) ) => {
+        $crate::lem::Op::BitXor(
+            $crate::var!($tgt),
+            $crate::var!($a),
+            $crate::var!($b),
+        )
+    };
+    ( let $tgt:ident = shl($a:ident, $b:ident) ) => {
+        $crate::lem::Op::Shl(
+            $crate::var!($tgt),
+            $crate::var!($a),
+            $crate::var!($b),
+        )
+    };
+    ( let $tgt:ident = shr($a:ident, $b:ident) ) => {
+        $crate::lem::Op::Shr(
+            $crate::var!($tgt),
+            $crate::var!($a),
+            $crate::var!($b),
+        )
+    };
+    ( let $tgt:ident = pow($a:ident, $b:ident) ) => {
+        $crate::lem::Op::Pow(
+            $crate::var!($tgt),
+            $crate::var!($a),
+            $crate::var!($b),
+        )
+    };
     ( emit($v:ident) ) => {
         $crate::lem::Op::Emit($crate::var!($v))
     };
@@ -405,6 +456,16 @@ macro_rules! block {
             $($tail)*
         )
     };
+    (@seq {$($limbs:expr)*}, let $tgt:ident = inv($a:ident) ; $($tail:tt)*) => {
+        $crate::block! (
+            @seq
+            {
+                $($limbs)*
+                $crate::op!(let $tgt = inv($a))
+            },
+            $($tail)*
+        )
+    };
     (@seq {$($limbs:expr)*}, let $tgt:ident = and($a:ident, $b:ident) ; $($tail:tt)*) => {
         $crate::block! (
             @seq
@@ -495,6 +556,66 @@ macro_rules! block {
             $($tail)*
         )
     };
+    (@seq {$($limbs:expr)*}, let $tgt:ident = bit_and($a:ident, $b:ident) ; $($tail:tt)*) => {
+        $crate::block! (
+            @seq
+            {
+                $($limbs)*
+                $crate::op!(let $tgt = bit_and($a, $b))
+            },
+            $($tail)*
+        )
+    };
+    (@seq {$($limbs:expr)*}, let $tgt:ident = bit_or($a:ident, $b:ident) ; $($tail:tt)*) => {
+        $crate::block! (
+            @seq
+            {
+                $($limbs)*
+                $crate::op!(let $tgt = bit_or($a, $b))
+            },
+            $($tail)*
+        )
+    };
+    (@seq {$($limbs:expr)*}, let $tgt:ident = bit_xor($a:ident, $b:ident) ; $($tail:tt)*) => {
+        $crate::block! (
+            @seq
+            {
+                $($limbs)*
+                $crate::op!(let $tgt = bit_xor($a, $b))
+            },
+            $($tail)*
+        )
+    };
+    (@seq {$($limbs:expr)*}, let $tgt:ident = shl($a:ident, $b:ident) ; $($tail:tt)*) => {
+        $crate::block! (
+            @seq
+            {
+                $($limbs)*
+                $crate::op!(let $tgt = shl($a, $b))
+            },
+            $($tail)*
+        )
+    };
+    (@seq {$($limbs:expr)*}, let $tgt:ident = shr($a:ident, $b:ident) ; $($tail:tt)*) => {
+        $crate::block! (
+            @seq
+            {
+                $($limbs)*
+                $crate::op!(let $tgt = shr($a, $b))
+            },
+            $($tail)*
+        )
+    };
+    (@seq {$($limbs:expr)*}, let $tgt:ident = pow($a:ident, $b:ident) ; $($tail:tt)*) => {
+        $crate::block! (
+            @seq
+            {
+                $($limbs)*
+                $crate::op!(let $tgt = pow($a, $b))
+            },
+            $($tail)*
+        )
+    };
     (@seq {$($limbs:expr)*}, emit($v:ident) ; $($tail:tt)*) => {
         $crate::block! (
             @seq
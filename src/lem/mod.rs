@@ -58,18 +58,24 @@
 //! 5. LEM should be transformed to SSA to make it simple to synthesize
 //! 6. We also check for variables that are not used. If intended they should
 //!    be prefixed by "_"
+//! 7. Variables are bound to pointers or booleans and can't be used in place
+//!    of one another, e.g. a boolean can't be the child of a `Cons2`, nor can a
+//!    pointer be the condition of an `If`
 
 pub mod circuit;
 pub mod coroutine;
+pub mod dot;
 pub mod eval;
 pub mod interpreter;
 mod macros;
 pub mod multiframe;
+pub mod optimize;
 pub mod pointers;
 mod slot;
 pub mod store;
 pub mod store_core;
 pub mod tag;
+pub mod text;
 mod var_map;
 
 use anyhow::{bail, Result};
@@ -79,7 +85,7 @@ use std::sync::Arc;
 #[cfg(test)]
 mod tests;
 
-use crate::{field::LurkField, symbol::Symbol, tag::ExprTag};
+use crate::{field::LurkField, state::lurk_sym, symbol::Symbol, tag::ExprTag};
 
 use self::{pointers::Ptr, slot::SlotsCounter, store::Store, tag::Tag, var_map::VarMap};
 
@@ -131,6 +137,16 @@ impl Lit {
         }
     }
 
+    /// The tag of the pointer returned by `to_ptr`, which is `Nil` for `nil`
+    /// and `Key` for keywords, as in `Store::intern_symbol`
+    pub fn tag(&self) -> Tag {
+        match self {
+            Self::Symbol(s) if *s == lurk_sym("nil") => Tag::Expr(ExprTag::Nil),
+            Self::Symbol(s) if s.is_keyword() => Tag::Expr(ExprTag::Key),
+            _ => self.lit_type().tag(),
+        }
+    }
+
     pub fn lit_type(&self) -> LitType {
         match self {
             Self::Symbol(..) => LitType::Symbol,
@@ -251,12 +267,29 @@ pub enum Op {
     Mul(Var, Var, Var),
     /// `Div(y, a, b)` binds `y` to the sum of `a` and `b`
     Div(Var, Var, Var),
+    /// `Inv(y, a)` binds `y` to the multiplicative inverse of `a`, which must not be zero
+    Inv(Var, Var),
     /// `Lt(y, a, b)` binds `y` to `1` if `a < b`, or to `0` otherwise
     Lt(Var, Var, Var),
     /// `Trunc(y, a, n)` binds `y` to `a` truncated to `n` bits, up to 64 bits
     Trunc(Var, Var, u32),
     /// `DivRem64(ys, a, b)` binds `ys` to `(a / b, a % b)` as if they were u64
     DivRem64([Var; 2], Var, Var),
+    /// `BitAnd(y, a, b)` binds `y` to the bitwise and of `a` and `b` as if they were u64
+    BitAnd(Var, Var, Var),
+    /// `BitOr(y, a, b)` binds `y` to the bitwise or of `a` and `b` as if they were u64
+    BitOr(Var, Var, Var),
+    /// `BitXor(y, a, b)` binds `y` to the bitwise xor of `a` and `b` as if they were u64
+    BitXor(Var, Var, Var),
+    /// `Shl(y, a, b)` binds `y` to the u64 `a` shifted `b` bits to the left, or to
+    /// `0` if `b >= 64`
+    Shl(Var, Var, Var),
+    /// `Shr(y, a, b)` binds `y` to the u64 `a` shifted `b` bits to the right, or to
+    /// `0` if `b >= 64`
+    Shr(Var, Var, Var),
+    /// `Pow(y, a, b)` binds `y` to `a` raised to the power of the u64 made of
+    /// the 64 least significant bits of `b`
+    Pow(Var, Var, Var),
     /// `Emit(v)` sends the value of `v` through the channel during interpretation
     Emit(Var),
     /// `Recv(v)` binds `v` to a variable received from the channel
@@ -295,6 +328,86 @@ pub enum Op {
     Unit(fn()),
 }
 
+/// The kinds of values that LEM variables are bound to during interpretation.
+/// Numbers are the pointers that are known to have a numeric tag, like the ones
+/// that result from arithmetic. They can be used wherever pointers are expected,
+/// except as hash images, but pointers must be cast or matched against numeric
+/// tags before they can be used as numbers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VarType {
+    Ptr,
+    Num,
+    Bool,
+}
+
+impl VarType {
+    fn accepts(self, found: VarType) -> bool {
+        match self {
+            VarType::Ptr => found != VarType::Bool,
+            VarType::Num | VarType::Bool => found == self,
+        }
+    }
+
+    /// The type of a pointer with tag `tag`
+    fn of_tag(tag: &Tag) -> VarType {
+        match tag {
+            Tag::Expr(ExprTag::Num | ExprTag::U64 | ExprTag::I64 | ExprTag::Char) => VarType::Num,
+            _ => VarType::Ptr,
+        }
+    }
+
+    /// The type that accepts both `self` and `other`
+    fn join(self, other: VarType) -> VarType {
+        if self == other {
+            self
+        } else {
+            VarType::Ptr
+        }
+    }
+}
+
+impl std::fmt::Display for VarType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VarType::Ptr => write!(f, "a pointer"),
+            VarType::Num => write!(f, "a number"),
+            VarType::Bool => write!(f, "a boolean"),
+        }
+    }
+}
+
+/// A step of the path of calls and branches that leads to an op or control
+/// statement. Steps are only formatted when reporting a type error
+enum Step<'a> {
+    Func(&'a str),
+    Op(&'a Op),
+    Return(&'a [Var]),
+    MatchTag(&'a Var),
+    TagCase(&'a Var, Option<&'a Tag>),
+    MatchValue(&'a Var),
+    LitCase(&'a Var, Option<&'a Lit>),
+    If(&'a Var),
+    Branch(&'a Var, bool),
+}
+
+impl std::fmt::Display for Step<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Step::Func(name) => write!(f, "`{name}`"),
+            Step::Op(op) => write!(f, "`{op}`"),
+            Step::Return(vars) => write!(f, "`return ({})`", text::fmt_vars(vars)),
+            Step::MatchTag(var) => write!(f, "`match {var}.tag`"),
+            Step::TagCase(var, Some(tag)) => write!(f, "`{var}.tag` is {}", text::fmt_tag(tag)),
+            Step::TagCase(var, None) => write!(f, "`{var}.tag` is _"),
+            Step::MatchValue(var) => write!(f, "`match {var}`"),
+            Step::LitCase(var, Some(lit)) => write!(f, "`{var}` is {}", text::fmt_lit(lit)),
+            Step::LitCase(var, None) => write!(f, "`{var}` is _"),
+            Step::If(var) => write!(f, "`if {var}`"),
+            Step::Branch(var, cond) => write!(f, "`{var}` is {cond}"),
+        }
+    }
+}
+
 impl Func {
     /// Instantiates a `Func` with the appropriate transformations and checks
     pub fn new(
@@ -405,7 +518,7 @@ impl Func {
                         is_bound(src, map)?;
                         is_unique(tgt, map);
                     }
-                    Op::Not(tgt, a) => {
+                    Op::Not(tgt, a) | Op::Inv(tgt, a) => {
                         is_bound(a, map)?;
                         is_unique(tgt, map);
                     }
@@ -417,7 +530,13 @@ impl Func {
                     | Op::Sub(tgt, a, b)
                     | Op::Mul(tgt, a, b)
                     | Op::Div(tgt, a, b)
-                    | Op::Lt(tgt, a, b) => {
+                    | Op::Lt(tgt, a, b)
+                    | Op::BitAnd(tgt, a, b)
+                    | Op::BitOr(tgt, a, b)
+                    | Op::BitXor(tgt, a, b)
+                    | Op::Shl(tgt, a, b)
+                    | Op::Shr(tgt, a, b)
+                    | Op::Pow(tgt, a, b) => {
                         is_bound(a, map)?;
                         is_bound(b, map)?;
                         is_unique(tgt, map);
@@ -504,6 +623,7 @@ impl Func {
                             Tag::Cont(..) => 1,
                             Tag::Op1(..) => 2,
                             Tag::Op2(..) => 3,
+                            Tag::Op3(..) => 4,
                         };
                         if let Some(kind) = kind {
                             if kind != tag_kind {
@@ -555,7 +675,268 @@ impl Func {
                 bail!("Variable {var} not used. If intended, please prefix it with \"_\"")
             }
         }
-        Ok(())
+        self.check_types()
+    }
+
+    /// Infers whether each variable is bound to a pointer, a number or a
+    /// boolean and fails on the first use of a variable as something it's not.
+    /// The error points at the offending op or control statement through the
+    /// path of calls and branches that leads to it.
+    ///
+    /// Called functions are checked with their parameters bound to the types of
+    /// the arguments, and their outputs take the types they're returned with.
+    fn check_types(&self) -> Result<()> {
+        use std::collections::HashMap;
+        use VarType::{Bool, Num, Ptr};
+
+        type Types = HashMap<Var, VarType>;
+        /// The types of the values returned by a block, joined over its branches
+        type Returns = Option<Vec<VarType>>;
+
+        fn get(var: &Var, types: &Types) -> Result<VarType> {
+            match types.get(var) {
+                Some(typ) => Ok(*typ),
+                None => bail!("Variable {var} is unbound."),
+            }
+        }
+
+        fn fmt_path(path: &[Step<'_>]) -> String {
+            let path = path.iter().map(|step| step.to_string()).collect::<Vec<_>>();
+            path.join(" > ")
+        }
+
+        fn expect(vars: &[Var], expected: VarType, types: &Types, path: &[Step<'_>]) -> Result<()> {
+            for var in vars {
+                let found = get(var, types)?;
+                if !expected.accepts(found) {
+                    bail!(
+                        "Type error at {}: `{var}` is {found}, but {expected} was expected",
+                        fmt_path(path)
+                    )
+                }
+            }
+            Ok(())
+        }
+
+        /// Numbers have no hash preimage, so destructuring one is an error
+        fn expect_hashed(var: &Var, types: &Types, path: &[Step<'_>]) -> Result<()> {
+            let found = get(var, types)?;
+            if found != Ptr {
+                bail!(
+                    "Type error at {}: `{var}` is {found}, but a pointer to hashed data was expected",
+                    fmt_path(path)
+                )
+            }
+            Ok(())
+        }
+
+        fn bind(vars: &[Var], typ: VarType, types: &mut Types) {
+            vars.iter().for_each(|var| {
+                types.insert(var.clone(), typ);
+            })
+        }
+
+        fn recurse<'a>(
+            block: &'a Block,
+            types: &mut Types,
+            returns: &mut Returns,
+            path: &mut Vec<Step<'a>>,
+        ) -> Result<()> {
+            for op in &block.ops {
+                path.push(Step::Op(op));
+                match op {
+                    Op::Crout(out, _, inp) => {
+                        expect(inp, Ptr, types, path)?;
+                        bind(std::slice::from_ref(out), Ptr, types);
+                    }
+                    Op::Cproc(out, _, inp) => {
+                        expect(inp, Ptr, types, path)?;
+                        bind(out, Ptr, types);
+                    }
+                    Op::Call(out, func, inp) => {
+                        expect(inp, Ptr, types, path)?;
+                        for (param, arg) in func.input_params.iter().zip(inp) {
+                            let typ = get(arg, types)?;
+                            bind(std::slice::from_ref(param), typ, types);
+                        }
+                        let mut func_returns = None;
+                        recurse(&func.body, types, &mut func_returns, path)?;
+                        let func_returns = func_returns.unwrap_or_else(|| vec![Ptr; out.len()]);
+                        for (var, typ) in out.iter().zip(func_returns) {
+                            bind(std::slice::from_ref(var), typ, types);
+                        }
+                    }
+                    Op::Copy(tgt, src) => {
+                        let typ = get(src, types)?;
+                        bind(std::slice::from_ref(tgt), typ, types);
+                    }
+                    Op::Zero(tgt, _)
+                    | Op::Hash3Zeros(tgt, _)
+                    | Op::Hash4Zeros(tgt, _)
+                    | Op::Hash6Zeros(tgt, _)
+                    | Op::Hash8Zeros(tgt, _)
+                    | Op::Recv(tgt) => bind(std::slice::from_ref(tgt), Ptr, types),
+                    Op::Lit(tgt, lit) => {
+                        let typ = match lit {
+                            Lit::Num(..) => Num,
+                            Lit::String(..) | Lit::Symbol(..) => Ptr,
+                        };
+                        bind(std::slice::from_ref(tgt), typ, types);
+                    }
+                    Op::Cast(tgt, tag, src) => {
+                        expect(std::slice::from_ref(src), Ptr, types, path)?;
+                        bind(std::slice::from_ref(tgt), VarType::of_tag(tag), types);
+                    }
+                    Op::Inv(tgt, src) | Op::Trunc(tgt, src, _) => {
+                        expect(std::slice::from_ref(src), Num, types, path)?;
+                        bind(std::slice::from_ref(tgt), Num, types);
+                    }
+                    Op::EqTag(tgt, a, b) | Op::EqVal(tgt, a, b) => {
+                        expect(&[a.clone(), b.clone()], Ptr, types, path)?;
+                        bind(std::slice::from_ref(tgt), Bool, types);
+                    }
+                    Op::Lt(tgt, a, b) => {
+                        expect(&[a.clone(), b.clone()], Num, types, path)?;
+                        bind(std::slice::from_ref(tgt), Bool, types);
+                    }
+                    Op::Not(tgt, a) => {
+                        expect(std::slice::from_ref(a), Bool, types, path)?;
+                        bind(std::slice::from_ref(tgt), Bool, types);
+                    }
+                    Op::And(tgt, a, b) | Op::Or(tgt, a, b) => {
+                        expect(&[a.clone(), b.clone()], Bool, types, path)?;
+                        bind(std::slice::from_ref(tgt), Bool, types);
+                    }
+                    Op::Add(tgt, a, b)
+                    | Op::Sub(tgt, a, b)
+                    | Op::Mul(tgt, a, b)
+                    | Op::Div(tgt, a, b)
+                    | Op::BitAnd(tgt, a, b)
+                    | Op::BitOr(tgt, a, b)
+                    | Op::BitXor(tgt, a, b)
+                    | Op::Shl(tgt, a, b)
+                    | Op::Shr(tgt, a, b)
+                    | Op::Pow(tgt, a, b) => {
+                        expect(&[a.clone(), b.clone()], Num, types, path)?;
+                        bind(std::slice::from_ref(tgt), Num, types);
+                    }
+                    Op::DivRem64(tgt, a, b) => {
+                        expect(&[a.clone(), b.clone()], Num, types, path)?;
+                        bind(tgt, Num, types);
+                    }
+                    Op::Hide(tgt, sec, src) => {
+                        expect(std::slice::from_ref(sec), Num, types, path)?;
+                        expect(std::slice::from_ref(src), Ptr, types, path)?;
+                        bind(std::slice::from_ref(tgt), Ptr, types);
+                    }
+                    Op::Emit(a) => expect(std::slice::from_ref(a), Ptr, types, path)?,
+                    Op::Cons2(img, _, preimg) => {
+                        expect(preimg, Ptr, types, path)?;
+                        bind(std::slice::from_ref(img), Ptr, types);
+                    }
+                    Op::Cons3(img, _, preimg) | Op::PushBinding(img, preimg) => {
+                        expect(preimg, Ptr, types, path)?;
+                        bind(std::slice::from_ref(img), Ptr, types);
+                    }
+                    Op::Cons4(img, _, preimg) => {
+                        expect(preimg, Ptr, types, path)?;
+                        bind(std::slice::from_ref(img), Ptr, types);
+                    }
+                    Op::Decons2(preimg, img) => {
+                        expect_hashed(img, types, path)?;
+                        bind(preimg, Ptr, types);
+                    }
+                    Op::Decons3(preimg, img) | Op::PopBinding(preimg, img) => {
+                        expect_hashed(img, types, path)?;
+                        bind(preimg, Ptr, types);
+                    }
+                    Op::Decons4(preimg, img) => {
+                        expect_hashed(img, types, path)?;
+                        bind(preimg, Ptr, types);
+                    }
+                    Op::Open(tgt_secret, tgt_ptr, comm_or_num) => {
+                        expect(std::slice::from_ref(comm_or_num), Ptr, types, path)?;
+                        bind(std::slice::from_ref(tgt_secret), Num, types);
+                        bind(std::slice::from_ref(tgt_ptr), Ptr, types);
+                    }
+                    Op::Unit(_) => (),
+                }
+                path.pop();
+            }
+            match &block.ctrl {
+                Ctrl::Return(return_vars) => {
+                    path.push(Step::Return(return_vars));
+                    expect(return_vars, Ptr, types, path)?;
+                    path.pop();
+                    let found = return_vars
+                        .iter()
+                        .map(|var| get(var, types))
+                        .collect::<Result<Vec<_>>>()?;
+                    match returns {
+                        None => *returns = Some(found),
+                        Some(typs) => typs
+                            .iter_mut()
+                            .zip(found)
+                            .for_each(|(typ, found)| *typ = typ.join(found)),
+                    }
+                }
+                Ctrl::MatchTag(var, cases, def) => {
+                    path.push(Step::MatchTag(var));
+                    expect(std::slice::from_ref(var), Ptr, types, path)?;
+                    path.pop();
+                    // `var` is a number in the cases of numeric tags
+                    let typ = get(var, types)?;
+                    for (tag, block) in cases {
+                        path.push(Step::TagCase(var, Some(tag)));
+                        bind(std::slice::from_ref(var), VarType::of_tag(tag), types);
+                        recurse(block, types, returns, path)?;
+                        bind(std::slice::from_ref(var), typ, types);
+                        path.pop();
+                    }
+                    if let Some(def) = def {
+                        path.push(Step::TagCase(var, None));
+                        recurse(def, types, returns, path)?;
+                        path.pop();
+                    }
+                }
+                Ctrl::MatchValue(var, _, cases, def) => {
+                    path.push(Step::MatchValue(var));
+                    expect(std::slice::from_ref(var), Ptr, types, path)?;
+                    path.pop();
+                    for (lit, block) in cases {
+                        path.push(Step::LitCase(var, Some(lit)));
+                        recurse(block, types, returns, path)?;
+                        path.pop();
+                    }
+                    if let Some(def) = def {
+                        path.push(Step::LitCase(var, None));
+                        recurse(def, types, returns, path)?;
+                        path.pop();
+                    }
+                }
+                Ctrl::If(x, true_block, false_block) => {
+                    path.push(Step::If(x));
+                    expect(std::slice::from_ref(x), Bool, types, path)?;
+                    path.pop();
+                    path.push(Step::Branch(x, true));
+                    recurse(true_block, types, returns, path)?;
+                    path.pop();
+                    path.push(Step::Branch(x, false));
+                    recurse(false_block, types, returns, path)?;
+                    path.pop();
+                }
+            }
+            Ok(())
+        }
+
+        let types = &mut HashMap::new();
+        bind(&self.input_params, Ptr, types);
+        recurse(
+            &self.body,
+            types,
+            &mut None,
+            &mut vec![Step::Func(&self.name)],
+        )
     }
 
     /// Deconflict will replace conflicting names and make the function SSA. The
@@ -715,6 +1096,11 @@ impl Block {
                     let tgt = insert_one(map, uniq, &tgt);
                     ops.push(Op::Div(tgt, a, b))
                 }
+                Op::Inv(tgt, a) => {
+                    let a = map.get_cloned(&a)?;
+                    let tgt = insert_one(map, uniq, &tgt);
+                    ops.push(Op::Inv(tgt, a))
+                }
                 Op::Lt(tgt, a, b) => {
                     let a = map.get_cloned(&a)?;
                     let b = map.get_cloned(&b)?;
@@ -732,6 +1118,42 @@ impl Block {
                     let tgt = insert_many(map, uniq, &tgt);
                     ops.push(Op::DivRem64(tgt.try_into().unwrap(), a, b))
                 }
+                Op::BitAnd(tgt, a, b) => {
+                    let a = map.get_cloned(&a)?;
+                    let b = map.get_cloned(&b)?;
+                    let tgt = insert_one(map, uniq, &tgt);
+                    ops.push(Op::BitAnd(tgt, a, b))
+                }
+                Op::BitOr(tgt, a, b) => {
+                    let a = map.get_cloned(&a)?;
+                    let b = map.get_cloned(&b)?;
+                    let tgt = insert_one(map, uniq, &tgt);
+                    ops.push(Op::BitOr(tgt, a, b))
+                }
+                Op::BitXor(tgt, a, b) => {
+                    let a = map.get_cloned(&a)?;
+                    let b = map.get_cloned(&b)?;
+                    let tgt = insert_one(map, uniq, &tgt);
+                    ops.push(Op::BitXor(tgt, a, b))
+                }
+                Op::Shl(tgt, a, b) => {
+                    let a = map.get_cloned(&a)?;
+                    let b = map.get_cloned(&b)?;
+                    let tgt = insert_one(map, uniq, &tgt);
+                    ops.push(Op::Shl(tgt, a, b))
+                }
+                Op::Shr(tgt, a, b) => {
+                    let a = map.get_cloned(&a)?;
+                    let b = map.get_cloned(&b)?;
+                    let tgt = insert_one(map, uniq, &tgt);
+                    ops.push(Op::Shr(tgt, a, b))
+                }
+                Op::Pow(tgt, a, b) => {
+                    let a = map.get_cloned(&a)?;
+                    let b = map.get_cloned(&b)?;
+                    let tgt = insert_one(map, uniq, &tgt);
+                    ops.push(Op::Pow(tgt, a, b))
+                }
                 Op::Emit(a) => {
                     let a = map.get_cloned(&a)?;
                     ops.push(Op::Emit(a))
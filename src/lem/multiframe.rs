@@ -500,7 +500,7 @@ const BIT_DECOMP_GRUMPKIN_WITNESS_SIZE: usize = 364;
 /// Computes the witness size for a `SlotType`. Note that the witness size for
 /// bit decomposition depends on the field we're in.
 #[inline]
-fn compute_witness_size<F: LurkField>(slot_type: &SlotType, store: &Store<F>) -> usize {
+pub(crate) fn compute_witness_size<F: LurkField>(slot_type: &SlotType, store: &Store<F>) -> usize {
     match slot_type {
         SlotType::Hash4 => store.hash4_cost() + 4, // 4 preimg elts
         SlotType::Hash6 => store.hash6_cost() + 6, // 6 preimg elts
@@ -0,0 +1,738 @@
+//! Optimization passes over LEM functions
+//!
+//! The passes expect functions in SSA, as produced by `Func::new`, and output
+//! functions in SSA. They are applied to the functions called by the optimized
+//! function as well, but never across calls.
+//!
+//! The passes are opt-in: Lurk's step function is built unoptimized and
+//! `Func::optimize` must be called on it explicitly.
+
+use indexmap::IndexMap;
+use std::collections::{HashMap, HashSet};
+
+use crate::tag::ExprTag::{Comm, Env, Num};
+
+use super::{Block, Ctrl, Func, Lit, Op, Tag, Var};
+
+/// The optimization passes over LEM functions
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pass {
+    /// Folds operations on literals and zeros into literals and zeros, and
+    /// boolean operations with statically known operands into their results
+    FoldConstants,
+    /// Removes the operations that compute what a previous operation in scope
+    /// has already computed, including the deconstruction of a pointer that
+    /// was just constructed
+    EliminateCommonSubexpressions,
+    /// Selects the branch of the `MatchTag`s, `MatchValue`s and `If`s whose
+    /// scrutinee is statically known
+    PruneBranches,
+    /// Removes the pure operations whose results aren't used, prefixing with
+    /// `_` the variables that become unused so the result still passes
+    /// `Func::check`
+    EliminateDeadCode,
+}
+
+impl Pass {
+    pub const ALL: [Pass; 4] = [
+        Pass::FoldConstants,
+        Pass::EliminateCommonSubexpressions,
+        Pass::PruneBranches,
+        Pass::EliminateDeadCode,
+    ];
+
+    /// Runs the pass over `func` and the functions it calls
+    pub fn run(self, func: &Func) -> Func {
+        let (input_params, body) = match self {
+            Pass::EliminateDeadCode => {
+                let mut dead_code = DeadCode::default();
+                let body = dead_code.block(&func.body);
+                let mut input_params = func.input_params.clone();
+                input_params
+                    .iter_mut()
+                    .filter(|var| !dead_code.live.contains(&**var))
+                    .for_each(mark_unused);
+                (input_params, body)
+            }
+            _ => {
+                let mut forward = Forward {
+                    pass: self,
+                    subst: HashMap::new(),
+                    known: HashMap::new(),
+                };
+                let body = forward.block(&func.body, HashMap::new());
+                (func.input_params.clone(), body)
+            }
+        };
+        Func::new_unchecked(func.name.clone(), input_params, func.output_size, body)
+    }
+}
+
+impl Func {
+    /// Runs `passes` in order, over and over, until they no longer change the
+    /// function
+    pub fn optimize(&self, passes: &[Pass]) -> Func {
+        let mut func = self.clone();
+        loop {
+            let next = passes
+                .iter()
+                .fold(func.clone(), |func, pass| pass.run(&func));
+            if next == func {
+                return func;
+            }
+            func = next;
+        }
+    }
+}
+
+fn inputs_mut(op: &mut Op) -> Vec<&mut Var> {
+    match op {
+        Op::Crout(_, _, xs) | Op::Cproc(_, _, xs) | Op::Call(_, _, xs) => xs.iter_mut().collect(),
+        Op::Copy(_, x)
+        | Op::Cast(_, _, x)
+        | Op::Not(_, x)
+        | Op::Inv(_, x)
+        | Op::Trunc(_, x, _)
+        | Op::Emit(x)
+        | Op::Decons2(_, x)
+        | Op::Decons3(_, x)
+        | Op::Decons4(_, x)
+        | Op::PopBinding(_, x)
+        | Op::Open(_, _, x) => vec![x],
+        Op::EqTag(_, a, b)
+        | Op::EqVal(_, a, b)
+        | Op::And(_, a, b)
+        | Op::Or(_, a, b)
+        | Op::Add(_, a, b)
+        | Op::Sub(_, a, b)
+        | Op::Mul(_, a, b)
+        | Op::Div(_, a, b)
+        | Op::Lt(_, a, b)
+        | Op::DivRem64(_, a, b)
+        | Op::BitAnd(_, a, b)
+        | Op::BitOr(_, a, b)
+        | Op::BitXor(_, a, b)
+        | Op::Shl(_, a, b)
+        | Op::Shr(_, a, b)
+        | Op::Pow(_, a, b)
+        | Op::Hide(_, a, b) => vec![a, b],
+        Op::Cons2(_, _, xs) => xs.iter_mut().collect(),
+        Op::Cons3(_, _, xs) | Op::PushBinding(_, xs) => xs.iter_mut().collect(),
+        Op::Cons4(_, _, xs) => xs.iter_mut().collect(),
+        Op::Zero(..)
+        | Op::Hash3Zeros(..)
+        | Op::Hash4Zeros(..)
+        | Op::Hash6Zeros(..)
+        | Op::Hash8Zeros(..)
+        | Op::Lit(..)
+        | Op::Recv(..)
+        | Op::Unit(..) => vec![],
+    }
+}
+
+fn outputs_mut(op: &mut Op) -> Vec<&mut Var> {
+    match op {
+        Op::Cproc(ys, ..) | Op::Call(ys, ..) => ys.iter_mut().collect(),
+        Op::DivRem64(ys, ..) | Op::Decons2(ys, _) => ys.iter_mut().collect(),
+        Op::Decons3(ys, _) | Op::PopBinding(ys, _) => ys.iter_mut().collect(),
+        Op::Decons4(ys, _) => ys.iter_mut().collect(),
+        Op::Open(s, p, _) => vec![s, p],
+        Op::Emit(_) | Op::Unit(_) => vec![],
+        Op::Crout(y, ..)
+        | Op::Copy(y, _)
+        | Op::Zero(y, _)
+        | Op::Hash3Zeros(y, _)
+        | Op::Hash4Zeros(y, _)
+        | Op::Hash6Zeros(y, _)
+        | Op::Hash8Zeros(y, _)
+        | Op::Lit(y, _)
+        | Op::Cast(y, ..)
+        | Op::EqTag(y, ..)
+        | Op::EqVal(y, ..)
+        | Op::Not(y, _)
+        | Op::And(y, ..)
+        | Op::Or(y, ..)
+        | Op::Add(y, ..)
+        | Op::Sub(y, ..)
+        | Op::Mul(y, ..)
+        | Op::Div(y, ..)
+        | Op::Inv(y, _)
+        | Op::Lt(y, ..)
+        | Op::Trunc(y, ..)
+        | Op::BitAnd(y, ..)
+        | Op::BitOr(y, ..)
+        | Op::BitXor(y, ..)
+        | Op::Shl(y, ..)
+        | Op::Shr(y, ..)
+        | Op::Pow(y, ..)
+        | Op::Recv(y)
+        | Op::Cons2(y, ..)
+        | Op::Cons3(y, ..)
+        | Op::Cons4(y, ..)
+        | Op::PushBinding(y, _)
+        | Op::Hide(y, ..) => vec![y],
+    }
+}
+
+/// Whether `op` can be removed when its results aren't used. Operations that
+/// can fail are kept because they also assert something about their inputs.
+/// That's the case of the ones that only work on numbers, such as `Add` or
+/// `Shl`, whose circuits also constrain the ranges of their operands, and of
+/// `Inv` or `Decons2`
+fn is_pure(op: &Op) -> bool {
+    matches!(
+        op,
+        Op::Copy(..)
+            | Op::Zero(..)
+            | Op::Hash3Zeros(..)
+            | Op::Hash4Zeros(..)
+            | Op::Hash6Zeros(..)
+            | Op::Hash8Zeros(..)
+            | Op::Lit(..)
+            | Op::Cast(..)
+            | Op::EqTag(..)
+            | Op::EqVal(..)
+            | Op::Not(..)
+            | Op::And(..)
+            | Op::Or(..)
+            | Op::Cons2(..)
+            | Op::Cons3(..)
+            | Op::Cons4(..)
+            | Op::PushBinding(..)
+    )
+}
+
+fn mark_unused(var: &mut Var) {
+    if !var.name().starts_with('_') {
+        *var = Var::new(&format!("_{var}"));
+    }
+}
+
+/// What's statically known about a variable
+#[derive(Clone, Debug, PartialEq)]
+enum Known {
+    /// A pointer with a known tag
+    Tag(Tag),
+    /// A pointer with a known tag whose value is a number
+    Num(Tag, u128),
+    /// A pointer to a string or symbol literal
+    Lit(Lit),
+    Bool(bool),
+}
+
+impl Known {
+    fn tag(&self) -> Option<Tag> {
+        match self {
+            Known::Tag(tag) | Known::Num(tag, _) => Some(*tag),
+            Known::Lit(lit) => Some(lit.tag()),
+            Known::Bool(_) => None,
+        }
+    }
+
+    fn lit(&self) -> Option<Lit> {
+        match self {
+            Known::Num(Tag::Expr(Num), num) => Some(Lit::Num(*num)),
+            Known::Lit(lit) => Some(lit.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Identifies the operations that compute the same results from the same
+/// inputs
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Key {
+    Op(&'static str, Option<Tag>, Vec<Var>),
+    Lit(Lit),
+    Trunc(Var, u32),
+}
+
+fn key(op: &Op) -> Option<(Key, Vec<Var>)> {
+    let ordered = |name, y: &Var, a: &Var, b: &Var| {
+        let inputs = vec![a.clone(), b.clone()];
+        (Key::Op(name, None, inputs), vec![y.clone()])
+    };
+    let commutative = |name, y: &Var, a: &Var, b: &Var| {
+        let mut inputs = vec![a.clone(), b.clone()];
+        inputs.sort_by(|a, b| a.name().cmp(b.name()));
+        (Key::Op(name, None, inputs), vec![y.clone()])
+    };
+    let tagged = |name, tag: &Tag, y: &Var, inputs: &[Var]| {
+        (Key::Op(name, Some(*tag), inputs.to_vec()), vec![y.clone()])
+    };
+    let key = match op {
+        Op::Zero(y, tag) => tagged("zero", tag, y, &[]),
+        Op::Hash3Zeros(y, tag) => tagged("hash3_zeros", tag, y, &[]),
+        Op::Hash4Zeros(y, tag) => tagged("hash4_zeros", tag, y, &[]),
+        Op::Hash6Zeros(y, tag) => tagged("hash6_zeros", tag, y, &[]),
+        Op::Hash8Zeros(y, tag) => tagged("hash8_zeros", tag, y, &[]),
+        Op::Lit(y, lit) => (Key::Lit(lit.clone()), vec![y.clone()]),
+        Op::Cast(y, tag, x) => tagged("cast", tag, y, &[x.clone()]),
+        Op::EqTag(y, a, b) => commutative("eq_tag", y, a, b),
+        Op::EqVal(y, a, b) => commutative("eq_val", y, a, b),
+        Op::Not(y, a) => (Key::Op("not", None, vec![a.clone()]), vec![y.clone()]),
+        Op::And(y, a, b) => commutative("and", y, a, b),
+        Op::Or(y, a, b) => commutative("or", y, a, b),
+        Op::Add(y, a, b) => commutative("add", y, a, b),
+        Op::Sub(y, a, b) => ordered("sub", y, a, b),
+        Op::Mul(y, a, b) => commutative("mul", y, a, b),
+        Op::Div(y, a, b) => ordered("div", y, a, b),
+        Op::Inv(y, a) => (Key::Op("inv", None, vec![a.clone()]), vec![y.clone()]),
+        Op::Lt(y, a, b) => ordered("lt", y, a, b),
+        Op::Trunc(y, a, n) => (Key::Trunc(a.clone(), *n), vec![y.clone()]),
+        Op::DivRem64(ys, a, b) => {
+            let inputs = vec![a.clone(), b.clone()];
+            (Key::Op("div_rem64", None, inputs), ys.to_vec())
+        }
+        Op::BitAnd(y, a, b) => commutative("bit_and", y, a, b),
+        Op::BitOr(y, a, b) => commutative("bit_or", y, a, b),
+        Op::BitXor(y, a, b) => commutative("bit_xor", y, a, b),
+        Op::Shl(y, a, b) => ordered("shl", y, a, b),
+        Op::Shr(y, a, b) => ordered("shr", y, a, b),
+        Op::Pow(y, a, b) => ordered("pow", y, a, b),
+        Op::Cons2(y, tag, xs) => tagged("cons2", tag, y, xs),
+        Op::Cons3(y, tag, xs) => tagged("cons3", tag, y, xs),
+        Op::Cons4(y, tag, xs) => tagged("cons4", tag, y, xs),
+        Op::Decons2(ys, x) => (Key::Op("decons2", None, vec![x.clone()]), ys.to_vec()),
+        Op::Decons3(ys, x) => (Key::Op("decons3", None, vec![x.clone()]), ys.to_vec()),
+        Op::Decons4(ys, x) => (Key::Op("decons4", None, vec![x.clone()]), ys.to_vec()),
+        Op::PushBinding(y, xs) => (Key::Op("push_binding", None, xs.to_vec()), vec![y.clone()]),
+        Op::PopBinding(ys, x) => (Key::Op("pop_binding", None, vec![x.clone()]), ys.to_vec()),
+        Op::Hide(y, s, p) => ordered("hide", y, s, p),
+        Op::Open(s, p, h) => (
+            Key::Op("open", None, vec![h.clone()]),
+            vec![s.clone(), p.clone()],
+        ),
+        Op::Crout(..)
+        | Op::Cproc(..)
+        | Op::Call(..)
+        | Op::Copy(..)
+        | Op::Emit(..)
+        | Op::Recv(..)
+        | Op::Unit(..) => return None,
+    };
+    Some(key)
+}
+
+/// The result of folding an operation
+enum Fold {
+    Keep,
+    Replace(Op),
+    /// The variable bound by the operation is equal to another one
+    Alias(Var, Var),
+}
+
+/// Runs the passes that follow the execution order. Since the functions are
+/// in SSA, what's known about a variable holds wherever it's in scope
+struct Forward {
+    pass: Pass,
+    /// Variables replaced by equal ones
+    subst: HashMap<Var, Var>,
+    known: HashMap<Var, Known>,
+}
+
+impl Forward {
+    fn var(&self, var: &Var) -> Var {
+        self.subst.get(var).unwrap_or(var).clone()
+    }
+
+    fn num(&self, var: &Var) -> Option<u128> {
+        match self.known.get(var) {
+            Some(Known::Num(_, num)) => Some(*num),
+            _ => None,
+        }
+    }
+
+    fn bool(&self, var: &Var) -> Option<bool> {
+        match self.known.get(var) {
+            Some(Known::Bool(b)) => Some(*b),
+            _ => None,
+        }
+    }
+
+    fn fold(&self, op: &Op) -> Fold {
+        let lit = |y: &Var, num: Option<u128>| match num {
+            Some(num) => Fold::Replace(Op::Lit(y.clone(), Lit::Num(num))),
+            None => Fold::Keep,
+        };
+        match op {
+            Op::Cast(y, tag, x) => match self.known.get(x) {
+                Some(known) if known.tag() == Some(*tag) => Fold::Alias(y.clone(), x.clone()),
+                Some(Known::Num(_, num)) if *tag == Tag::Expr(Num) => lit(y, Some(*num)),
+                Some(Known::Num(_, 0)) => Fold::Replace(Op::Zero(y.clone(), *tag)),
+                _ => Fold::Keep,
+            },
+            Op::And(y, a, b) => match (self.bool(a), self.bool(b)) {
+                (Some(true), _) => Fold::Alias(y.clone(), b.clone()),
+                (_, Some(true)) => Fold::Alias(y.clone(), a.clone()),
+                _ => Fold::Keep,
+            },
+            Op::Or(y, a, b) => match (self.bool(a), self.bool(b)) {
+                (Some(false), _) => Fold::Alias(y.clone(), b.clone()),
+                (_, Some(false)) => Fold::Alias(y.clone(), a.clone()),
+                _ => Fold::Keep,
+            },
+            // the results must fit in a `Lit::Num`, thus they are smaller than
+            // the field's modulus and there's no wrapping around
+            Op::Add(y, a, b) => lit(
+                y,
+                self.num(a)
+                    .zip(self.num(b))
+                    .and_then(|(a, b)| a.checked_add(b)),
+            ),
+            Op::Sub(y, a, b) => lit(
+                y,
+                self.num(a)
+                    .zip(self.num(b))
+                    .and_then(|(a, b)| a.checked_sub(b)),
+            ),
+            Op::Mul(y, a, b) => lit(
+                y,
+                self.num(a)
+                    .zip(self.num(b))
+                    .and_then(|(a, b)| a.checked_mul(b)),
+            ),
+            _ => Fold::Keep,
+        }
+    }
+
+    /// Records what's known about the variables bound by `op`
+    fn learn(&mut self, op: &Op) {
+        let (var, known) = match op {
+            Op::Lit(y, Lit::Num(num)) => (y, Known::Num(Tag::Expr(Num), *num)),
+            Op::Lit(y, lit) => (y, Known::Lit(lit.clone())),
+            Op::Zero(y, tag) => (y, Known::Num(*tag, 0)),
+            Op::Hash3Zeros(y, tag)
+            | Op::Hash4Zeros(y, tag)
+            | Op::Hash6Zeros(y, tag)
+            | Op::Hash8Zeros(y, tag)
+            | Op::Cons2(y, tag, _)
+            | Op::Cons3(y, tag, _)
+            | Op::Cons4(y, tag, _) => (y, Known::Tag(*tag)),
+            Op::Cast(y, tag, x) => match self.num(x) {
+                Some(num) => (y, Known::Num(*tag, num)),
+                None => (y, Known::Tag(*tag)),
+            },
+            Op::PushBinding(y, _) => (y, Known::Tag(Tag::Expr(Env))),
+            Op::Hide(y, ..) => (y, Known::Tag(Tag::Expr(Comm))),
+            Op::Add(y, ..)
+            | Op::Sub(y, ..)
+            | Op::Mul(y, ..)
+            | Op::Div(y, ..)
+            | Op::Inv(y, _)
+            | Op::Trunc(y, ..)
+            | Op::BitAnd(y, ..)
+            | Op::BitOr(y, ..)
+            | Op::BitXor(y, ..)
+            | Op::Shl(y, ..)
+            | Op::Shr(y, ..)
+            | Op::Pow(y, ..) => (y, Known::Tag(Tag::Expr(Num))),
+            Op::EqTag(y, a, b) => {
+                let tags = self.known.get(a).and_then(Known::tag);
+                let tags = tags.zip(self.known.get(b).and_then(Known::tag));
+                match tags {
+                    _ if a == b => (y, Known::Bool(true)),
+                    Some((a, b)) => (y, Known::Bool(a == b)),
+                    None => return,
+                }
+            }
+            Op::EqVal(y, a, b) => match (self.known.get(a), self.known.get(b)) {
+                _ if a == b => (y, Known::Bool(true)),
+                (Some(Known::Num(_, a)), Some(Known::Num(_, b))) => (y, Known::Bool(a == b)),
+                // different literals may have the same value, like the empty
+                // string and the root symbol
+                (Some(Known::Lit(a)), Some(Known::Lit(b))) if a == b => (y, Known::Bool(true)),
+                _ => return,
+            },
+            Op::Not(y, a) => match self.bool(a) {
+                Some(a) => (y, Known::Bool(!a)),
+                None => return,
+            },
+            Op::And(y, a, b) => match (self.bool(a), self.bool(b)) {
+                (Some(false), _) | (_, Some(false)) => (y, Known::Bool(false)),
+                (Some(true), Some(true)) => (y, Known::Bool(true)),
+                _ => return,
+            },
+            Op::Or(y, a, b) => match (self.bool(a), self.bool(b)) {
+                (Some(true), _) | (_, Some(true)) => (y, Known::Bool(true)),
+                (Some(false), Some(false)) => (y, Known::Bool(false)),
+                _ => return,
+            },
+            _ => return,
+        };
+        self.known.insert(var.clone(), known);
+    }
+
+    fn op(&mut self, op: &Op, ops: &mut Vec<Op>, exprs: &mut HashMap<Key, Vec<Var>>) {
+        let mut op = match op {
+            Op::Call(ys, func, xs) => {
+                let func = Box::new(self.pass.run(func));
+                Op::Call(ys.clone(), func, xs.clone())
+            }
+            _ => op.clone(),
+        };
+        for var in inputs_mut(&mut op) {
+            *var = self.var(var);
+        }
+        if self.pass == Pass::FoldConstants {
+            match self.fold(&op) {
+                Fold::Keep => (),
+                Fold::Replace(folded) => op = folded,
+                Fold::Alias(y, x) => {
+                    self.subst.insert(y, x);
+                    return;
+                }
+            }
+        }
+        if self.pass == Pass::EliminateCommonSubexpressions {
+            if let Some((key, ys)) = key(&op) {
+                if let Some(previous) = exprs.get(&key) {
+                    for (y, previous) in ys.into_iter().zip(previous) {
+                        self.subst.insert(y, previous.clone());
+                    }
+                    return;
+                }
+                if let Op::Cons2(y, _, xs) = &op {
+                    let key = Key::Op("decons2", None, vec![y.clone()]);
+                    exprs.entry(key).or_insert_with(|| xs.to_vec());
+                }
+                if let Op::Cons3(y, _, xs) = &op {
+                    let key = Key::Op("decons3", None, vec![y.clone()]);
+                    exprs.entry(key).or_insert_with(|| xs.to_vec());
+                }
+                if let Op::Cons4(y, _, xs) = &op {
+                    let key = Key::Op("decons4", None, vec![y.clone()]);
+                    exprs.entry(key).or_insert_with(|| xs.to_vec());
+                }
+                exprs.insert(key, ys);
+            }
+        }
+        self.learn(&op);
+        ops.push(op);
+    }
+
+    fn block(&mut self, block: &Block, mut exprs: HashMap<Key, Vec<Var>>) -> Block {
+        let mut ops = vec![];
+        let ctrl = self.seq(block, &mut ops, &mut exprs);
+        Block { ops, ctrl }
+    }
+
+    /// Pushes the operations of `block` into `ops` and returns its control. A
+    /// pruned control is replaced by the contents of the selected block
+    fn seq(
+        &mut self,
+        block: &Block,
+        ops: &mut Vec<Op>,
+        exprs: &mut HashMap<Key, Vec<Var>>,
+    ) -> Ctrl {
+        for op in &block.ops {
+            self.op(op, ops, exprs);
+        }
+        let prune = self.pass == Pass::PruneBranches;
+        match &block.ctrl {
+            Ctrl::Return(vars) => Ctrl::Return(vars.iter().map(|var| self.var(var)).collect()),
+            Ctrl::If(b, true_block, false_block) => {
+                let b = self.var(b);
+                match self.bool(&b) {
+                    Some(true) if prune => self.seq(true_block, ops, exprs),
+                    Some(false) if prune => self.seq(false_block, ops, exprs),
+                    _ => {
+                        let true_block = self.block(true_block, exprs.clone());
+                        let false_block = self.block(false_block, exprs.clone());
+                        Ctrl::if_(b, true_block, false_block)
+                    }
+                }
+            }
+            Ctrl::MatchTag(x, cases, def) => {
+                let x = self.var(x);
+                let tag = self.known.get(&x).and_then(Known::tag);
+                let selected = tag.and_then(|tag| cases.get(&tag).or(def.as_deref()));
+                match selected {
+                    Some(selected) if prune => self.seq(selected, ops, exprs),
+                    _ => {
+                        let cases: IndexMap<_, _> = cases
+                            .iter()
+                            .map(|(tag, block)| (*tag, self.block(block, exprs.clone())))
+                            .collect();
+                        let def = def
+                            .as_ref()
+                            .map(|def| Box::new(self.block(def, exprs.clone())));
+                        Ctrl::MatchTag(x, cases, def)
+                    }
+                }
+            }
+            Ctrl::MatchValue(x, lit_type, cases, def) => {
+                let x = self.var(x);
+                let lit = self.known.get(&x).and_then(Known::lit);
+                // `MatchValue` fails if the literal has the wrong type
+                let lit = lit.filter(|lit| lit.lit_type() == *lit_type);
+                let selected = lit.and_then(|lit| cases.get(&lit).or(def.as_deref()));
+                match selected {
+                    Some(selected) if prune => self.seq(selected, ops, exprs),
+                    _ => {
+                        let cases: IndexMap<_, _> = cases
+                            .iter()
+                            .map(|(lit, block)| (lit.clone(), self.block(block, exprs.clone())))
+                            .collect();
+                        let def = def
+                            .as_ref()
+                            .map(|def| Box::new(self.block(def, exprs.clone())));
+                        Ctrl::MatchValue(x, *lit_type, cases, def)
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Runs dead code elimination, which follows the reverse execution order.
+/// Since the functions are in SSA, a variable is live if it's used anywhere
+#[derive(Default)]
+struct DeadCode {
+    live: HashSet<Var>,
+}
+
+impl DeadCode {
+    fn block(&mut self, block: &Block) -> Block {
+        let ctrl = match &block.ctrl {
+            Ctrl::Return(vars) => {
+                self.live.extend(vars.iter().cloned());
+                Ctrl::Return(vars.clone())
+            }
+            Ctrl::If(b, true_block, false_block) => {
+                let true_block = self.block(true_block);
+                let false_block = self.block(false_block);
+                self.live.insert(b.clone());
+                Ctrl::if_(b.clone(), true_block, false_block)
+            }
+            Ctrl::MatchTag(x, cases, def) => {
+                let cases: IndexMap<_, _> = cases
+                    .iter()
+                    .map(|(tag, block)| (*tag, self.block(block)))
+                    .collect();
+                let def = def.as_ref().map(|def| Box::new(self.block(def)));
+                self.live.insert(x.clone());
+                Ctrl::MatchTag(x.clone(), cases, def)
+            }
+            Ctrl::MatchValue(x, lit_type, cases, def) => {
+                let cases: IndexMap<_, _> = cases
+                    .iter()
+                    .map(|(lit, block)| (lit.clone(), self.block(block)))
+                    .collect();
+                let def = def.as_ref().map(|def| Box::new(self.block(def)));
+                self.live.insert(x.clone());
+                Ctrl::MatchValue(x.clone(), *lit_type, cases, def)
+            }
+        };
+        let mut ops = Vec::with_capacity(block.ops.len());
+        for op in block.ops.iter().rev() {
+            let mut op = match op {
+                Op::Call(ys, func, xs) => {
+                    let func = Box::new(Pass::EliminateDeadCode.run(func));
+                    Op::Call(ys.clone(), func, xs.clone())
+                }
+                _ => op.clone(),
+            };
+            let used = outputs_mut(&mut op)
+                .into_iter()
+                .any(|var| self.live.contains(&*var));
+            if !used && is_pure(&op) {
+                continue;
+            }
+            outputs_mut(&mut op)
+                .into_iter()
+                .filter(|var| !self.live.contains(&**var))
+                .for_each(mark_unused);
+            self.live
+                .extend(inputs_mut(&mut op).into_iter().map(|var| var.clone()));
+            ops.push(op);
+        }
+        ops.reverse();
+        Block { ops, ctrl }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Pass;
+    use crate::{aux_func, lem::eval::eval_step};
+
+    #[test]
+    fn test_fold_and_prune() {
+        let func = aux_func!(foo(a): 1 => {
+            let one = Num(1);
+            let two = Num(2);
+            let three = add(one, two);
+            let nil_sym = Symbol("nil");
+            let nil = cast(nil_sym, Expr::Nil);
+            match nil.tag {
+                Expr::Nil => {
+                    let is_three = eq_val(three, three);
+                    if is_three {
+                        return (three)
+                    }
+                    return (a)
+                }
+                Expr::Cons => {
+                    return (a)
+                }
+            }
+        });
+        let expected = aux_func!(foo(_a): 1 => {
+            let three = Num(3);
+            return (three)
+        });
+        assert_eq!(func.optimize(&Pass::ALL), expected);
+    }
+
+    #[test]
+    fn test_common_subexpressions() {
+        let func = aux_func!(foo(a, b): 2 => {
+            let x: Expr::Cons = cons2(a, b);
+            let y: Expr::Cons = cons2(a, b);
+            let (c, d) = decons2(y);
+            let z: Expr::Cons = cons2(c, d);
+            let _inv = inv(b);
+            return (x, z)
+        });
+        let expected = aux_func!(foo(a, b): 2 => {
+            let x: Expr::Cons = cons2(a, b);
+            let _inv = inv(b);
+            return (x, x)
+        });
+        assert_eq!(func.optimize(&Pass::ALL), expected);
+    }
+
+    #[test]
+    fn test_symbol_tags() {
+        // `nil` is interned with the `Nil` tag, unlike other symbols
+        let func = aux_func!(foo(a): 1 => {
+            let nil = Symbol("nil");
+            let sym = Symbol("cons");
+            let nil_is_sym = eq_tag(nil, sym);
+            if nil_is_sym {
+                return (a)
+            }
+            match nil.tag {
+                Expr::Sym => {
+                    return (a)
+                }
+                Expr::Nil => {
+                    return (nil)
+                }
+            }
+        });
+        let expected = aux_func!(foo(_a): 1 => {
+            let nil = Symbol("nil");
+            return (nil)
+        });
+        assert_eq!(func.optimize(&Pass::ALL), expected);
+    }
+
+    #[test]
+    fn test_optimized_eval_step() {
+        let func = eval_step().optimize(&Pass::ALL);
+        func.check().unwrap();
+        assert_eq!(func.optimize(&Pass::ALL), func);
+    }
+}
@@ -196,23 +196,34 @@ impl SlotsCounter {
     }
 }
 
+impl Op {
+    /// The slots needed by `self`, which are those of the called function in
+    /// the case of a `Call`
+    pub fn count_slots(&self) -> SlotsCounter {
+        match self {
+            Op::Cons2(..) | Op::Decons2(..) | Op::PushBinding(..) | Op::PopBinding(..) => {
+                SlotsCounter::new((1, 0, 0, 0, 0))
+            }
+            Op::Cons3(..) | Op::Decons3(..) => SlotsCounter::new((0, 1, 0, 0, 0)),
+            Op::Cons4(..) | Op::Decons4(..) => SlotsCounter::new((0, 0, 1, 0, 0)),
+            Op::Hide(..) | Op::Open(..) => SlotsCounter::new((0, 0, 0, 1, 0)),
+            Op::Lt(..) => SlotsCounter::new((0, 0, 0, 0, 3)),
+            Op::Trunc(..) | Op::Pow(..) => SlotsCounter::new((0, 0, 0, 0, 1)),
+            Op::BitAnd(..) | Op::BitOr(..) | Op::BitXor(..) | Op::Shl(..) | Op::Shr(..) => {
+                SlotsCounter::new((0, 0, 0, 0, 2))
+            }
+            Op::Call(_, func, _) => func.slots_count,
+            _ => SlotsCounter::default(),
+        }
+    }
+}
+
 impl Block {
     pub fn count_slots(&self) -> SlotsCounter {
-        let ops_slots = self.ops.iter().fold(SlotsCounter::default(), |acc, op| {
-            let val = match op {
-                Op::Cons2(..) | Op::Decons2(..) | Op::PushBinding(..) | Op::PopBinding(..) => {
-                    SlotsCounter::new((1, 0, 0, 0, 0))
-                }
-                Op::Cons3(..) | Op::Decons3(..) => SlotsCounter::new((0, 1, 0, 0, 0)),
-                Op::Cons4(..) | Op::Decons4(..) => SlotsCounter::new((0, 0, 1, 0, 0)),
-                Op::Hide(..) | Op::Open(..) => SlotsCounter::new((0, 0, 0, 1, 0)),
-                Op::Lt(..) => SlotsCounter::new((0, 0, 0, 0, 3)),
-                Op::Trunc(..) => SlotsCounter::new((0, 0, 0, 0, 1)),
-                Op::Call(_, func, _) => func.slots_count,
-                _ => SlotsCounter::default(),
-            };
-            acc.add(val)
-        });
+        let ops_slots = self
+            .ops
+            .iter()
+            .fold(SlotsCounter::default(), |acc, op| acc.add(op.count_slots()));
         let ctrl_slots = match &self.ctrl {
             Ctrl::MatchTag(_, cases, def) => {
                 let init = def
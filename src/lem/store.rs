@@ -22,7 +22,8 @@ use crate::{
         StreamDispatch, StreamPause, StreamStart, Tail, Terminal, Unop,
     },
     tag::ExprTag::{
-        Char, Comm, Cons, Cproc, Env, Fun, Key, Nil, Num, Prov, Rec, Str, Sym, Thunk, U64,
+        Char, Comm, Cons, Cont, Cproc, Env, Fun, Key, Nil, Num, Prov, Rec, RecEnv, RecTop, Str,
+        Sym, Thunk, Vector, I64, U64,
     },
 };
 
@@ -388,6 +389,11 @@ impl<F: LurkField> Store<F> {
         self.intern_atom(Tag::Expr(U64), F::from_u64(u))
     }
 
+    #[inline]
+    pub fn i64(&self, i: i64) -> Ptr {
+        self.intern_atom(Tag::Expr(I64), F::from_u64(i as u64))
+    }
+
     #[inline]
     pub fn fetch_u64(&self, ptr: &Ptr) -> Option<u64> {
         match_opt!(ptr.tag(), Tag::Expr(U64) => self.fetch_f_by_val(ptr.val()).and_then(F::to_u64)?)
@@ -778,6 +784,78 @@ impl<F: LurkField> Store<F> {
         }
     }
 
+    /// Interns a sequence of pointers as a vector. Vectors are headers holding
+    /// the length, the capacity and the root of a 4-ary tree whose leaves are
+    /// the elements. The capacity is the smallest power of 4 that can fit all
+    /// the elements and missing leaves are filled with `nil`
+    pub fn intern_vector(&self, elts: Vec<Ptr>) -> Ptr {
+        let nil = self.intern_nil();
+        let len = elts.len() as u64;
+        let mut cap = 1;
+        let mut level = elts;
+        while level.len() > 1 {
+            level = level
+                .chunks(4)
+                .map(|chunk| {
+                    let mut children = [nil; 4];
+                    children[..chunk.len()].copy_from_slice(chunk);
+                    self.core.intern_tuple4(children, Tag::Expr(Vector), None)
+                })
+                .collect();
+            cap *= 4;
+        }
+        let root = level.pop().unwrap_or(nil);
+        self.core.intern_tuple4(
+            [self.num_u64(len), self.num_u64(cap), root, self.dummy()],
+            Tag::Expr(Vector),
+            None,
+        )
+    }
+
+    /// Fetches the elements of a vector
+    pub fn fetch_vector(&self, ptr: &Ptr) -> Option<Vec<Ptr>> {
+        if ptr.tag() != &Tag::Expr(Vector) {
+            return None;
+        }
+        let [len, cap, root, _] = self.core.fetch_tuple4(ptr.val().get_tuple4_idx()?)?;
+        let len = usize::try_from(self.fetch_num(len)?.to_u64()?).ok()?;
+        let mut cap = self.fetch_num(cap)?.to_u64()?;
+        let nil = self.intern_nil();
+        let mut level = vec![*root];
+        while cap > 1 {
+            let mut children = Vec::with_capacity(4 * level.len());
+            for node in level {
+                if node == nil {
+                    // subtrees made of padding only are collapsed into `nil`
+                    children.extend([nil; 4]);
+                } else {
+                    children.extend(self.core.fetch_tuple4(node.val().get_tuple4_idx()?)?);
+                }
+            }
+            level = children;
+            cap /= 4;
+        }
+        level.truncate(len);
+        Some(level)
+    }
+
+    /// Interns an error continuation that records `reason` and the offending
+    /// expression `expr`, like the ones built during evaluation
+    pub fn intern_error(&self, reason: &str, expr: Ptr) -> Ptr {
+        let reason = self.intern_string(reason);
+        self.intern_tuple2([reason, expr], Tag::Cont(ContTag::Error), None)
+    }
+
+    /// Fetches the reason and the offending expression recorded in an error
+    /// continuation. Returns `None` for the error atom, which carries neither
+    pub fn fetch_error(&self, ptr: &Ptr) -> Option<(String, Ptr)> {
+        if ptr.tag() != &Tag::Cont(ContTag::Error) {
+            return None;
+        }
+        let [reason, expr] = self.core.fetch_tuple2(ptr.val().get_tuple2_idx()?)?;
+        Some((self.fetch_string(reason)?, *expr))
+    }
+
     /// Fetches an environment
     pub fn fetch_env(&self, ptr: &Ptr) -> Option<Vec<(Ptr, Ptr)>> {
         if ptr.tag() != &Tag::Expr(Env) {
@@ -791,7 +869,18 @@ impl<F: LurkField> Store<F> {
         let mut list = vec![];
         let empty_env_val = *self.intern_empty_env().val();
         while let Some([sym, v, env]) = self.core.fetch_compact_by_val(env_val_mut) {
-            list.push((*sym, *v));
+            // the value of the top binding of a `letrec` group is tagged `RecTop`,
+            // and wrapped unless it's a recursive closure
+            if v.tag() == &Tag::Expr(RecTop) {
+                let [wrapped, _, _, member] = self.core.fetch_tuple4(v.get_tuple4_idx()?)?;
+                if member.tag() == &Tag::Expr(Num) {
+                    list.push((*sym, *wrapped));
+                } else {
+                    list.push((*sym, v.cast(Tag::Expr(Rec))));
+                }
+            } else {
+                list.push((*sym, *v));
+            }
             let env_val = env.val();
             if env_val == &empty_env_val {
                 break;
@@ -826,6 +915,7 @@ impl<F: LurkField> Store<F> {
         match syn {
             Syntax::Num(_, x) => self.num(x.into_scalar()),
             Syntax::UInt(_, x) => self.u64(x.into()),
+            Syntax::Int(_, x) => self.i64(x.into()),
             Syntax::Char(_, x) => self.char(x),
             Syntax::Symbol(_, x) => self.intern_symbol(&x),
             Syntax::String(_, x) => self.intern_string(&x),
@@ -833,6 +923,18 @@ impl<F: LurkField> Store<F> {
                 self.intern_symbol(&lurk_sym("quote")),
                 self.intern_syntax(*x),
             ]),
+            Syntax::Quasiquote(_, x) => self.list(vec![
+                self.intern_symbol(&lurk_sym("quasiquote")),
+                self.intern_syntax(*x),
+            ]),
+            Syntax::Unquote(_, x) => self.list(vec![
+                self.intern_symbol(&lurk_sym("unquote")),
+                self.intern_syntax(*x),
+            ]),
+            Syntax::UnquoteSplicing(_, x) => self.list(vec![
+                self.intern_symbol(&lurk_sym("unquote-splicing")),
+                self.intern_syntax(*x),
+            ]),
             Syntax::List(_, xs) => self.list(xs.into_iter().map(|x| self.intern_syntax(x))),
             Syntax::Improper(_, xs, y) => self.improper_list(
                 xs.into_iter().map(|x| self.intern_syntax(x)).collect(),
@@ -961,6 +1063,13 @@ impl Ptr {
                         "<Malformed U64>".into()
                     }
                 }
+                I64 => {
+                    if let Some(u) = store.fetch_f_by_val(self.val()).and_then(F::to_u64) {
+                        format!("{}s64", u as i64)
+                    } else {
+                        "<Malformed I64>".into()
+                    }
+                }
                 Fun => {
                     let Some(idx) = self.val().get_tuple4_idx() else {
                         return "<Malformed Fun>".into();
@@ -1076,11 +1185,52 @@ impl Ptr {
                         )
                     }
                 }
+                Vector => {
+                    let Some(elts) = store.fetch_vector(self) else {
+                        return "<Opaque Vector>".into();
+                    };
+                    let elts = elts
+                        .iter()
+                        .map(|p| p.fmt_to_string(store, state))
+                        .collect::<Vec<_>>();
+                    format!("<VECTOR ({})>", elts.join(" "))
+                }
+                Cont => {
+                    if self.val().get_tuple2_idx().is_none() {
+                        return "<Malformed Continuation>".into();
+                    }
+                    // a captured continuation can only be applied, so neither its
+                    // frames nor the environments they saved are shown
+                    "<CONTINUATION>".into()
+                }
+                RecEnv => {
+                    let env = self.cast(Tag::Expr(Env));
+                    format!("<REC_ENV {}>", env.fmt_to_string(store, state))
+                }
+                RecTop => {
+                    let Some(idx) = self.val().get_tuple4_idx() else {
+                        return "<Malformed RecTop>".into();
+                    };
+                    let Some([wrapped, _, _, member]) = store.core.fetch_tuple4(idx) else {
+                        return "<Opaque RecTop>".into();
+                    };
+                    if member.tag() == &Tag::Expr(Num) {
+                        wrapped.fmt_to_string(store, state)
+                    } else {
+                        self.cast(Tag::Expr(Rec)).fmt_to_string(store, state)
+                    }
+                }
             },
             Tag::Cont(t) => match t {
                 Outermost => "Outermost".into(),
                 Dummy => "Dummy".into(),
-                ContTag::Error => "Error".into(),
+                ContTag::Error => match store.fetch_error(self) {
+                    Some((reason, expr)) => format!(
+                        "Error{{ reason: {reason:?}, expr: {} }}",
+                        expr.fmt_to_string(store, state)
+                    ),
+                    None => "Error".into(),
+                },
                 Terminal => "Terminal".into(),
                 Call0 => self.fmt_cont2_to_string("Call0", "saved_env", store, state),
                 Call => {
@@ -1102,7 +1252,7 @@ impl Ptr {
                 If => self.fmt_cont2_to_string("If", "unevaled_args", store, state),
                 Let => self.fmt_cont4_to_string("Let", ("var", "saved_env", "body"), store, state),
                 LetRec => {
-                    self.fmt_cont4_to_string("LetRec", ("var", "saved_env", "body"), store, state)
+                    self.fmt_cont4_to_string("LetRec", ("var", "rest", "members_env"), store, state)
                 }
                 Emit => "Emit <CONTINUATION>".into(),
                 ContTag::Cproc => self.fmt_cont4_to_string(
@@ -1114,9 +1264,109 @@ impl Ptr {
                 StreamStart => "StreamStart".into(),
                 StreamDispatch => "StreamDispatch".into(),
                 StreamPause => "StreamPause".into(),
+                ContTag::Vector => {
+                    self.fmt_cont4_to_string("Vector", ("args", "count", "saved_env"), store, state)
+                }
+                ContTag::VectorBuild => self.fmt_cont4_to_string(
+                    "VectorBuild",
+                    ("pending", "built", "header"),
+                    store,
+                    state,
+                ),
+                ContTag::VectorRef => {
+                    self.fmt_cont3_to_string("VectorRef", ("node", "capacity"), store, state)
+                }
+                ContTag::VectorSet => self.fmt_cont4_to_string(
+                    "VectorSet",
+                    ("node", "capacity", "value"),
+                    store,
+                    state,
+                ),
+                ContTag::VectorNode => {
+                    self.fmt_cont3_to_string("VectorNode", ("position", "parent"), store, state)
+                }
+                ContTag::Catch => self.fmt_cont2_to_string("Catch", "tag", store, state),
+                ContTag::Rest => self.fmt_cont4_to_string(
+                    "Rest",
+                    ("function", "pending", "saved_env"),
+                    store,
+                    state,
+                ),
+                ContTag::RestReverse => {
+                    self.fmt_cont3_to_string("RestReverse", ("function", "reversed"), store, state)
+                }
+                ContTag::Cond => {
+                    self.fmt_cont4_to_string("Cond", ("body", "clauses", "saved_env"), store, state)
+                }
+                ContTag::And => {
+                    self.fmt_cont3_to_string("And", ("unevaled_args", "saved_env"), store, state)
+                }
+                ContTag::Or => {
+                    self.fmt_cont3_to_string("Or", ("unevaled_args", "saved_env"), store, state)
+                }
+                ContTag::Match => {
+                    self.fmt_cont3_to_string("Match", ("clauses", "saved_env"), store, state)
+                }
+                ContTag::MatchPattern => self.fmt_cont4_to_string(
+                    "MatchPattern",
+                    ("pattern", "value", "pending"),
+                    store,
+                    state,
+                ),
+                ContTag::Splice => {
+                    self.fmt_cont3_to_string("Splice", ("template", "saved_env"), store, state)
+                }
+                ContTag::StrScan => {
+                    self.fmt_cont4_to_string("StrScan", ("operator", "state", "acc"), store, state)
+                }
+                ContTag::StrBuild => self.fmt_cont2_to_string("StrBuild", "reversed", store, state),
+                ContTag::Digits => {
+                    self.fmt_cont3_to_string("Digits", ("string", "count"), store, state)
+                }
+                ContTag::Receive => self.fmt_cont4_to_string(
+                    "Receive",
+                    ("formals", "saved_env", "body"),
+                    store,
+                    state,
+                ),
+                ContTag::Values => {
+                    self.fmt_cont3_to_string("Values", ("unevaled_args", "saved_env"), store, state)
+                }
+                ContTag::EnvScan => self.fmt_cont2_to_string("EnvScan", "collected", store, state),
+                ContTag::Ternop => self.fmt_cont4_to_string(
+                    "Ternop",
+                    ("operator", "saved_env", "unevaled_args"),
+                    store,
+                    state,
+                ),
+                ContTag::Ternop2 => self.fmt_cont4_to_string(
+                    "Ternop2",
+                    ("evaled_arg", "unevaled_arg", "saved_env"),
+                    store,
+                    state,
+                ),
+                ContTag::Ternop3 => self.fmt_cont3_to_string(
+                    "Ternop3",
+                    ("evaled_arg1", "evaled_arg2"),
+                    store,
+                    state,
+                ),
+                ContTag::Quasiquote => {
+                    self.fmt_cont3_to_string("Quasiquote", ("level", "saved_env"), store, state)
+                }
+                ContTag::Quasiquote2 => self.fmt_cont4_to_string(
+                    "Quasiquote2",
+                    ("template", "level", "saved_env"),
+                    store,
+                    state,
+                ),
+                ContTag::Quasiquote3 => {
+                    self.fmt_cont2_to_string("Quasiquote3", "expanded_head", store, state)
+                }
             },
             Tag::Op1(op) => op.to_string(),
             Tag::Op2(op) => op.to_string(),
+            Tag::Op3(op) => op.to_string(),
         }
     }
 
@@ -1301,6 +1551,35 @@ mod tests {
         assert_eq!(non_nil, Some(c));
     }
 
+    #[test]
+    fn test_vector() {
+        let store = Store::<Fr>::default();
+        let state = initial_lurk_state();
+
+        let vector = store.intern_vector(vec![]);
+        assert_eq!(vector.fmt_to_string(&store, state), "<VECTOR ()>");
+        assert!(store.fetch_vector(&vector).unwrap().is_empty());
+
+        let a = store.char('a');
+        let b = store.char('b');
+        let vector = store.intern_vector(vec![a, b]);
+        assert_eq!(vector.fmt_to_string(&store, state), "<VECTOR ('a' 'b')>");
+
+        // lengths around the capacities of trees with up to 3 levels
+        let nil = store.intern_nil();
+        for len in [1, 3, 4, 5, 16, 17, 63, 64] {
+            let elts = (0..len).map(|i| store.num_u64(i)).collect::<Vec<_>>();
+            let vector = store.intern_vector(elts.clone());
+            assert_eq!(store.fetch_vector(&vector).unwrap(), elts);
+            // `nil` elements aren't mistaken for padding
+            let nils = vec![nil; len as usize];
+            let vector = store.intern_vector(nils.clone());
+            assert_eq!(store.fetch_vector(&vector).unwrap(), nils);
+        }
+
+        assert!(store.fetch_vector(&a).is_none());
+    }
+
     #[test]
     fn test_basic_hashing() {
         let store = Store::<Fr>::default();
@@ -1424,6 +1703,10 @@ mod tests {
                 Pos::No,
                 crate::UInt::U64(store.expect_f(*idx).to_u64_unchecked()),
             ),
+            (Tag::Expr(ExprTag::I64), IVal::Atom(idx)) => Syntax::Int(
+                Pos::No,
+                crate::Int::I64(store.expect_f(*idx).to_u64_unchecked() as i64),
+            ),
             (Tag::Expr(ExprTag::Sym | ExprTag::Key), IVal::Atom(_) | IVal::Tuple2(_)) => {
                 Syntax::Symbol(Pos::No, store.fetch_symbol(&ptr).unwrap().into())
             }
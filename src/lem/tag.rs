@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     field::LurkField,
-    tag::{ContTag, ExprTag, Op1, Op2, Tag as TagTrait},
+    tag::{ContTag, ExprTag, Op1, Op2, Op3, Tag as TagTrait},
 };
 
 /// The LEM `Tag` is a wrapper around other types that are used as tags
@@ -13,6 +13,7 @@ pub enum Tag {
     Cont(ContTag),
     Op1(Op1),
     Op2(Op2),
+    Op3(Op3),
 }
 
 impl TryFrom<u16> for Tag {
@@ -27,6 +28,8 @@ impl TryFrom<u16> for Tag {
             Ok(Tag::Op1(tag))
         } else if let Ok(tag) = Op2::try_from(val) {
             Ok(Tag::Op2(tag))
+        } else if let Ok(tag) = Op3::try_from(val) {
+            Ok(Tag::Op3(tag))
         } else {
             bail!("Invalid u16 for Tag: {val}")
         }
@@ -40,6 +43,7 @@ impl From<Tag> for u16 {
             Tag::Cont(tag) => tag.into(),
             Tag::Op1(tag) => tag.into(),
             Tag::Op2(tag) => tag.into(),
+            Tag::Op3(tag) => tag.into(),
         }
     }
 }
@@ -62,18 +66,20 @@ impl Tag {
             Self::Cont(tag) => tag.to_field(),
             Self::Op1(tag) => tag.to_field(),
             Self::Op2(tag) => tag.to_field(),
+            Self::Op3(tag) => tag.to_field(),
         }
     }
 }
 
 impl std::fmt::Display for Tag {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        use Tag::{Cont, Expr, Op1, Op2};
+        use Tag::{Cont, Expr, Op1, Op2, Op3};
         match self {
             Expr(tag) => write!(f, "expr.{}", tag),
             Cont(tag) => write!(f, "cont.{}", tag),
             Op1(tag) => write!(f, "op1.{}", tag),
             Op2(tag) => write!(f, "op2.{}", tag),
+            Op3(tag) => write!(f, "op3.{}", tag),
         }
     }
 }
@@ -1,4 +1,5 @@
 use expect_test::{expect, Expect};
+use ff::Field;
 use halo2curves::bn256::Fr;
 
 use crate::{
@@ -7,14 +8,17 @@ use crate::{
     lang::{Coproc, Lang},
     lem::{
         eval::{
-            evaluate_simple, make_cprocs_funcs_from_lang, make_eval_step_from_config, EvalConfig,
+            evaluate, evaluate_simple, make_cprocs_funcs_from_lang, make_eval_step_from_config,
+            EvalConfig,
         },
+        optimize::Pass,
         pointers::Ptr,
         store::Store,
         Tag,
     },
+    proof::tests::error_cont,
     state::{State, StateRcCell},
-    tag::Op,
+    tag::{ContTag, ExprTag, Op},
 };
 
 fn test_aux<C: Coprocessor<Fr>>(
@@ -120,6 +124,16 @@ fn do_test<C: Coprocessor<Fr>>(
         let cprocs = make_cprocs_funcs_from_lang(lang);
         evaluate_simple(Some((&func, &cprocs, lang)), *expr, s, limit, &t1).unwrap()
     };
+
+    // the optimization passes must not change the results of the evaluation
+    let (t3, t4) = pair_terminals();
+    let func = make_eval_step_from_config(&EvalConfig::new_ivc(lang)).optimize(&Pass::ALL);
+    let cprocs = make_cprocs_funcs_from_lang(lang);
+    let (optimized_output, optimized_iterations) =
+        evaluate_simple(Some((&func, &cprocs, lang)), *expr, s, limit, &t3).unwrap();
+    assert_eq!(output, optimized_output);
+    assert_eq!(iterations, optimized_iterations);
+
     let new_expr = output[0];
     let new_env = output[1];
     let new_cont = output[2];
@@ -136,12 +150,27 @@ fn do_test<C: Coprocessor<Fr>>(
         assert!(s.ptr_eq(&expected_env, &new_env));
     }
     if let Some(expected_cont) = expected_cont {
-        assert_eq!(expected_cont, new_cont);
+        // error continuations also record the reason and the offending expression
+        if let Some((expected_reason, expected_expr)) = s.fetch_error(&expected_cont) {
+            let (reason, expr) = s
+                .fetch_error(&new_cont)
+                .expect("expected an error continuation");
+            assert_eq!(expected_reason, reason);
+            assert!(
+                s.ptr_eq(&expected_expr, &expr),
+                "assertion failed {} != {}",
+                expected_expr.fmt_to_string_simple(s),
+                expr.fmt_to_string_simple(s)
+            );
+        } else {
+            assert_eq!(expected_cont, new_cont);
+        }
     } else {
         assert_eq!(s.cont_terminal(), new_cont);
     }
+    let emitted = t2.collect();
+    assert_eq!(emitted, t4.collect());
     if let Some(expected_emitted) = expected_emitted {
-        let emitted = t2.collect();
         assert_eq!(expected_emitted.len(), emitted.len());
 
         assert!(expected_emitted
@@ -555,7 +584,7 @@ fn evaluate_quotient_divide_by_zero() {
     let s = &Store::<Fr>::default();
     let expr = "(/ 21 0)";
 
-    let error = s.cont_error();
+    let error = error_cont(s, "division by zero", "0");
     test_aux::<Coproc<Fr>>(s, expr, None, None, Some(error), None, &expect!["3"], &None);
 }
 
@@ -701,7 +730,7 @@ fn evaluate_let_empty_error() {
     let s = &Store::<Fr>::default();
     let expr = "(let)";
 
-    let error = s.cont_error();
+    let error = error_cont(s, "malformed expression", expr);
     test_aux::<Coproc<Fr>>(s, expr, None, None, Some(error), None, &expect!["1"], &None);
 }
 
@@ -710,7 +739,7 @@ fn evaluate_let_empty_body_error() {
     let s = &Store::<Fr>::default();
     let expr = "(let ((a 1)))";
 
-    let error = s.cont_error();
+    let error = error_cont(s, "malformed expression", expr);
     test_aux::<Coproc<Fr>>(s, expr, None, None, Some(error), None, &expect!["1"], &None);
 }
 
@@ -719,7 +748,7 @@ fn evaluate_letrec_empty_error() {
     let s = &Store::<Fr>::default();
     let expr = "(letrec)";
 
-    let error = s.cont_error();
+    let error = error_cont(s, "malformed expression", expr);
     test_aux::<Coproc<Fr>>(s, expr, None, None, Some(error), None, &expect!["1"], &None);
 }
 
@@ -728,7 +757,7 @@ fn evaluate_letrec_empty_body_error() {
     let s = &Store::<Fr>::default();
     let expr = "(letrec ((a 1)))";
 
-    let error = s.cont_error();
+    let error = error_cont(s, "malformed expression", expr);
     test_aux::<Coproc<Fr>>(s, expr, None, None, Some(error), None, &expect!["1"], &None);
 }
 
@@ -1078,7 +1107,7 @@ fn evaluate_multiple_letrec_bindings() {
         None,
         Some(terminal),
         None,
-        &expect!["19"],
+        &expect!["18"],
         &None,
     );
 }
@@ -1099,7 +1128,7 @@ fn evaluate_multiple_letrec_bindings_referencing() {
         None,
         Some(terminal),
         None,
-        &expect!["25"],
+        &expect!["24"],
         &None,
     );
 }
@@ -1131,7 +1160,160 @@ fn evaluate_multiple_letrec_bindings_recursive() {
         None,
         Some(terminal),
         None,
-        &expect!["144"],
+        &expect!["142"],
+        &None,
+    );
+}
+
+#[test]
+fn evaluate_mutually_recursive_letrec_bindings() {
+    let s = &Store::<Fr>::default();
+    let expr = "(letrec ((even (lambda (n)
+                                  (if (= 0 n)
+                                      t
+                                      (odd (- n 1)))))
+                           (odd (lambda (n)
+                                  (if (= 0 n)
+                                      nil
+                                      (even (- n 1))))))
+                   (even 2))";
+
+    let expected = s.intern_lurk_symbol("t");
+    let terminal = s.cont_terminal();
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr,
+        Some(expected),
+        None,
+        Some(terminal),
+        None,
+        &expect!["34"],
+        &None,
+    );
+}
+
+#[test]
+fn evaluate_letrec_forward_references() {
+    let s = &Store::<Fr>::default();
+    let expr = "(letrec ((a (lambda (n) (c n)))
+                           (b (lambda (n) n))
+                           (c (lambda (n) (b n))))
+                   (a 1))";
+
+    let expected = s.num_u64(1);
+    let terminal = s.cont_terminal();
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr,
+        Some(expected),
+        None,
+        Some(terminal),
+        None,
+        &expect!["14"],
+        &None,
+    );
+}
+
+#[test]
+fn evaluate_letrec_forward_references_in_long_lookups() {
+    let s = &Store::<Fr>::default();
+    // `a` is looked up from `i` past more bindings than a single iteration
+    // goes through, and still sees `i` afterwards
+    let expr = "(letrec ((count (lambda (n) (if (= 0 n) 0 (+ 1 (count (- n 1)))))))
+                  (letrec ((a (lambda (n) (if (= 0 n) (count 3) (i (- n 1)))))
+                           (b (lambda (n) n))
+                           (c (lambda (n) n))
+                           (d (lambda (n) n))
+                           (e (lambda (n) n))
+                           (f (lambda (n) n))
+                           (g (lambda (n) n))
+                           (h (lambda (n) n))
+                           (i (lambda (n) (a n))))
+                    (i 2)))";
+
+    let expected = s.num_u64(3);
+    let terminal = s.cont_terminal();
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr,
+        Some(expected),
+        None,
+        Some(terminal),
+        None,
+        &expect!["105"],
+        &None,
+    );
+}
+
+#[test]
+fn evaluate_letrec_group_shadows_outer_bindings() {
+    let s = &Store::<Fr>::default();
+    let expr = "(let ((odd (lambda (n) 42)))
+                  (letrec ((even (lambda (n)
+                                   (if (= 0 n)
+                                       t
+                                       (odd (- n 1)))))
+                           (odd (lambda (n)
+                                  (if (= 0 n)
+                                      nil
+                                      (even (- n 1))))))
+                    (even 3)))";
+
+    let expected = s.intern_nil();
+    let terminal = s.cont_terminal();
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr,
+        Some(expected),
+        None,
+        Some(terminal),
+        None,
+        &expect!["47"],
+        &None,
+    );
+}
+
+#[test]
+fn evaluate_letrec_forward_reference_to_value() {
+    let s = &Store::<Fr>::default();
+    let expr = "(letrec ((f (lambda () (g)))
+                           (k 10)
+                           (g (lambda () k)))
+                   (f))";
+
+    let expected = s.num_u64(10);
+    let terminal = s.cont_terminal();
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr,
+        Some(expected),
+        None,
+        Some(terminal),
+        None,
+        &expect!["9"],
+        &None,
+    );
+}
+
+#[test]
+fn evaluate_letrec_member_closed_over_another_env() {
+    let s = &Store::<Fr>::default();
+    // `inc` is a closure over the environment of the call to `add`, which it
+    // must keep instead of closing over the group
+    let expr = "(letrec ((add (lambda (a b) (+ a b)))
+                           (inc (add 1)))
+                   (inc 2))";
+
+    let expected = s.num_u64(3);
+    let terminal = s.cont_terminal();
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr,
+        Some(expected),
+        None,
+        Some(terminal),
+        None,
+        &expect!["12"],
         &None,
     );
 }
@@ -1302,7 +1484,7 @@ fn evaluate_zero_arg_lambda() {
             None,
             Some(terminal),
             None,
-            &expect!["9"],
+            &expect!["8"],
             &None,
         );
     }
@@ -1314,7 +1496,8 @@ fn evaluate_zero_arg_lambda_variants() {
         let s = &Store::<Fr>::default();
         let expr = "((lambda () 123) 1)";
 
-        let error = s.cont_error();
+        let fun = s.intern_fun(s.intern_nil(), s.num_u64(123), s.intern_empty_env());
+        let error = s.intern_error("arity mismatch", fun);
         test_aux::<Coproc<Fr>>(s, expr, None, None, Some(error), None, &expect!["2"], &None);
     }
 }
@@ -1358,7 +1541,7 @@ fn evaluate_make_tree() {
             None,
             Some(terminal),
             None,
-            &expect!["397"],
+            &expect!["393"],
             &None,
         );
     }
@@ -1424,7 +1607,7 @@ fn evaluate_map_tree_numequal_bug() {
                                                   (map-tree f (cdr tree)))))))
                        (map-tree (lambda (x) (+ 1 x)) '((1 . 2) . (3 . 4))))";
         let expected = s.intern_nil();
-        let error = s.cont_error();
+        let error = error_cont(s, "non-numeric argument", "nil");
         test_aux::<Coproc<Fr>>(
             s,
             expr,
@@ -1467,7 +1650,7 @@ fn env_lost_bug() {
             None,
             Some(terminal),
             None,
-            &expect!["18"],
+            &expect!["16"],
             &None,
         );
     }
@@ -1492,7 +1675,7 @@ fn dont_discard_rest_env() {
             None,
             Some(terminal),
             None,
-            &expect!["14"],
+            &expect!["12"],
             &None,
         );
     }
@@ -1509,7 +1692,6 @@ fn test_str_car_cdr_cons() {
     let empty = s.intern_string("");
     let nil = s.intern_nil();
     let terminal = s.cont_terminal();
-    let error = s.cont_error();
 
     test_aux::<Coproc<Fr>>(
         s,
@@ -1576,7 +1758,7 @@ fn test_str_car_cdr_cons() {
         r"(strcons #\a #\b)",
         None,
         None,
-        Some(error),
+        Some(error_cont(s, "invalid argument", r"#\b")),
         None,
         &expect!["3"],
         &None,
@@ -1586,7 +1768,7 @@ fn test_str_car_cdr_cons() {
         r#"(strcons "a" "b")"#,
         None,
         None,
-        Some(error),
+        Some(error_cont(s, "invalid argument", r#""b""#)),
         None,
         &expect!["3"],
         &None,
@@ -1596,7 +1778,7 @@ fn test_str_car_cdr_cons() {
         r#"(strcons 1 2)"#,
         None,
         None,
-        Some(error),
+        Some(error_cont(s, "invalid argument", "2")),
         None,
         &expect!["3"],
         &None,
@@ -1606,7 +1788,7 @@ fn test_str_car_cdr_cons() {
         r#"(strcons)"#,
         None,
         None,
-        Some(error),
+        Some(error_cont(s, "arity mismatch", "(strcons)")),
         None,
         &expect!["1"],
         &None,
@@ -1616,7 +1798,7 @@ fn test_str_car_cdr_cons() {
 #[test]
 fn test_one_arg_cons_error() {
     let s = &Store::<Fr>::default();
-    let error = s.cont_error();
+    let error = error_cont(s, "arity mismatch", r#"(cons "")"#);
     test_aux::<Coproc<Fr>>(
         s,
         r#"(cons "")"#,
@@ -1666,13 +1848,12 @@ fn test_cdr_nil() {
 #[test]
 fn test_car_cdr_invalid_tag_error_sym() {
     let s = &Store::<Fr>::default();
-    let error = s.cont_error();
     test_aux::<Coproc<Fr>>(
         s,
         r#"(car 'car)"#,
         None,
         None,
-        Some(error),
+        Some(error_cont(s, "car of non-list", "car")),
         None,
         &expect!["2"],
         &None,
@@ -1682,7 +1863,7 @@ fn test_car_cdr_invalid_tag_error_sym() {
         r#"(cdr 'car)"#,
         None,
         None,
-        Some(error),
+        Some(error_cont(s, "cdr of non-list", "car")),
         None,
         &expect!["2"],
         &None,
@@ -1692,13 +1873,12 @@ fn test_car_cdr_invalid_tag_error_sym() {
 #[test]
 fn test_car_cdr_invalid_tag_error_char() {
     let s = &Store::<Fr>::default();
-    let error = s.cont_error();
     test_aux::<Coproc<Fr>>(
         s,
         r"(car #\a)",
         None,
         None,
-        Some(error),
+        Some(error_cont(s, "car of non-list", r"#\a")),
         None,
         &expect!["2"],
         &None,
@@ -1708,7 +1888,7 @@ fn test_car_cdr_invalid_tag_error_char() {
         r"(cdr #\a)",
         None,
         None,
-        Some(error),
+        Some(error_cont(s, "cdr of non-list", r"#\a")),
         None,
         &expect!["2"],
         &None,
@@ -1718,13 +1898,12 @@ fn test_car_cdr_invalid_tag_error_char() {
 #[test]
 fn test_car_cdr_invalid_tag_error_num() {
     let s = &Store::<Fr>::default();
-    let error = s.cont_error();
     test_aux::<Coproc<Fr>>(
         s,
         r#"(car 42)"#,
         None,
         None,
-        Some(error),
+        Some(error_cont(s, "car of non-list", "42")),
         None,
         &expect!["2"],
         &None,
@@ -1734,7 +1913,7 @@ fn test_car_cdr_invalid_tag_error_num() {
         r#"(cdr 42)"#,
         None,
         None,
-        Some(error),
+        Some(error_cont(s, "cdr of non-list", "42")),
         None,
         &expect!["2"],
         &None,
@@ -1744,13 +1923,14 @@ fn test_car_cdr_invalid_tag_error_num() {
 #[test]
 fn test_car_cdr_invalid_tag_error_lambda() {
     let s = &Store::<Fr>::default();
-    let error = s.cont_error();
+    let x = s.intern_user_symbol("x");
+    let fun = s.intern_fun(s.list(vec![x]), x, s.intern_empty_env());
     test_aux::<Coproc<Fr>>(
         s,
         r#"(car (lambda (x) x))"#,
         None,
         None,
-        Some(error),
+        Some(s.intern_error("car of non-list", fun)),
         None,
         &expect!["2"],
         &None,
@@ -1760,7 +1940,7 @@ fn test_car_cdr_invalid_tag_error_lambda() {
         r#"(cdr (lambda (x) x))"#,
         None,
         None,
-        Some(error),
+        Some(s.intern_error("cdr of non-list", fun)),
         None,
         &expect!["2"],
         &None,
@@ -1967,7 +2147,7 @@ fn commit_nil() {
 fn commit_error() {
     let s = &Store::<Fr>::default();
     let expr = "(commit 123 456)";
-    let error = s.cont_error();
+    let error = error_cont(s, "arity mismatch", expr);
     test_aux::<Coproc<Fr>>(s, expr, None, None, Some(error), None, &expect!["1"], &None);
 }
 
@@ -1975,7 +2155,7 @@ fn commit_error() {
 fn open_error() {
     let s = &Store::<Fr>::default();
     let expr = "(open 123 456)";
-    let error = s.cont_error();
+    let error = error_cont(s, "arity mismatch", expr);
     test_aux::<Coproc<Fr>>(s, expr, None, None, Some(error), None, &expect!["1"], &None);
 }
 
@@ -1983,7 +2163,7 @@ fn open_error() {
 fn secret_error() {
     let s = &Store::<Fr>::default();
     let expr = "(secret 123 456)";
-    let error = s.cont_error();
+    let error = error_cont(s, "arity mismatch", expr);
     test_aux::<Coproc<Fr>>(s, expr, None, None, Some(error), None, &expect!["1"], &None);
 }
 
@@ -1991,7 +2171,7 @@ fn secret_error() {
 fn num_error() {
     let s = &Store::<Fr>::default();
     let expr = "(num 123 456)";
-    let error = s.cont_error();
+    let error = error_cont(s, "arity mismatch", expr);
     test_aux::<Coproc<Fr>>(s, expr, None, None, Some(error), None, &expect!["1"], &None);
 }
 
@@ -1999,7 +2179,7 @@ fn num_error() {
 fn comm_error() {
     let s = &Store::<Fr>::default();
     let expr = "(comm 123 456)";
-    let error = s.cont_error();
+    let error = error_cont(s, "arity mismatch", expr);
     test_aux::<Coproc<Fr>>(s, expr, None, None, Some(error), None, &expect!["1"], &None);
 }
 
@@ -2007,7 +2187,7 @@ fn comm_error() {
 fn char_error() {
     let s = &Store::<Fr>::default();
     let expr = "(char 123 456)";
-    let error = s.cont_error();
+    let error = error_cont(s, "arity mismatch", expr);
     test_aux::<Coproc<Fr>>(s, expr, None, None, Some(error), None, &expect!["1"], &None);
 }
 
@@ -2214,14 +2394,22 @@ fn num_invalid_tag() {
     let expr = "(num (quote x))";
     let expr1 = "(num \"asdf\")";
     let expr2 = "(num '(1))";
-    let error = s.cont_error();
-    test_aux::<Coproc<Fr>>(s, expr, None, None, Some(error), None, &expect!["2"], &None);
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr,
+        None,
+        None,
+        Some(error_cont(s, "non-numeric argument", "x")),
+        None,
+        &expect!["2"],
+        &None,
+    );
     test_aux::<Coproc<Fr>>(
         s,
         expr1,
         None,
         None,
-        Some(error),
+        Some(error_cont(s, "non-numeric argument", r#""asdf""#)),
         None,
         &expect!["2"],
         &None,
@@ -2231,7 +2419,7 @@ fn num_invalid_tag() {
         expr2,
         None,
         None,
-        Some(error),
+        Some(error_cont(s, "non-numeric argument", "(1)")),
         None,
         &expect!["2"],
         &None,
@@ -2244,14 +2432,22 @@ fn comm_invalid_tag() {
     let expr = "(comm (quote x))";
     let expr1 = "(comm \"asdf\")";
     let expr2 = "(comm '(1))";
-    let error = s.cont_error();
-    test_aux::<Coproc<Fr>>(s, expr, None, None, Some(error), None, &expect!["2"], &None);
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr,
+        None,
+        None,
+        Some(error_cont(s, "non-numeric argument", "x")),
+        None,
+        &expect!["2"],
+        &None,
+    );
     test_aux::<Coproc<Fr>>(
         s,
         expr1,
         None,
         None,
-        Some(error),
+        Some(error_cont(s, "non-numeric argument", r#""asdf""#)),
         None,
         &expect!["2"],
         &None,
@@ -2261,7 +2457,7 @@ fn comm_invalid_tag() {
         expr2,
         None,
         None,
-        Some(error),
+        Some(error_cont(s, "non-numeric argument", "(1)")),
         None,
         &expect!["2"],
         &None,
@@ -2274,14 +2470,22 @@ fn char_invalid_tag() {
     let expr = "(char (quote x))";
     let expr1 = "(char \"asdf\")";
     let expr2 = "(char '(1))";
-    let error = s.cont_error();
-    test_aux::<Coproc<Fr>>(s, expr, None, None, Some(error), None, &expect!["2"], &None);
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr,
+        None,
+        None,
+        Some(error_cont(s, "non-numeric argument", "x")),
+        None,
+        &expect!["2"],
+        &None,
+    );
     test_aux::<Coproc<Fr>>(
         s,
         expr1,
         None,
         None,
-        Some(error),
+        Some(error_cont(s, "non-numeric argument", r#""asdf""#)),
         None,
         &expect!["2"],
         &None,
@@ -2291,7 +2495,7 @@ fn char_invalid_tag() {
         expr2,
         None,
         None,
-        Some(error),
+        Some(error_cont(s, "non-numeric argument", "(1)")),
         None,
         &expect!["2"],
         &None,
@@ -2328,7 +2532,7 @@ fn open_opaque_commit() {
 fn open_wrong_type() {
     let s = &Store::<Fr>::default();
     let expr = "(open 'asdf)";
-    let error = s.cont_error();
+    let error = error_cont(s, "invalid argument", "asdf");
     test_aux::<Coproc<Fr>>(s, expr, None, None, Some(error), None, &expect!["2"], &None);
 }
 
@@ -2336,7 +2540,7 @@ fn open_wrong_type() {
 fn secret_wrong_type() {
     let s = &Store::<Fr>::default();
     let expr = "(secret 'asdf)";
-    let error = s.cont_error();
+    let error = error_cont(s, "invalid argument", "asdf");
     test_aux::<Coproc<Fr>>(s, expr, None, None, Some(error), None, &expect!["2"], &None);
 }
 
@@ -2732,7 +2936,7 @@ fn test_eval_env_regression() {
     let expr = "(let ((a 1)) (eval 'a))";
     let expr2 = "(let ((a 1)) (eval 'a (current-env)))";
     let res = s.num_u64(1);
-    let error = s.cont_error();
+    let error = error_cont(s, "unbound variable", "a");
     let terminal = s.cont_terminal();
 
     test_aux::<Coproc<Fr>>(s, expr, None, None, Some(error), None, &expect!["5"], &None);
@@ -2878,7 +3082,7 @@ fn test_u64_div() {
     let expr3 = "(/ 100u64 0u64)";
 
     let terminal = s.cont_terminal();
-    let error = s.cont_error();
+    let error = error_cont(s, "division by zero", "0u64");
 
     test_aux::<Coproc<Fr>>(
         s,
@@ -2925,7 +3129,7 @@ fn test_u64_mod() {
     let expr3 = "(% 100u64 0u64)";
 
     let terminal = s.cont_terminal();
-    let error = s.cont_error();
+    let error = error_cont(s, "division by zero", "0u64");
 
     test_aux::<Coproc<Fr>>(
         s,
@@ -2967,15 +3171,22 @@ fn test_num_mod() {
     let expr2 = "(% 100 3u64)";
     let expr3 = "(% 100u64 3)";
 
-    let error = s.cont_error();
-
-    test_aux::<Coproc<Fr>>(s, expr, None, None, Some(error), None, &expect!["3"], &None);
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr,
+        None,
+        None,
+        Some(error_cont(s, "invalid argument", "100")),
+        None,
+        &expect!["3"],
+        &None,
+    );
     test_aux::<Coproc<Fr>>(
         s,
         expr2,
         None,
         None,
-        Some(error),
+        Some(error_cont(s, "invalid argument", "100")),
         None,
         &expect!["3"],
         &None,
@@ -2985,7 +3196,7 @@ fn test_num_mod() {
         expr3,
         None,
         None,
-        Some(error),
+        Some(error_cont(s, "invalid argument", "3")),
         None,
         &expect!["3"],
         &None,
@@ -2993,33 +3204,28 @@ fn test_num_mod() {
 }
 
 #[test]
-fn test_u64_comp() {
+fn test_u64_bitwise() {
     let s = &Store::<Fr>::default();
 
-    let expr = "(< 0u64 1u64)";
-    let expr2 = "(< 1u64 0u64)";
-    let expr3 = "(<= 0u64 1u64)";
-    let expr4 = "(<= 1u64 0u64)";
-
-    let expr5 = "(> 0u64 1u64)";
-    let expr6 = "(> 1u64 0u64)";
-    let expr7 = "(>= 0u64 1u64)";
-    let expr8 = "(>= 1u64 0u64)";
-
-    let expr9 = "(<= 0u64 0u64)";
-    let expr10 = "(>= 0u64 0u64)";
-
-    let expr11 = "(= 0u64 0u64)";
-    let expr12 = "(= 0u64 1u64)";
-
-    let t = s.intern_lurk_symbol("t");
-    let nil = s.intern_nil();
+    let expr = "(logand 12u64 10u64)";
+    let expr2 = "(logor 12u64 10u64)";
+    let expr3 = "(logxor 12u64 10u64)";
+    let expr4 = "(lognot 0u64)";
+    let expr5 = "(lognot 18446744073709551610u64)";
+    let expr6 = "(logand 12 10u64)";
+    let expr7 = "(lognot 1)";
+
+    let res = s.u64(8);
+    let res2 = s.u64(14);
+    let res3 = s.u64(6);
+    let res4 = s.u64(u64::MAX);
+    let res5 = s.u64(5);
     let terminal = s.cont_terminal();
 
     test_aux::<Coproc<Fr>>(
         s,
         expr,
-        Some(t),
+        Some(res),
         None,
         Some(terminal),
         None,
@@ -3029,7 +3235,7 @@ fn test_u64_comp() {
     test_aux::<Coproc<Fr>>(
         s,
         expr2,
-        Some(nil),
+        Some(res2),
         None,
         Some(terminal),
         None,
@@ -3039,7 +3245,7 @@ fn test_u64_comp() {
     test_aux::<Coproc<Fr>>(
         s,
         expr3,
-        Some(t),
+        Some(res3),
         None,
         Some(terminal),
         None,
@@ -3049,30 +3255,29 @@ fn test_u64_comp() {
     test_aux::<Coproc<Fr>>(
         s,
         expr4,
-        Some(nil),
+        Some(res4),
         None,
         Some(terminal),
         None,
-        &expect!["3"],
+        &expect!["2"],
         &None,
     );
-
     test_aux::<Coproc<Fr>>(
         s,
         expr5,
-        Some(nil),
+        Some(res5),
         None,
         Some(terminal),
         None,
-        &expect!["3"],
+        &expect!["2"],
         &None,
     );
     test_aux::<Coproc<Fr>>(
         s,
         expr6,
-        Some(t),
         None,
-        Some(terminal),
+        None,
+        Some(error_cont(s, "invalid argument", "12")),
         None,
         &expect!["3"],
         &None,
@@ -3080,7 +3285,39 @@ fn test_u64_comp() {
     test_aux::<Coproc<Fr>>(
         s,
         expr7,
-        Some(nil),
+        None,
+        None,
+        Some(error_cont(s, "invalid argument", "1")),
+        None,
+        &expect!["2"],
+        &None,
+    );
+}
+
+#[test]
+fn test_u64_shifts() {
+    let s = &Store::<Fr>::default();
+
+    let expr = "(shl 3u64 4u64)";
+    let expr2 = "(shr 48u64 4u64)";
+    let expr3 = "(shl 18446744073709551615u64 63u64)";
+    let expr4 = "(shr 18446744073709551615u64 63u64)";
+    let expr5 = "(shl 1u64 64u64)";
+    let expr6 = "(shr 1u64 0u64)";
+    let expr7 = "(shl 1 2u64)";
+
+    let res = s.u64(48);
+    let res2 = s.u64(3);
+    let res3 = s.u64(1 << 63);
+    let res4 = s.u64(1);
+    let res5 = s.u64(0);
+    let terminal = s.cont_terminal();
+    let error = error_cont(s, "invalid argument", "1");
+
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr,
+        Some(res),
         None,
         Some(terminal),
         None,
@@ -3089,19 +3326,18 @@ fn test_u64_comp() {
     );
     test_aux::<Coproc<Fr>>(
         s,
-        expr8,
-        Some(t),
+        expr2,
+        Some(res2),
         None,
         Some(terminal),
         None,
         &expect!["3"],
         &None,
     );
-
     test_aux::<Coproc<Fr>>(
         s,
-        expr9,
-        Some(t),
+        expr3,
+        Some(res3),
         None,
         Some(terminal),
         None,
@@ -3110,19 +3346,18 @@ fn test_u64_comp() {
     );
     test_aux::<Coproc<Fr>>(
         s,
-        expr10,
-        Some(t),
+        expr4,
+        Some(res4),
         None,
         Some(terminal),
         None,
         &expect!["3"],
         &None,
     );
-
     test_aux::<Coproc<Fr>>(
         s,
-        expr11,
-        Some(t),
+        expr5,
+        Some(res5),
         None,
         Some(terminal),
         None,
@@ -3131,39 +3366,2163 @@ fn test_u64_comp() {
     );
     test_aux::<Coproc<Fr>>(
         s,
-        expr12,
-        Some(nil),
+        expr6,
+        Some(res4),
         None,
         Some(terminal),
         None,
         &expect!["3"],
         &None,
     );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr7,
+        None,
+        None,
+        Some(error),
+        None,
+        &expect!["3"],
+        &None,
+    );
 }
 
 #[test]
-fn test_u64_conversion() {
+fn test_u64_comp() {
     let s = &Store::<Fr>::default();
 
-    let expr = "(+ 0 1u64)";
-    let expr2 = "(num 1u64)";
+    let expr = "(< 0u64 1u64)";
+    let expr2 = "(< 1u64 0u64)";
+    let expr3 = "(<= 0u64 1u64)";
+    let expr4 = "(<= 1u64 0u64)";
+
+    let expr5 = "(> 0u64 1u64)";
+    let expr6 = "(> 1u64 0u64)";
+    let expr7 = "(>= 0u64 1u64)";
+    let expr8 = "(>= 1u64 0u64)";
+
+    let expr9 = "(<= 0u64 0u64)";
+    let expr10 = "(>= 0u64 0u64)";
+
+    let expr11 = "(= 0u64 0u64)";
+    let expr12 = "(= 0u64 1u64)";
+
+    let t = s.intern_lurk_symbol("t");
+    let nil = s.intern_nil();
+    let terminal = s.cont_terminal();
+
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr,
+        Some(t),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr2,
+        Some(nil),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr3,
+        Some(t),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr4,
+        Some(nil),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr5,
+        Some(nil),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr6,
+        Some(t),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr7,
+        Some(nil),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr8,
+        Some(t),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr9,
+        Some(t),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr10,
+        Some(t),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr11,
+        Some(t),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr12,
+        Some(nil),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+}
+
+#[test]
+fn test_u64_conversion() {
+    let s = &Store::<Fr>::default();
+
+    let expr = "(+ 0 1u64)";
+    let expr2 = "(num 1u64)";
     let expr3 = "(+ 1 1u64)";
     let expr4 = "(u64 (+ 1 1))";
     let expr5 = "(u64 123u64)";
     let expr6 = "(u64)";
     let expr7 = "(u64 1 1)";
 
-    let res = s.num_u64(1);
-    let res2 = s.num_u64(2);
-    let res3 = s.u64(2);
-    let res5 = s.u64(123);
+    let res = s.num_u64(1);
+    let res2 = s.num_u64(2);
+    let res3 = s.u64(2);
+    let res5 = s.u64(123);
+    let terminal = s.cont_terminal();
+
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr,
+        Some(res),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr2,
+        Some(res),
+        None,
+        Some(terminal),
+        None,
+        &expect!["2"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr3,
+        Some(res2),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr4,
+        Some(res3),
+        None,
+        Some(terminal),
+        None,
+        &expect!["5"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr5,
+        Some(res5),
+        None,
+        Some(terminal),
+        None,
+        &expect!["2"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr6,
+        None,
+        None,
+        Some(error_cont(s, "arity mismatch", expr6)),
+        None,
+        &expect!["1"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr7,
+        None,
+        None,
+        Some(error_cont(s, "arity mismatch", expr7)),
+        None,
+        &expect!["1"],
+        &None,
+    );
+}
+
+#[test]
+fn test_numeric_type_error() {
+    let s = &Store::<Fr>::default();
+
+    // `offending` is the argument reported when the first one is a field element
+    let test = |op, reason, offending| {
+        let expr = &format!("({op} 0 'a)");
+        let expr2 = &format!("({op} 0u64 'a)");
+        let error = error_cont(s, reason, offending);
+        let error2 = error_cont(s, "non-numeric argument", "a");
+
+        test_aux::<Coproc<Fr>>(s, expr, None, None, Some(error), None, &expect!["3"], &None);
+        test_aux::<Coproc<Fr>>(
+            s,
+            expr2,
+            None,
+            None,
+            Some(error2),
+            None,
+            &expect!["3"],
+            &None,
+        );
+    };
+
+    test("+", "non-numeric argument", "a");
+    test("-", "non-numeric argument", "a");
+    test("*", "non-numeric argument", "a");
+    test("/", "non-numeric argument", "a");
+    // `%` only takes u64s, so it reports the field element before the symbol
+    test("%", "invalid argument", "0");
+    test(">", "non-numeric argument", "a");
+    test("<", "non-numeric argument", "a");
+    test(">=", "non-numeric argument", "a");
+    test("<=", "non-numeric argument", "a");
+    test("=", "non-numeric argument", "a");
+}
+
+#[test]
+fn test_u64_num_comparison() {
+    let s = &Store::<Fr>::default();
+
+    let expr = "(= 1 1u64)";
+    let expr2 = "(= 1 2u64)";
+    let t = s.intern_lurk_symbol("t");
+    let nil = s.intern_nil();
+    let terminal = s.cont_terminal();
+
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr,
+        Some(t),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr2,
+        Some(nil),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+}
+
+#[test]
+fn test_u64_num_cons() {
+    let s = &Store::<Fr>::default();
+
+    let expr = "(cons 1 1u64)";
+    let expr2 = "(cons 1u64 1)";
+    let res = s.read_with_default_state("(1 . 1u64)").unwrap();
+    let res2 = s.read_with_default_state("(1u64 . 1)").unwrap();
+    let terminal = s.cont_terminal();
+
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr,
+        Some(res),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr2,
+        Some(res2),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+}
+
+#[test]
+fn test_i64_arith() {
+    let s = &Store::<Fr>::default();
+
+    let expr = "(+ 5s64 -7s64)";
+    let expr2 = "(+ 9223372036854775807s64 1s64)";
+    let expr3 = "(- -5s64 7s64)";
+    let expr4 = "(* -3s64 4s64)";
+    let expr5 = "(/ -7s64 2s64)";
+    let expr6 = "(% -7s64 2s64)";
+    let expr7 = "(/ 7s64 -2s64)";
+    let expr8 = "(% 7s64 -2s64)";
+    let expr9 = "(/ -9223372036854775808s64 -1s64)";
+    let expr10 = "(/ 1s64 0s64)";
+    let expr11 = "(+ 1s64 1u64)";
+
+    let res = s.i64(-2);
+    let res2 = s.i64(i64::MIN);
+    let res3 = s.i64(-12);
+    let res5 = s.i64(-3);
+    let res6 = s.i64(-1);
+    let res8 = s.i64(1);
+    let terminal = s.cont_terminal();
+
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr,
+        Some(res),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr2,
+        Some(res2),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr3,
+        Some(res3),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr4,
+        Some(res3),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr5,
+        Some(res5),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr6,
+        Some(res6),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr7,
+        Some(res5),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr8,
+        Some(res8),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr9,
+        Some(res2),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr10,
+        None,
+        None,
+        Some(error_cont(s, "division by zero", "0s64")),
+        None,
+        &expect!["3"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr11,
+        None,
+        None,
+        Some(error_cont(s, "invalid argument", "1u64")),
+        None,
+        &expect!["3"],
+        &None,
+    );
+}
+
+#[test]
+fn test_i64_comp() {
+    let s = &Store::<Fr>::default();
+
+    let expr = "(< -1s64 1s64)";
+    let expr2 = "(> -1s64 1s64)";
+    let expr3 = "(<= -5s64 -5s64)";
+    let expr4 = "(>= -6s64 -5s64)";
+    let expr5 = "(< -9223372036854775808s64 9223372036854775807s64)";
+    let expr6 = "(= -1s64 -1s64)";
+
+    let t = s.intern_lurk_symbol("t");
+    let nil = s.intern_nil();
+    let terminal = s.cont_terminal();
+
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr,
+        Some(t),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr2,
+        Some(nil),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr3,
+        Some(t),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr4,
+        Some(nil),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr5,
+        Some(t),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr6,
+        Some(t),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+}
+
+#[test]
+fn test_i64_conversion() {
+    let s = &Store::<Fr>::default();
+
+    let expr = "(i64 -1)";
+    let expr2 = "(i64 18446744073709551615u64)";
+    let expr3 = "(u64 -1s64)";
+    let expr4 = "(num -3s64)";
+    let expr5 = "(num 3s64)";
+    let expr6 = "(i64 'a')";
+    let expr7 = "(i64 -9223372036854775808)";
+
+    let res = s.i64(-1);
+    let res3 = s.u64(u64::MAX);
+    let res4 = s.num(-Fr::from(3));
+    let res5 = s.num_u64(3);
+    let terminal = s.cont_terminal();
+    let error = error_cont(s, "non-numeric argument", "'a'");
+
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr,
+        Some(res),
+        None,
+        Some(terminal),
+        None,
+        &expect!["2"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr2,
+        Some(res),
+        None,
+        Some(terminal),
+        None,
+        &expect!["2"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr3,
+        Some(res3),
+        None,
+        Some(terminal),
+        None,
+        &expect!["2"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr4,
+        Some(res4),
+        None,
+        Some(terminal),
+        None,
+        &expect!["2"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr5,
+        Some(res5),
+        None,
+        Some(terminal),
+        None,
+        &expect!["2"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr6,
+        None,
+        None,
+        Some(error),
+        None,
+        &expect!["2"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr7,
+        Some(s.i64(i64::MIN)),
+        None,
+        Some(terminal),
+        None,
+        &expect!["2"],
+        &None,
+    );
+}
+
+#[test]
+fn test_vector() {
+    let s = &Store::<Fr>::default();
+
+    let expr = "(vector)";
+    let expr2 = "(vector 1)";
+    let expr3 = "(vector 1 2 3)";
+    let expr4 = "(vector 0 1 2 3 4)";
+    let expr5 = "(vector-length (vector 1 2 3))";
+    let expr6 = "(vector-length (vector))";
+    let expr7 = "(vector-length 1)";
+    let expr8 = "(vector? (vector))";
+    let expr9 = "(vector? '(1))";
+
+    let nums = |n: u64| (0..n).map(|i| s.num_u64(i)).collect::<Vec<_>>();
+    let res = s.intern_vector(vec![]);
+    let res2 = s.intern_vector(vec![s.num_u64(1)]);
+    let res3 = s.intern_vector(vec![s.num_u64(1), s.num_u64(2), s.num_u64(3)]);
+    let res4 = s.intern_vector(nums(5));
+    let res5 = s.num_u64(3);
+    let res6 = s.num_u64(0);
+    let t = s.intern_lurk_symbol("t");
+    let nil = s.intern_nil();
+    let terminal = s.cont_terminal();
+    let error = error_cont(s, "invalid argument", "1");
+
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr,
+        Some(res),
+        None,
+        Some(terminal),
+        None,
+        &expect!["1"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr2,
+        Some(res2),
+        None,
+        Some(terminal),
+        None,
+        &expect!["2"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr3,
+        Some(res3),
+        None,
+        Some(terminal),
+        None,
+        &expect!["8"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr4,
+        Some(res4),
+        None,
+        Some(terminal),
+        None,
+        &expect!["15"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr5,
+        Some(res5),
+        None,
+        Some(terminal),
+        None,
+        &expect!["10"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr6,
+        Some(res6),
+        None,
+        Some(terminal),
+        None,
+        &expect!["2"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr7,
+        None,
+        None,
+        Some(error),
+        None,
+        &expect!["2"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr8,
+        Some(t),
+        None,
+        Some(terminal),
+        None,
+        &expect!["2"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr9,
+        Some(nil),
+        None,
+        Some(terminal),
+        None,
+        &expect!["2"],
+        &None,
+    );
+}
+
+#[test]
+fn test_vector_ref() {
+    let s = &Store::<Fr>::default();
+
+    let expr = "(vector-ref (vector 1 2 3) 2)";
+    let expr2 = "(vector-ref (vector 0 1 2 3 4) 4)";
+    let expr3 = "(vector-ref (vector 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16) 13)";
+    let expr4 = "(vector-ref (vector 'a (cons 1 2)) 0)";
+    let expr5 = "(vector-ref (vector 1 2) 2)";
+    let expr6 = "(vector-ref (vector 1 2) -1)";
+    let expr7 = "(vector-ref 1 0)";
+
+    let res = s.num_u64(3);
+    let res2 = s.num_u64(4);
+    let res3 = s.num_u64(13);
+    let res4 = s.intern_user_symbol("a");
+    let terminal = s.cont_terminal();
+
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr,
+        Some(res),
+        None,
+        Some(terminal),
+        None,
+        &expect!["12"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr2,
+        Some(res2),
+        None,
+        Some(terminal),
+        None,
+        &expect!["20"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr3,
+        Some(res3),
+        None,
+        Some(terminal),
+        None,
+        &expect!["44"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr4,
+        Some(res4),
+        None,
+        Some(terminal),
+        None,
+        &expect!["14"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr5,
+        None,
+        None,
+        Some(error_cont(s, "index out of bounds", "2")),
+        None,
+        &expect!["10"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr6,
+        None,
+        None,
+        Some(error_cont(s, "index out of bounds", "-1")),
+        None,
+        &expect!["10"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr7,
+        None,
+        None,
+        Some(error_cont(s, "invalid argument", "0")),
+        None,
+        &expect!["3"],
+        &None,
+    );
+}
+
+#[test]
+fn test_vector_set() {
+    let s = &Store::<Fr>::default();
+
+    let expr = "(vector-set (vector 1 2 3) 0 9)";
+    let expr2 = "(vector-set (vector 0 1 2 3 4) 4 7)";
+    let expr3 = "(vector-set (vector 1) 0 2)";
+    let expr4 = "(vector-set (vector 1 2 3) 3 4)";
+    let expr5 = "(vector-set (vector 1 2 3) 0)";
+
+    let res = s.intern_vector(vec![s.num_u64(9), s.num_u64(2), s.num_u64(3)]);
+    let res2 = s.intern_vector((0..4).chain([7]).map(|i| s.num_u64(i)).collect());
+    let res3 = s.intern_vector(vec![s.num_u64(2)]);
+    let terminal = s.cont_terminal();
+
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr,
+        Some(res),
+        None,
+        Some(terminal),
+        None,
+        &expect!["15"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr2,
+        Some(res2),
+        None,
+        Some(terminal),
+        None,
+        &expect!["24"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr3,
+        Some(res3),
+        None,
+        Some(terminal),
+        None,
+        &expect!["6"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr4,
+        None,
+        None,
+        Some(error_cont(s, "index out of bounds", "3")),
+        None,
+        &expect!["12"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        expr5,
+        None,
+        None,
+        Some(error_cont(s, "arity mismatch", expr5)),
+        None,
+        &expect!["1"],
+        &None,
+    );
+}
+
+#[test]
+fn test_vector_index_past_length() {
+    let s = &Store::<Fr>::default();
+    // vectors of capacity 4, 16 and 64, with trees of 1, 2 and 3 levels. Indices
+    // past the length, up to the capacity and beyond, never reach the tree
+    let v4 = "(vector 0 1 2)";
+    let v16 = "(vector 0 1 2 3 4)";
+    let v64 = "(vector 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16)";
+    let cases = [
+        (v4, "3"),
+        (v4, "4"),
+        (v16, "5"),
+        (v16, "16"),
+        (v64, "17"),
+        (v64, "64"),
+        (v64, "18446744073709551615u64"),
+    ];
+    for (vec, idx) in cases {
+        for expr in [
+            format!("(vector-ref {vec} {idx})"),
+            format!("(vector-set {vec} {idx} 'a)"),
+        ] {
+            let expr = s.read_with_default_state(&expr).unwrap();
+            let (output, _) =
+                evaluate_simple::<Fr, Coproc<Fr>>(None, expr, s, 100, &dummy_terminal()).unwrap();
+            let (reason, offending) = s.fetch_error(&output[2]).unwrap();
+            assert_eq!(reason, "index out of bounds");
+            let idx = s.read_with_default_state(idx).unwrap();
+            assert!(s.ptr_eq(&offending, &idx));
+        }
+    }
+
+    // the last element of each vector is at the bottom of its tree
+    for (vec, last) in [(v4, 2), (v16, 4), (v64, 16)] {
+        let expr = format!("(vector-ref {vec} {last})");
+        let expr = s.read_with_default_state(&expr).unwrap();
+        let (output, _) =
+            evaluate_simple::<Fr, Coproc<Fr>>(None, expr, s, 100, &dummy_terminal()).unwrap();
+        assert!(s.ptr_eq(&output[0], &s.num_u64(last)));
+    }
+}
+
+#[test]
+fn test_catch_throw() {
+    let s = &Store::<Fr>::default();
+    let terminal = s.cont_terminal();
+
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(catch 'a (+ 1 2))",
+        Some(s.num_u64(3)),
+        None,
+        Some(terminal),
+        None,
+        &expect!["6"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(catch 'a (throw 'a 1))",
+        Some(s.num_u64(1)),
+        None,
+        Some(terminal),
+        None,
+        &expect!["5"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(catch 'a (+ 1 (throw 'a 2)))",
+        Some(s.num_u64(2)),
+        None,
+        Some(terminal),
+        None,
+        &expect!["8"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(catch 'a (catch 'b (throw 'a 1)))",
+        Some(s.num_u64(1)),
+        None,
+        Some(terminal),
+        None,
+        &expect!["8"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(catch 'a (throw 'b 1))",
+        None,
+        None,
+        Some(error_cont(s, "uncaught throw", "b")),
+        None,
+        &expect!["6"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(throw 'a 1)",
+        None,
+        None,
+        Some(error_cont(s, "uncaught throw", "a")),
+        None,
+        &expect!["3"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(catch 'a)",
+        None,
+        None,
+        Some(error_cont(s, "arity mismatch", "(catch 'a)")),
+        None,
+        &expect!["1"],
+        &None,
+    );
+}
+
+#[test]
+fn test_variadic_lambda() {
+    let s = &Store::<Fr>::default();
+    let nil = s.intern_nil();
+    let res = s.list((1..=3).map(|i| s.num_u64(i)));
+    let res2 = s.list(vec![s.num_u64(1)]);
+    let res3 = s.list(vec![s.num_u64(2)]);
+    let terminal = s.cont_terminal();
+    let vars = s.read_with_default_state("(&rest)").unwrap();
+    let fun = s.intern_fun(vars, s.num_u64(1), s.intern_empty_env());
+    let error = s.intern_error("malformed expression", fun);
+
+    test_aux::<Coproc<Fr>>(
+        s,
+        "((lambda (&rest xs) xs))",
+        Some(nil),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "((lambda (&rest xs) xs) 1 2 3)",
+        Some(res),
+        None,
+        Some(terminal),
+        None,
+        &expect!["10"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "((lambda (a &rest xs) (cons a xs)) 1)",
+        Some(res2),
+        None,
+        Some(terminal),
+        None,
+        &expect!["7"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "((lambda (a &rest xs) xs) 1 2)",
+        Some(res3),
+        None,
+        Some(terminal),
+        None,
+        &expect!["7"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "((lambda (&rest) 1))",
+        None,
+        None,
+        Some(error),
+        None,
+        &expect!["2"],
+        &None,
+    );
+}
+
+#[test]
+fn test_apply() {
+    let s = &Store::<Fr>::default();
+    let one = s.num_u64(1);
+    let three = s.num_u64(3);
+    let res = s.list((1..=3).map(|i| s.num_u64(i)));
+    let terminal = s.cont_terminal();
+    let error = error_cont(s, "not a function", "a");
+
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(apply (lambda (a b) (+ a b)) '(1 2))",
+        Some(three),
+        None,
+        Some(terminal),
+        None,
+        &expect!["9"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(apply (lambda (&rest xs) xs) '(1 2 3))",
+        Some(res),
+        None,
+        Some(terminal),
+        None,
+        &expect!["12"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(apply (lambda () 1) nil)",
+        Some(one),
+        None,
+        Some(terminal),
+        None,
+        &expect!["5"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(apply (lambda (x) (lambda (y) (+ x y))) '(1 2))",
+        Some(three),
+        None,
+        Some(terminal),
+        None,
+        &expect!["10"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(apply 'a '(1))",
+        None,
+        None,
+        Some(error),
+        None,
+        &expect!["4"],
+        &None,
+    );
+}
+
+#[test]
+fn test_apply_dispatch() {
+    let s = &Store::<Fr>::default();
+    let two = s.num_u64(2);
+    let three = s.num_u64(3);
+    let a = s.intern_user_symbol("a");
+    let rest = s.list(vec![s.num_u64(2), three]);
+    let terminal = s.cont_terminal();
+    let invalid = error_cont(s, "invalid argument", "2");
+
+    // continuations are applied like functions
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(+ 1 (call/cc (lambda (k) (apply k '(2)))))",
+        Some(three),
+        None,
+        Some(terminal),
+        None,
+        &expect!["13"],
+        &None,
+    );
+    // arguments aren't evaluated again
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(apply (lambda (x) x) '(a))",
+        Some(a),
+        None,
+        Some(terminal),
+        None,
+        &expect!["6"],
+        &None,
+    );
+    // oversaturated calls, with and without a rest parameter
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(apply (lambda (x &rest ys) ys) '(1 2 3))",
+        Some(rest),
+        None,
+        Some(terminal),
+        None,
+        &expect!["11"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(apply (lambda (x) (lambda (y) y)) '(1 2))",
+        Some(two),
+        None,
+        Some(terminal),
+        None,
+        &expect!["8"],
+        &None,
+    );
+    // the list of arguments must be proper
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(apply (lambda (x y) y) (cons 1 2))",
+        None,
+        None,
+        Some(invalid),
+        None,
+        &expect!["8"],
+        &None,
+    );
+
+    let fails = |expr: &str, reason: &str| {
+        let expr = s.read_with_default_state(expr).unwrap();
+        let (output, _) =
+            evaluate_simple::<Fr, Coproc<Fr>>(None, expr, s, 100, &dummy_terminal()).unwrap();
+        let (got_reason, _) = s.fetch_error(&output[2]).unwrap();
+        assert_eq!(got_reason, reason);
+    };
+    fails("(call/cc (lambda (k) (apply k '(2 3))))", "arity mismatch");
+    // `&rest` without a name to bind the rest of the arguments to
+    fails(
+        "(apply (lambda (x &rest) x) '(1 2))",
+        "malformed expression",
+    );
+}
+
+#[test]
+fn test_short_circuit_forms() {
+    let s = &Store::<Fr>::default();
+    let one = s.num_u64(1);
+    let two = s.num_u64(2);
+    let three = s.num_u64(3);
+    let nil = s.intern_nil();
+    let t = s.intern_lurk_symbol("t");
+    let b = s.intern_user_symbol("b");
+    let terminal = s.cont_terminal();
+    let error = error_cont(s, "malformed expression", "(cond 1)");
+
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(cond ((= 1 2) 'a) ((= 1 1) 'b) (t 'c))",
+        Some(b),
+        None,
+        Some(terminal),
+        None,
+        &expect!["10"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(cond ((= 1 2) 'a))",
+        Some(nil),
+        None,
+        Some(terminal),
+        None,
+        &expect!["5"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(cond 1)",
+        None,
+        None,
+        Some(error),
+        None,
+        &expect!["1"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(and 1 2 3)",
+        Some(three),
+        None,
+        Some(terminal),
+        None,
+        &expect!["4"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(and 1 nil 2)",
+        Some(nil),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(and)",
+        Some(t),
+        None,
+        Some(terminal),
+        None,
+        &expect!["1"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(or nil 2 3)",
+        Some(two),
+        None,
+        Some(terminal),
+        None,
+        &expect!["3"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(or 1 (car 1))",
+        Some(one),
+        None,
+        Some(terminal),
+        None,
+        &expect!["2"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(or)",
+        Some(nil),
+        None,
+        Some(terminal),
+        None,
+        &expect!["1"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(when (= 1 1) 1 2)",
+        Some(two),
+        None,
+        Some(terminal),
+        None,
+        &expect!["8"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(unless (= 1 1) 1)",
+        Some(nil),
+        None,
+        Some(terminal),
+        None,
+        &expect!["6"],
+        &None,
+    );
+}
+
+#[test]
+fn test_shadowed_builtins() {
+    let s = &Store::<Fr>::default();
+    let nil = s.intern_nil();
+    let two = s.num_u64(2);
+    let terminal = s.cont_terminal();
+
+    // like `if`, the short-circuit forms ignore bindings of their names
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(let ((and (lambda (a b) b))) (and nil 2))",
+        Some(nil),
+        None,
+        Some(terminal),
+        None,
+        &expect!["4"],
+        &None,
+    );
+    // a variable where a clause is expected makes `cond` an ordinary call
+    test_aux::<Coproc<Fr>>(
+        s,
+        "((lambda (cond a) (cond a)) (lambda (x) (+ x 1)) 1)",
+        Some(two),
+        None,
+        Some(terminal),
+        None,
+        &expect!["10"],
+        &None,
+    );
+    // the other built-ins yield to a binding of their name
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(let ((i64 (lambda (x) (+ x 1)))) (i64 1))",
+        Some(two),
+        None,
+        Some(terminal),
+        None,
+        &expect!["8"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(let ((x 2)) (i64 x))",
+        Some(s.i64(2)),
+        None,
+        Some(terminal),
+        None,
+        &expect!["6"],
+        &None,
+    );
+    // in an empty environment there is nothing to look up
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(i64 2)",
+        Some(s.i64(2)),
+        None,
+        Some(terminal),
+        None,
+        &expect!["2"],
+        &None,
+    );
+}
+
+#[test]
+fn test_match() {
+    let s = &Store::<Fr>::default();
+    let three = s.num_u64(3);
+    let two = s.intern_user_symbol("two");
+    let many = s.intern_user_symbol("many");
+    let tail = s.list(vec![s.num_u64(2), three]);
+    let terminal = s.cont_terminal();
+
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(match '(1 2) ((a b) (+ a b)))",
+        Some(three),
+        None,
+        Some(terminal),
+        None,
+        &expect!["9"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(match 2 (1 'one) (2 'two) (_ 'many))",
+        Some(two),
+        None,
+        Some(terminal),
+        None,
+        &expect!["6"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(match 3 (1 'one) (2 'two) (_ 'many))",
+        Some(many),
+        None,
+        Some(terminal),
+        None,
+        &expect!["8"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(match '(1 2) ((_ x) x))",
+        Some(s.num_u64(2)),
+        None,
+        Some(terminal),
+        None,
+        &expect!["7"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(match '(add 1 2) (('sub x y) (- x y)) (('add x y) (+ x y)))",
+        Some(three),
+        None,
+        Some(terminal),
+        None,
+        &expect!["14"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(match '(1 2 3) ((h . tl) tl))",
+        Some(tail),
+        None,
+        Some(terminal),
+        None,
+        &expect!["6"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(match 1 (2 3))",
+        None,
+        None,
+        Some(error_cont(s, "no matching clause", "1")),
+        None,
+        &expect!["3"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(match 1)",
+        None,
+        None,
+        Some(error_cont(s, "malformed expression", "(match 1)")),
+        None,
+        &expect!["1"],
+        &None,
+    );
+}
+
+#[test]
+fn test_quasiquote() {
+    let s = &Store::<Fr>::default();
+    let a = s.intern_user_symbol("a");
+    let one_two = s.list(vec![s.num_u64(1), s.num_u64(2)]);
+    let spliced = s.list((0..4).map(|n| s.num_u64(n)).collect::<Vec<_>>());
+    let terminal = s.cont_terminal();
+    let error = error_cont(s, "invalid argument", "2");
+    let misplaced = error_cont(s, "malformed expression", ",@'(2)");
+    let read = |expr| s.read_with_default_state(expr).unwrap();
+
+    test_aux::<Coproc<Fr>>(
+        s,
+        "`a",
+        Some(a),
+        None,
+        Some(terminal),
+        None,
+        &expect!["1"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "`(1 ,(+ 1 1))",
+        Some(one_two),
+        None,
+        Some(terminal),
+        None,
+        &expect!["8"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "`(0 ,@'(1 2) 3)",
+        Some(spliced),
+        None,
+        Some(terminal),
+        None,
+        &expect!["9"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "`(1 ,@2)",
+        None,
+        None,
+        Some(error),
+        None,
+        &expect!["3"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "`(1 . ,(+ 1 1))",
+        Some(read("(1 . 2)")),
+        None,
+        Some(terminal),
+        None,
+        &expect!["6"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "`(1 . ,@'(2))",
+        None,
+        None,
+        Some(misplaced),
+        None,
+        &expect!["2"],
+        &None,
+    );
+    // only the forms unquoted as many times as they are quasiquoted are evaluated
+    test_aux::<Coproc<Fr>>(
+        s,
+        "`(1 `(2 ,(+ 1 2)))",
+        Some(read("(1 `(2 ,(+ 1 2)))")),
+        None,
+        Some(terminal),
+        None,
+        &expect!["21"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "`(1 `(2 ,,(+ 1 2) ,@,@'((+ 3 4))))",
+        Some(read("(1 `(2 ,3 ,@(+ 3 4)))")),
+        None,
+        Some(terminal),
+        None,
+        &expect!["30"],
+        &None,
+    );
+}
+
+#[test]
+fn test_string_builtins() {
+    let s = &Store::<Fr>::default();
+    let three = s.num_u64(3);
+    let abcd = s.intern_string("abcd");
+    let chars = s.list(vec![s.char('a'), s.char('b')]);
+    let el = s.intern_string("el");
+    let t = s.intern_lurk_symbol("t");
+    let nil = s.intern_nil();
+    let terminal = s.cont_terminal();
+    let error = error_cont(s, "index out of bounds", "(0 . 1)");
+    let reversed = error_cont(s, "index out of bounds", "1");
+
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(string-length \"abc\")",
+        Some(three),
+        None,
+        Some(terminal),
+        None,
+        &expect!["6"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(string-append \"ab\" \"cd\")",
+        Some(abcd),
+        None,
+        Some(terminal),
+        None,
+        &expect!["7"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(string->list \"ab\")",
+        Some(chars),
+        None,
+        Some(terminal),
+        None,
+        &expect!["6"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(substring \"hello\" 1 3)",
+        Some(el),
+        None,
+        Some(terminal),
+        None,
+        &expect!["9"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(string< \"abc\" \"abd\")",
+        Some(t),
+        None,
+        Some(terminal),
+        None,
+        &expect!["6"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(string< \"ab\" \"a\")",
+        Some(nil),
+        None,
+        Some(terminal),
+        None,
+        &expect!["5"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(substring \"ab\" 1 3)",
+        None,
+        None,
+        Some(error),
+        None,
+        &expect!["7"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(substring \"hello\" 3 1)",
+        None,
+        None,
+        Some(reversed),
+        None,
+        &expect!["4"],
+        &None,
+    );
+}
+
+#[test]
+fn test_num_string_conversions() {
+    let s = &Store::<Fr>::default();
+    let state = State::init_lurk_state();
+    // the strings are the ones the printer writes for the same values
+    let print = |ptr: Ptr| s.intern_string(&ptr.fmt_to_string(s, &state));
+    let neg = s.num(-Fr::from(17));
+    let big = s.read_with_default_state("18446744073709551616").unwrap();
+    let ninety_seven = s.num_u64(97);
+    let terminal = s.cont_terminal();
+
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(u64->string 42u64)",
+        Some(print(s.u64(42))),
+        None,
+        Some(terminal),
+        None,
+        &expect!["4"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(num->string 42)",
+        Some(print(s.num_u64(42))),
+        None,
+        Some(terminal),
+        None,
+        &expect!["4"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(num->string -17)",
+        Some(print(neg)),
+        None,
+        Some(terminal),
+        None,
+        &expect!["66"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(num->string 18446744073709551616)",
+        Some(print(big)),
+        None,
+        Some(terminal),
+        None,
+        &expect!["66"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(string->num \"-17\")",
+        Some(neg),
+        None,
+        Some(terminal),
+        None,
+        &expect!["5"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(string->num (num->string -17))",
+        Some(neg),
+        None,
+        Some(terminal),
+        None,
+        &expect!["133"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(string->num \"0x10000000000000000\")",
+        Some(big),
+        None,
+        Some(terminal),
+        None,
+        &expect!["20"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(string->num \"0b1_01\")",
+        Some(s.num_u64(5)),
+        None,
+        Some(terminal),
+        None,
+        &expect!["7"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(string->num \"255u64\")",
+        Some(s.u64(255)),
+        None,
+        Some(terminal),
+        None,
+        &expect!["6"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(string->num \"-0x80s64\")",
+        Some(s.i64(-128)),
+        None,
+        Some(terminal),
+        None,
+        &expect!["5"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(char->num 'a')",
+        Some(ninety_seven),
+        None,
+        Some(terminal),
+        None,
+        &expect!["2"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(string->num \"12a\")",
+        None,
+        None,
+        Some(error_cont(s, "invalid argument", r#""a""#)),
+        None,
+        &expect!["5"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(string->num \"-1u64\")",
+        None,
+        None,
+        Some(error_cont(s, "invalid argument", r#""u64""#)),
+        None,
+        &expect!["4"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(string->num \"18446744073709551616u64\")",
+        None,
+        None,
+        Some(error_cont(s, "invalid argument", r#""u64""#)),
+        None,
+        &expect!["23"],
+        &None,
+    );
+    // the modulus wraps around to zero, like in the reader
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(string->num \"0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001\")",
+        Some(s.num_u64(0)),
+        None,
+        Some(terminal),
+        None,
+        &expect!["67"],
+        &None,
+    );
+    // but it's too big for a u64 nonetheless
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(string->num \"0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001u64\")",
+        None,
+        None,
+        Some(error_cont(s, "invalid argument", r#""u64""#)),
+        None,
+        &expect!["67"],
+        &None,
+    );
+}
+
+#[test]
+fn test_string_to_num_like_reader() {
+    let s = &Store::<Fr>::default();
+    // fractions are left out since `string->num` doesn't read them
+    let inputs = [
+        "0",
+        "-0",
+        "42",
+        "-17",
+        "0b1_01",
+        "0o17",
+        "0d99",
+        "0xfF",
+        "-0x10",
+        "1_",
+        "255u64",
+        "18446744073709551615u64",
+        "18446744073709551616u64",
+        "-1u64",
+        "-0x80i64",
+        "9223372036854775807i64",
+        "9223372036854775808i64",
+        "-9223372036854775808i64",
+        "-9223372036854775809i64",
+        "-0x80s64",
+        "9223372036854775807s64",
+        "9223372036854775808s64",
+        "-9223372036854775808s64",
+        "-9223372036854775809s64",
+        "0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000002",
+        "0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000002u64",
+        "0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000002i64",
+        "0xFFu64",
+        "1__2",
+        "1_u64",
+        "0u64",
+        "-0u64",
+        "-0i64",
+        "0x8000000000000000i64",
+        "-0x8000000000000000i64",
+        "-0s64",
+        "-0x8000000000000000s64",
+        "1u8",
+        "1i128",
+        "0x",
+        "0b",
+        "0b2",
+        "0x_1",
+        "_1",
+        "+1",
+        "-",
+    ];
+    for input in inputs {
+        let expr = s.list(vec![
+            s.intern_lurk_symbol("string->num"),
+            s.intern_string(input),
+        ]);
+        let (output, ..) =
+            evaluate_simple::<Fr, Coproc<Fr>>(None, expr, s, 200, &dummy_terminal()).unwrap();
+        // what the reader doesn't read as a number is read as a symbol or fails
+        match s.read_with_default_state(input) {
+            Ok(num)
+                if matches!(
+                    num.tag(),
+                    Tag::Expr(ExprTag::Num | ExprTag::U64 | ExprTag::I64)
+                ) =>
+            {
+                assert_eq!(output[0], num, "{input}");
+                assert_eq!(output[2], s.cont_terminal(), "{input}");
+            }
+            _ => assert_eq!(output[2].tag(), &Tag::Cont(ContTag::Error), "{input}"),
+        }
+    }
+}
+
+#[test]
+fn test_string_to_num_unlike_reader() {
+    let s = &Store::<Fr>::default();
+    // the reader reads fractions, skips leading whitespace and stops after the
+    // number, so it reads a number out of all of these, but `string->num`
+    // needs the whole string to be a number without a fraction
+    let inputs = [
+        ("1/2", "/2"),
+        ("12a", "a"),
+        (" 1", " 1"),
+        ("0B1", "B1"),
+        ("1 2", " 2"),
+    ];
+    for (input, rest) in inputs {
+        let num = s.read_with_default_state(input).unwrap();
+        assert!(matches!(num.tag(), Tag::Expr(ExprTag::Num)), "{input}");
+        let expr = s.list(vec![
+            s.intern_lurk_symbol("string->num"),
+            s.intern_string(input),
+        ]);
+        let (output, ..) =
+            evaluate_simple::<Fr, Coproc<Fr>>(None, expr, s, 200, &dummy_terminal()).unwrap();
+        let rest = s.intern_string(rest);
+        assert_eq!(output[0], rest, "{input}");
+        assert_eq!(
+            output[2],
+            s.intern_error("invalid argument", rest),
+            "{input}"
+        );
+    }
+}
+
+#[test]
+fn test_symbol_builtins() {
+    let s = &Store::<Fr>::default();
+    let foo = s.intern_string("foo");
+    let t = s.intern_lurk_symbol("t");
+    let nil = s.intern_nil();
+    let car = s.intern_lurk_symbol("car");
+    let user = s.intern_lurk_symbol("user");
+    let terminal = s.cont_terminal();
+    let error = error_cont(s, "invalid argument", "1");
+
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(symbol->string 'foo)",
+        Some(foo),
+        None,
+        Some(terminal),
+        None,
+        &expect!["2"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(eq (string->symbol \"foo\") 'foo)",
+        Some(t),
+        None,
+        Some(terminal),
+        None,
+        &expect!["5"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(string->symbol \"car\" (symbol-package 'car))",
+        Some(car),
+        None,
+        Some(terminal),
+        None,
+        &expect!["5"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(string->symbol \"nil\" (symbol-package nil))",
+        Some(nil),
+        None,
+        Some(terminal),
+        None,
+        &expect!["5"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(string->symbol \"foo\" (symbol-package :foo))",
+        Some(s.key("foo")),
+        None,
+        Some(terminal),
+        None,
+        &expect!["5"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(symbol-package 'foo)",
+        Some(user),
+        None,
+        Some(terminal),
+        None,
+        &expect!["2"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(keyword? :foo)",
+        Some(t),
+        None,
+        Some(terminal),
+        None,
+        &expect!["2"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(keyword? 'foo)",
+        Some(nil),
+        None,
+        Some(terminal),
+        None,
+        &expect!["2"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(symbol->string 1)",
+        None,
+        None,
+        Some(error),
+        None,
+        &expect!["2"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(string->symbol 1)",
+        None,
+        None,
+        Some(error),
+        None,
+        &expect!["2"],
+        &None,
+    );
+    let error = error_cont(s, "invalid argument", "2");
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(string->symbol 2 'user)",
+        None,
+        None,
+        Some(error),
+        None,
+        &expect!["3"],
+        &None,
+    );
+    let error = error_cont(s, "arity mismatch", r#""a""#);
+    test_aux::<Coproc<Fr>>(
+        s,
+        r#"(string->symbol "a" 'user 'user)"#,
+        None,
+        None,
+        Some(error),
+        None,
+        &expect!["2"],
+        &None,
+    );
+}
+
+#[test]
+fn test_expt_and_inverse() {
+    let s = &Store::<Fr>::default();
     let terminal = s.cont_terminal();
-    let error = s.cont_error();
 
     test_aux::<Coproc<Fr>>(
         s,
-        expr,
-        Some(res),
+        "(expt 2 10)",
+        Some(s.num_u64(1024)),
         None,
         Some(terminal),
         None,
@@ -3172,61 +5531,127 @@ fn test_u64_conversion() {
     );
     test_aux::<Coproc<Fr>>(
         s,
-        expr2,
-        Some(res),
+        "(expt 3 0)",
+        Some(s.num_u64(1)),
         None,
         Some(terminal),
         None,
-        &expect!["2"],
+        &expect!["3"],
         &None,
     );
     test_aux::<Coproc<Fr>>(
         s,
-        expr3,
-        Some(res2),
+        "(expt 2 300u64)",
+        Some(s.num(Fr::from(2).pow_vartime([300]))),
         None,
         Some(terminal),
         None,
         &expect!["3"],
         &None,
     );
-
     test_aux::<Coproc<Fr>>(
         s,
-        expr4,
-        Some(res3),
+        "(expt 2 -1)",
+        None,
+        None,
+        Some(error_cont(s, "invalid argument", "-1")),
+        None,
+        &expect!["3"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(inverse 2)",
+        Some(s.num(Fr::from(2).invert().unwrap())),
         None,
         Some(terminal),
         None,
-        &expect!["5"],
+        &expect!["2"],
         &None,
     );
     test_aux::<Coproc<Fr>>(
         s,
-        expr5,
-        Some(res5),
+        "(* 3 (inverse 3))",
+        Some(s.num_u64(1)),
         None,
         Some(terminal),
         None,
+        &expect!["5"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(inverse 0)",
+        None,
+        None,
+        Some(error_cont(s, "division by zero", "0")),
+        None,
         &expect!["2"],
         &None,
     );
+}
+
+#[test]
+fn test_multiple_values() {
+    let s = &Store::<Fr>::default();
+    let terminal = s.cont_terminal();
+
     test_aux::<Coproc<Fr>>(
         s,
-        expr6,
+        "(receive (a b) (values 1 2) (+ a b))",
+        Some(s.num_u64(3)),
         None,
+        Some(terminal),
         None,
-        Some(error),
+        &expect!["7"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(receive (a b) ((lambda () (values 1 2))) (+ a b))",
+        Some(s.num_u64(3)),
         None,
-        &expect!["1"],
+        Some(terminal),
+        None,
+        &expect!["9"],
         &None,
     );
     test_aux::<Coproc<Fr>>(
         s,
-        expr7,
+        "(receive (x) 5 x)",
+        Some(s.num_u64(5)),
+        None,
+        Some(terminal),
         None,
+        &expect!["3"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(let-values (((a b) (values 1 2)) ((c) 3)) (+ a (+ b c)))",
+        Some(s.num_u64(6)),
         None,
-        Some(error),
+        Some(terminal),
+        None,
+        &expect!["14"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(receive (a b) (values 1) a)",
+        None,
+        None,
+        Some(error_cont(s, "arity mismatch", "1")),
+        None,
+        &expect!["3"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(values 1 2)",
+        None,
+        None,
+        Some(error_cont(s, "arity mismatch", "(values 1 2)")),
         None,
         &expect!["1"],
         &None,
@@ -3234,109 +5659,224 @@ fn test_u64_conversion() {
 }
 
 #[test]
-fn test_numeric_type_error() {
+fn test_multiple_values_cost() {
     let s = &Store::<Fr>::default();
-    let error = s.cont_error();
-
-    let test = |op| {
-        let expr = &format!("({op} 0 'a)");
-        let expr2 = &format!("({op} 0u64 'a)");
-
-        test_aux::<Coproc<Fr>>(s, expr, None, None, Some(error), None, &expect!["3"], &None);
-        test_aux::<Coproc<Fr>>(
-            s,
-            expr2,
-            None,
-            None,
-            Some(error),
-            None,
-            &expect!["3"],
-            &None,
-        );
+    // the frames taken to evaluate `expr` and the hashes computed on them
+    let cost = |expr: &str| {
+        let ptr = s.read_with_default_state(expr).unwrap();
+        let frames = evaluate::<Fr, Coproc<Fr>>(None, ptr, s, 1000, &dummy_terminal()).unwrap();
+        let output = &frames.last().unwrap().output;
+        assert_eq!(output[0], s.num_u64(3));
+        assert_eq!(output[2], s.cont_terminal());
+        let hashes = frames
+            .iter()
+            .flat_map(|frame| [&frame.hints.hash4, &frame.hints.hash6, &frame.hints.hash8])
+            .map(|slots| slots.iter().filter(|slot| slot.is_some()).count())
+            .sum::<usize>();
+        (frames.len(), hashes)
     };
-
-    test("+");
-    test("-");
-    test("*");
-    test("/");
-    test("%");
-    test(">");
-    test("<");
-    test(">=");
-    test("<=");
-    test("=");
+    // binding the values one by one is cheaper than taking them from a pair
+    let (values_frames, values_hashes) = cost("(receive (a b) (values 1 2) (+ a b))");
+    let (cons_frames, cons_hashes) = cost("(let ((p (cons 1 2))) (+ (car p) (cdr p)))");
+    assert!(values_frames < cons_frames);
+    assert!(values_hashes < cons_hashes);
 }
 
 #[test]
-fn test_u64_num_comparison() {
+fn test_call_cc() {
     let s = &Store::<Fr>::default();
-
-    let expr = "(= 1 1u64)";
-    let expr2 = "(= 1 2u64)";
-    let t = s.intern_lurk_symbol("t");
-    let nil = s.intern_nil();
     let terminal = s.cont_terminal();
+    // the continuation captured at the top level is the outermost one
+    let k = s.intern_tuple2(
+        [s.cont_outermost(), s.dummy()],
+        Tag::Expr(ExprTag::Cont),
+        None,
+    );
+    let error = s.intern_error("arity mismatch", k);
 
     test_aux::<Coproc<Fr>>(
         s,
-        expr,
-        Some(t),
+        "(call/cc (lambda (k) 5))",
+        Some(s.num_u64(5)),
         None,
         Some(terminal),
         None,
-        &expect!["3"],
+        &expect!["4"],
         &None,
     );
     test_aux::<Coproc<Fr>>(
         s,
-        expr2,
-        Some(nil),
+        "(+ 1 (call/cc (lambda (k) (+ 2 (k 3)))))",
+        Some(s.num_u64(4)),
         None,
         Some(terminal),
         None,
-        &expect!["3"],
+        &expect!["11"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(call/cc (lambda (k) (k 1 2)))",
+        None,
+        None,
+        Some(error),
+        None,
+        &expect!["6"],
         &None,
     );
+
+    // a continuation that escapes doesn't show the environments it saved, and
+    // can't be taken apart
+    let escape = |expr: &str| {
+        let expr = s.read_with_default_state(expr).unwrap();
+        let (output, _) =
+            evaluate_simple::<Fr, Coproc<Fr>>(None, expr, s, 100, &dummy_terminal()).unwrap();
+        output
+    };
+    let output = escape("(let ((secret 42)) (cons (call/cc (lambda (k) k)) secret))");
+    assert_eq!(output[0].fmt_to_string_simple(s), "(<CONTINUATION> . 42)");
+    for op in ["car", "cdr", "open", "env->list"] {
+        let output = escape(&format!(
+            "(let ((secret 42)) ({op} (call/cc (lambda (k) k))))"
+        ));
+        assert_eq!(output[2].tag(), &Tag::Cont(ContTag::Error));
+    }
 }
 
 #[test]
-fn test_u64_num_cons() {
+fn test_env_builtins() {
     let s = &Store::<Fr>::default();
-
-    let expr = "(cons 1 1u64)";
-    let expr2 = "(cons 1u64 1)";
-    let res = s.read_with_default_state("(1 . 1u64)").unwrap();
-    let res2 = s.read_with_default_state("(1u64 . 1)").unwrap();
+    let a = s.intern_user_symbol("a");
+    let b = s.intern_user_symbol("b");
+    let bindings = s.list(vec![s.cons(b, s.num_u64(2)), s.cons(a, s.num_u64(1))]);
+    let nil = s.intern_nil();
     let terminal = s.cont_terminal();
 
     test_aux::<Coproc<Fr>>(
         s,
-        expr,
-        Some(res),
+        "(env->list (let ((a 1) (b 2)) (current-env)))",
+        Some(bindings),
         None,
         Some(terminal),
         None,
-        &expect!["3"],
+        &expect!["10"],
         &None,
     );
+    // the value of the top binding of a `letrec` group is listed unwrapped
     test_aux::<Coproc<Fr>>(
         s,
-        expr2,
-        Some(res2),
+        "(env->list (letrec ((a 1) (b 2)) (current-env)))",
+        Some(bindings),
         None,
         Some(terminal),
         None,
-        &expect!["3"],
+        &expect!["10"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(env->list (empty-env))",
+        Some(nil),
+        None,
+        Some(terminal),
+        None,
+        &expect!["2"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(env-lookup 'b (let ((a 1) (b 2)) (current-env)))",
+        Some(s.num_u64(2)),
+        None,
+        Some(terminal),
+        None,
+        &expect!["8"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(env-lookup 'b (letrec ((a 1) (b 2)) (current-env)))",
+        Some(s.num_u64(2)),
+        None,
+        Some(terminal),
+        None,
+        &expect!["7"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(env-lookup 'x (env-extend 'x 1 (empty-env)))",
+        Some(s.num_u64(1)),
+        None,
+        Some(terminal),
+        None,
+        &expect!["8"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(env-lookup 'y (empty-env))",
+        None,
+        None,
+        Some(error_cont(s, "unbound variable", "y")),
+        None,
+        &expect!["4"],
+        &None,
+    );
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(env-extend 1 2 (empty-env))",
+        None,
+        None,
+        Some(error_cont(s, "invalid argument", "1")),
+        None,
+        &expect!["4"],
         &None,
     );
 }
 
+#[test]
+fn test_checked_u64_arithmetic() {
+    let s = &Store::<Fr>::default();
+    let terminal = s.cont_terminal();
+
+    let ok = |expr: &str, res: u64| {
+        test_aux::<Coproc<Fr>>(
+            s,
+            expr,
+            Some(s.u64(res)),
+            None,
+            Some(terminal),
+            None,
+            &expect!["3"],
+            &None,
+        )
+    };
+    ok("(+! 18446744073709551614u64 1u64)", u64::MAX);
+    ok("(-! 3u64 3u64)", 0);
+    ok("(*! 4294967296u64 4294967295u64)", u64::MAX - 4294967295);
+
+    let fails = |expr: &str, reason: &str, offending: &str| {
+        let error = error_cont(s, reason, offending);
+        test_aux::<Coproc<Fr>>(s, expr, None, None, Some(error), None, &expect!["3"], &None)
+    };
+    fails("(+! 18446744073709551615u64 1u64)", "u64 overflow", "1u64");
+    fails("(-! 1u64 2u64)", "u64 underflow", "2u64");
+    fails(
+        "(*! 4294967296u64 4294967296u64)",
+        "u64 overflow",
+        "4294967296u64",
+    );
+    fails("(+! 1 2)", "invalid argument", "1");
+    fails("(+! 1u64 2)", "invalid argument", "2");
+}
+
 #[test]
 fn test_hide_u64_secret() {
     let s = &Store::<Fr>::default();
 
     let expr = "(hide 0u64 123)";
-    let error = s.cont_error();
+    let error = error_cont(s, "invalid argument", "0u64");
 
     test_aux::<Coproc<Fr>>(s, expr, None, None, Some(error), None, &expect!["3"], &None);
 }
@@ -3492,22 +6032,78 @@ fn test_lambda_args_regression() {
 fn test_eval_bad_form() {
     let s = &Store::<Fr>::default();
     let expr = "(* 5 (eval '(+ 1 a) '((0 . 3))))"; // two-arg eval, optional second arg is env.
-    let error = s.cont_error();
+    let error = error_cont(s, "invalid argument", "((0 . 3))");
 
     test_aux::<Coproc<Fr>>(s, expr, None, None, Some(error), None, &expect!["5"], &None);
 }
 
+#[test]
+fn test_error_reasons() {
+    let s = &Store::<Fr>::default();
+    // one failing expression per reason, along with the offending expression
+    // recorded next to it. A number given where a number of another kind is
+    // expected is an invalid argument, whereas anything else is non-numeric
+    let cases = [
+        ("x", "unbound variable", "x"),
+        ("(quote 1 2)", "malformed expression", "(quote 1 2)"),
+        ("(if nil)", "malformed expression", "(if nil)"),
+        ("(if nil 5 6 7)", "malformed expression", "(if nil 5 6 7)"),
+        ("(lambda (x))", "malformed expression", "(lambda (x))"),
+        ("(lambda (1) 1)", "malformed expression", "(lambda (1) 1)"),
+        ("(car 1 2)", "arity mismatch", "(car 1 2)"),
+        ("(- 9 8 7)", "arity mismatch", "9"),
+        ("('a 1)", "not a function", "a"),
+        ("(\"a\" 2)", "not a function", "(\"a\" 2)"),
+        ("(car 1)", "car of non-list", "1"),
+        ("(cdr 'a)", "cdr of non-list", "a"),
+        ("(+ 1 'a)", "non-numeric argument", "a"),
+        ("(= nil 5)", "non-numeric argument", "nil"),
+        ("(lognot 'a)", "non-numeric argument", "a"),
+        ("(lognot 1)", "invalid argument", "1"),
+        ("(string-length 1)", "invalid argument", "1"),
+        ("(open 'a)", "invalid argument", "a"),
+        ("(/ 1 0)", "division by zero", "0"),
+        ("(vector-ref (vector 1 2) 2)", "index out of bounds", "2"),
+        ("(throw 'a 1)", "uncaught throw", "a"),
+        ("(match 1 (2 3))", "no matching clause", "1"),
+        ("(+! 18446744073709551615u64 1u64)", "u64 overflow", "1u64"),
+        ("(-! 1u64 2u64)", "u64 underflow", "2u64"),
+    ];
+    for (expr, reason, offending) in cases {
+        let expr = s.read_with_default_state(expr).unwrap();
+        let (output, _) =
+            evaluate_simple::<Fr, Coproc<Fr>>(None, expr, s, 100, &dummy_terminal()).unwrap();
+        let (got_reason, got_offending) = s.fetch_error(&output[2]).unwrap();
+        assert_eq!(got_reason, reason);
+        let offending = s.read_with_default_state(offending).unwrap();
+        assert!(s.ptr_eq(&got_offending, &offending));
+    }
+
+    // the error atom carries no reason
+    assert!(s.fetch_error(&s.cont_error()).is_none());
+}
+
 #[test]
 fn test_eval_quote_error() {
     let s = &Store::<Fr>::default();
-    let error = s.cont_error();
 
+    // `(quote)` used to evaluate to nil
+    test_aux::<Coproc<Fr>>(
+        s,
+        "(quote)",
+        None,
+        None,
+        Some(error_cont(s, "malformed expression", "(quote)")),
+        None,
+        &expect!["1"],
+        &None,
+    );
     test_aux::<Coproc<Fr>>(
         s,
         "(quote . 1)",
         None,
         None,
-        Some(error),
+        Some(error_cont(s, "malformed expression", "(quote . 1)")),
         None,
         &expect!["1"],
         &None,
@@ -3517,7 +6113,7 @@ fn test_eval_quote_error() {
         "(quote 1 . 1)",
         None,
         None,
-        Some(error),
+        Some(error_cont(s, "malformed expression", "(quote 1 . 1)")),
         None,
         &expect!["1"],
         &None,
@@ -3527,7 +6123,7 @@ fn test_eval_quote_error() {
         "(quote 1 1)",
         None,
         None,
-        Some(error),
+        Some(error_cont(s, "malformed expression", "(quote 1 1)")),
         None,
         &expect!["1"],
         &None,
@@ -3538,7 +6134,7 @@ fn test_eval_quote_error() {
 fn test_eval_dotted_syntax_error() {
     let s = &Store::<Fr>::default();
     let expr = "(let ((a (lambda (x) (+ x 1)))) (a . 1))";
-    let error = s.cont_error();
+    let error = error_cont(s, "malformed expression", "(a . 1)");
 
     test_aux::<Coproc<Fr>>(s, expr, None, None, Some(error), None, &expect!["3"], &None);
 }
@@ -3546,7 +6142,6 @@ fn test_eval_dotted_syntax_error() {
 #[allow(dead_code)]
 fn op_syntax_error<T: Op + Copy>() {
     let s = &Store::<Fr>::default();
-    let error = s.cont_error();
     let test = |op: T| {
         let name = op.symbol_name();
 
@@ -3558,7 +6153,7 @@ fn op_syntax_error<T: Op + Copy>() {
                 &expr,
                 None,
                 None,
-                Some(error),
+                Some(error_cont(s, "malformed expression", &expr)),
                 None,
                 &expect!["1"],
                 &None,
@@ -3573,7 +6168,7 @@ fn op_syntax_error<T: Op + Copy>() {
                 &expr,
                 None,
                 None,
-                Some(error),
+                Some(error_cont(s, "arity mismatch", &expr)),
                 None,
                 &expect!["1"],
                 &None,
@@ -3587,7 +6182,7 @@ fn op_syntax_error<T: Op + Copy>() {
                 &expr,
                 None,
                 None,
-                Some(error),
+                Some(error_cont(s, "arity mismatch", &expr)),
                 None,
                 &expect!["1"],
                 &None,
@@ -3601,7 +6196,7 @@ fn op_syntax_error<T: Op + Copy>() {
                 &expr,
                 None,
                 None,
-                Some(error),
+                Some(error_cont(s, "arity mismatch", &expr)),
                 None,
                 &expect!["1"],
                 &None,
@@ -3611,10 +6206,11 @@ fn op_syntax_error<T: Op + Copy>() {
         if !op.supports_arity(3) {
             let expr = format!("({name} 123 456 789)");
             tracing::debug!("{}", &expr);
-            let iterations = if op.supports_arity(2) {
-                &expect!["2"]
+            // binops only find the extra argument once the first one is evaluated
+            let (error, iterations) = if op.supports_arity(2) {
+                (error_cont(s, "arity mismatch", "123"), &expect!["2"])
             } else {
-                &expect!["1"]
+                (error_cont(s, "arity mismatch", &expr), &expect!["1"])
             };
             test_aux::<Coproc<Fr>>(s, &expr, None, None, Some(error), None, iterations, &None);
         }
@@ -3635,17 +6231,21 @@ fn test_eval_binop_syntax_error() {
     op_syntax_error::<crate::tag::Op2>();
 }
 
+#[test]
+fn test_eval_ternop_syntax_error() {
+    op_syntax_error::<crate::tag::Op3>();
+}
+
 #[test]
 fn test_eval_lambda_body_syntax() {
     let s = &Store::<Fr>::default();
-    let error = s.cont_error();
 
     test_aux::<Coproc<Fr>>(
         s,
         "((lambda ()))",
         None,
         None,
-        Some(error),
+        Some(error_cont(s, "malformed expression", "(lambda ())")),
         None,
         &expect!["2"],
         &None,
@@ -3655,7 +6255,7 @@ fn test_eval_lambda_body_syntax() {
         "((lambda () 1 2))",
         None,
         None,
-        Some(error),
+        Some(error_cont(s, "malformed expression", "(lambda () 1 2)")),
         None,
         &expect!["2"],
         &None,
@@ -3665,7 +6265,7 @@ fn test_eval_lambda_body_syntax() {
         "((lambda (x)) 1)",
         None,
         None,
-        Some(error),
+        Some(error_cont(s, "malformed expression", "(lambda (x))")),
         None,
         &expect!["2"],
         &None,
@@ -3675,7 +6275,7 @@ fn test_eval_lambda_body_syntax() {
         "((lambda (x) 1 2) 1)",
         None,
         None,
-        Some(error),
+        Some(error_cont(s, "malformed expression", "(lambda (x) 1 2)")),
         None,
         &expect!["2"],
         &None,
@@ -3685,7 +6285,6 @@ fn test_eval_lambda_body_syntax() {
 #[test]
 fn test_eval_non_symbol_binding_error() {
     let s = &Store::<Fr>::default();
-    let error = s.cont_error();
 
     let test = |x| {
         let expr = format!("(let (({x} 123)) {x})");
@@ -3697,7 +6296,7 @@ fn test_eval_non_symbol_binding_error() {
             &expr,
             None,
             None,
-            Some(error),
+            Some(error_cont(s, "malformed expression", &expr)),
             None,
             &expect!["1"],
             &None,
@@ -3707,7 +6306,7 @@ fn test_eval_non_symbol_binding_error() {
             &expr2,
             None,
             None,
-            Some(error),
+            Some(error_cont(s, "malformed expression", &expr2)),
             None,
             &expect!["1"],
             &None,
@@ -3717,7 +6316,7 @@ fn test_eval_non_symbol_binding_error() {
             &expr3,
             None,
             None,
-            Some(error),
+            Some(error_cont(s, "malformed expression", &expr3)),
             None,
             &expect!["1"],
             &None,
@@ -3767,7 +6366,10 @@ fn test_dumb_lang() {
     let error6 = s.char('x');
     let error7 = s.char('y');
 
-    let error = s.cont_error();
+    // the coprocessor itself doesn't record why it failed, so the evaluator
+    // reports it along with what the coprocessor returned
+    let error6_cont = s.intern_error("coprocessor error", error6);
+    let error7_cont = s.intern_error("coprocessor error", error7);
     let terminal = s.cont_terminal();
 
     test_aux(
@@ -3805,7 +6407,7 @@ fn test_dumb_lang() {
         expr4,
         Some(error4),
         None,
-        Some(error),
+        Some(s.intern_error("arity mismatch", error4)),
         None,
         &expect!["4"],
         &Some(&lang),
@@ -3815,7 +6417,7 @@ fn test_dumb_lang() {
         expr5,
         Some(error5),
         None,
-        Some(error),
+        Some(s.intern_error("arity mismatch", error5)),
         None,
         &expect!["2"],
         &Some(&lang),
@@ -3825,7 +6427,7 @@ fn test_dumb_lang() {
         expr6,
         Some(error6),
         None,
-        Some(error),
+        Some(error6_cont),
         None,
         &expect!["3"],
         &Some(&lang),
@@ -3835,7 +6437,7 @@ fn test_dumb_lang() {
         expr6_,
         Some(error6),
         None,
-        Some(error),
+        Some(error6_cont),
         None,
         &expect!["3"],
         &Some(&lang),
@@ -3845,7 +6447,7 @@ fn test_dumb_lang() {
         expr7,
         Some(error7),
         None,
-        Some(error),
+        Some(error7_cont),
         None,
         &expect!["3"],
         &Some(&lang),
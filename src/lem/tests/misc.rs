@@ -1,13 +1,16 @@
 use bellpepper::util_cs::Comparable;
 use bellpepper_core::{test_cs::TestConstraintSystem, Delta};
+use ff::Field;
 use halo2curves::bn256::Fr;
 
 use crate::{
+    aux_func, block,
     dual_channel::dummy_terminal,
     field::LurkField,
     func,
     lang::{DummyCoprocessor, Lang},
-    lem::{pointers::Ptr, slot::SlotsCounter, store::Store, Func},
+    lem::{pointers::Ptr, slot::SlotsCounter, store::Store, Block, Func},
+    var,
 };
 
 /// Helper function for testing circuit synthesis.
@@ -28,6 +31,7 @@ fn synthesize_test_helper(
     assert_eq!(func.slots_count, expected_num_slots);
 
     let computed_num_constraints = func.num_constraints::<Fr>(store);
+    let computed_num_aux = func.num_aux::<Fr>(store);
 
     let lang: Lang<Fr, DummyCoprocessor<Fr>> = Lang::new();
 
@@ -51,6 +55,7 @@ fn synthesize_test_helper(
             .unwrap();
         assert!(cs.is_satisfied());
         assert_eq!(computed_num_constraints, cs.num_constraints());
+        assert_eq!(computed_num_aux, cs.aux().len());
         if let Some(cs_prev) = cs_prev {
             // Check for all input expressions that all frames are uniform.
             assert_eq!(cs.delta(&cs_prev, true), Delta::Equal);
@@ -286,3 +291,290 @@ fn test_unhash_nested_slots() {
     let inputs = vec![store.num(Fr::from_u64(42)), store.char('c')];
     synthesize_test_helper(&lem, inputs, SlotsCounter::new((4, 4, 4, 0, 0)), &store);
 }
+
+#[test]
+fn test_pow() {
+    let lem = func!(foo(expr_in, env_in, cont_in): 3 => {
+        let three = Num(3);
+        let exp = cast(expr_in, Expr::Num);
+        let pow = pow(three, exp);
+        return (pow, env_in, cont_in);
+    });
+
+    let store = Store::default();
+    let inputs = [0, 1, 10, 300, u64::MAX].map(|n| store.num(Fr::from_u64(n)));
+    synthesize_test_helper(
+        &lem,
+        inputs.to_vec(),
+        SlotsCounter::new((0, 0, 0, 0, 1)),
+        &store,
+    );
+
+    let nil = store.intern_nil();
+    let outermost = store.cont_outermost();
+    let lang: Lang<Fr, DummyCoprocessor<Fr>> = Lang::new();
+    for (input, n) in inputs.into_iter().zip([0, 1, 10, 300, u64::MAX]) {
+        let frame = lem
+            .call(
+                &[input, nil, outermost],
+                &store,
+                Default::default(),
+                &dummy_terminal(),
+                &lang,
+                0,
+            )
+            .unwrap();
+        assert_eq!(frame.output[0], store.num(Fr::from_u64(3).pow_vartime([n])));
+    }
+}
+
+#[test]
+fn test_numeric_ops() {
+    let lem = func!(foo(expr_in, env_in, cont_in): 3 => {
+        let a = cast(expr_in, Expr::Num);
+        let three = Num(3);
+        let b = add(a, three);
+        let c = sub(b, a);
+        let d = mul(a, c);
+        let e = div(d, c);
+        let f = inv(b);
+        let g = truncate(f, 16);
+        let (q, r) = div_rem64(d, b);
+        let h = bit_and(q, a);
+        let i = bit_or(h, r);
+        let j = bit_xor(i, e);
+        let k = shl(j, c);
+        let l = shr(k, three);
+        let is_lt = lt(l, g);
+        if is_lt {
+            return (l, env_in, cont_in);
+        }
+        return (g, env_in, cont_in);
+    });
+
+    let store = Store::default();
+    let inputs = [0, 1, 10, 300, 1 << 40].map(|n| store.num(Fr::from_u64(n)));
+    synthesize_test_helper(
+        &lem,
+        inputs.to_vec(),
+        SlotsCounter::new((0, 0, 0, 0, 14)),
+        &store,
+    );
+}
+
+#[test]
+fn test_boolean_ops() {
+    let lem = func!(foo(expr_in, env_in, cont_in): 3 => {
+        let is_eq = eq_tag(expr_in, env_in);
+        let not_eq = not(is_eq);
+        let b = and(is_eq, not_eq);
+        let c = or(b, not_eq);
+        if c {
+            return (expr_in, env_in, cont_in);
+        }
+        return (env_in, expr_in, cont_in);
+    });
+
+    let store = Store::default();
+    let inputs = vec![
+        store.num(Fr::from_u64(42)),
+        store.char('c'),
+        store.intern_nil(),
+    ];
+    synthesize_test_helper(&lem, inputs, SlotsCounter::default(), &store);
+}
+
+#[test]
+fn test_binding_ops() {
+    let lem = func!(foo(expr_in, _env_in, cont_in): 3 => {
+        let empty_env: Expr::Env;
+        let one = Num(1);
+        let env = push_binding(expr_in, one, empty_env);
+        let (sym, val, _rest) = pop_binding(env);
+        let same = eq_val(sym, expr_in);
+        if same {
+            return (val, env, cont_in);
+        }
+        return (sym, env, cont_in);
+    });
+
+    let store = Store::default();
+    let inputs = vec![store.intern_user_symbol("x"), store.intern_user_symbol("y")];
+    synthesize_test_helper(&lem, inputs, SlotsCounter::new((2, 0, 0, 0, 0)), &store);
+}
+
+#[test]
+fn test_hash_and_commitment_ops() {
+    let lem = func!(foo(expr_in, env_in, cont_in): 3 => {
+        let c3: Expr::Comm = HASH_3_ZEROS;
+        let c4: Expr::Cons = HASH_4_ZEROS;
+        let c6: Expr::Fun = HASH_6_ZEROS;
+        let c8: Cont::Call = HASH_8_ZEROS;
+        let a: Expr::Cons = cons2(expr_in, c4);
+        let b: Expr::Fun = cons3(a, c6, c3);
+        let k: Cont::Call = cons4(b, c8, env_in, cont_in);
+        let (k1, _k2, _k3, _k4) = decons4(k);
+        let (b1, _b2, _b3) = decons3(k1);
+        let (_a1, a2) = decons2(b1);
+        let seven = Num(7);
+        let comm = hide(seven, a2);
+        let (_secret, opened) = open(comm);
+        let foo = String("foo");
+        let is_foo = eq_val(opened, foo);
+        if is_foo {
+            return (foo, env_in, cont_in);
+        }
+        match expr_in.value {
+            Num(0) => {
+                return (c3, opened, cont_in);
+            }
+            Num(1) => {
+                return (c4, opened, k);
+            }
+        };
+        return (b, opened, k);
+    });
+
+    let store = Store::default();
+    let inputs = [0, 1, 42].map(|n| store.num(Fr::from_u64(n)));
+    synthesize_test_helper(
+        &lem,
+        inputs.to_vec(),
+        SlotsCounter::new((2, 2, 2, 2, 0)),
+        &store,
+    );
+}
+
+#[test]
+fn test_type_errors() {
+    let error = |body: Block| {
+        Func::new("foo".into(), vec![var!(x)], 1, body)
+            .unwrap_err()
+            .to_string()
+    };
+
+    assert_eq!(
+        error(block!({
+            let t: Expr::Nil;
+            let is_nil = eq_tag(x, t);
+            match x.tag {
+                Expr::Cons => {
+                    let y: Expr::Cons = cons2(x, is_nil);
+                    return (y);
+                }
+            };
+            return (x);
+        })),
+        "Type error at `foo` > `x#1.tag` is Expr::Cons > \
+        `let y#4: Expr::Cons = cons2(x#1, is_nil#3)`: \
+        `is_nil#3` is a boolean, but a pointer was expected"
+    );
+    assert_eq!(
+        error(block!({
+            if x {
+                return (x);
+            }
+            return (x);
+        })),
+        "Type error at `foo` > `if x#1`: `x#1` is a pointer, but a boolean was expected"
+    );
+    assert_eq!(
+        error(block!({
+            let one = Num(1);
+            let one_is_x = eq_val(one, x);
+            let y = add(one, one_is_x);
+            return (y);
+        })),
+        "Type error at `foo` > `let y#4 = add(one#2, one_is_x#3)`: \
+        `one_is_x#3` is a boolean, but a number was expected"
+    );
+    assert_eq!(
+        error(block!({
+            let one = Num(1);
+            let b = not(one);
+            if b {
+                return (x)
+            }
+            return (one)
+        })),
+        "Type error at `foo` > `let b#3 = not(one#2)`: `one#2` is a number, but a boolean was expected"
+    );
+    assert_eq!(
+        error(block!({
+            let one = Num(1);
+            let two = add(one, one);
+            let (car, _cdr) = decons2(two);
+            let y: Expr::Cons = cons2(car, x);
+            return (y);
+        })),
+        "Type error at `foo` > `let (car#4, _cdr#5) = decons2(two#3)`: \
+        `two#3` is a number, but a pointer to hashed data was expected"
+    );
+    assert_eq!(
+        error(block!({
+            let one = Num(1);
+            let y = add(one, x);
+            return (y);
+        })),
+        "Type error at `foo` > `let y#3 = add(one#2, x#1)`: \
+        `x#1` is a pointer, but a number was expected"
+    );
+    // the types of the outputs of calls are the ones the callee returns
+    let double = aux_func!(double(n): 1 => {
+        let m = add(n, n);
+        return (m);
+    });
+    assert_eq!(
+        error(block!({
+            let one = Num(1);
+            let (two) = double(one);
+            let (car, _cdr) = decons2(two);
+            let y: Expr::Cons = cons2(car, x);
+            return (y);
+        })),
+        "Type error at `foo` > `let (car#6, _cdr#7) = decons2(two#3)`: \
+        `two#3` is a number, but a pointer to hashed data was expected"
+    );
+    let first = aux_func!(first(xs): 1 => {
+        let (y, _ys) = decons2(xs);
+        return (y);
+    });
+    assert_eq!(
+        error(block!({
+            let one = Num(1);
+            let (y) = first(x);
+            let z = add(one, y);
+            return (z);
+        })),
+        "Type error at `foo` > `let z#7 = add(one#2, y#3)`: \
+        `y#3` is a pointer, but a number was expected"
+    );
+    // and the parameters of the callee take the types of the arguments
+    assert_eq!(
+        error(block!({
+            let (y) = double(x);
+            return (y);
+        })),
+        "Type error at `foo` > `let (y#2) = double(x#1)` > `let m#4 = add(n#3, n#3)`: \
+        `n#3` is a pointer, but a number was expected"
+    );
+    // pointers become numbers once they're cast or matched against numeric tags
+    assert!(Func::new(
+        "foo".into(),
+        vec![var!(x)],
+        1,
+        block!({
+            let one = Num(1);
+            match x.tag {
+                Expr::U64 => {
+                    let y = add(one, x);
+                    return (y);
+                }
+            };
+            let x = cast(x, Expr::Num);
+            let y = add(one, x);
+            return (y);
+        }),
+    )
+    .is_ok());
+}
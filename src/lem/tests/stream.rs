@@ -127,3 +127,50 @@ fn test_fun_callable() {
         expected_iterations,
     );
 }
+
+#[test]
+fn test_throw_callable() {
+    // the throws are caught within each call of the stream
+    let callable_str = "(letrec ((add (lambda (counter x)
+            (let ((counter (catch 'overflow
+                    (if (< 500 (+ counter x)) (throw 'overflow 0) (+ counter x)))))
+            (cons counter (add counter))))))
+        (add 0))";
+    let store = Store::<Fr>::default();
+    let callable = get_callable(callable_str, &store);
+
+    let output = assert_start_stream(
+        callable,
+        store.num_u64(123),
+        &store,
+        &store.num_u64(123),
+        &expect!["27"],
+    );
+    let output = assert_resume_stream(
+        output,
+        store.num_u64(321),
+        &store,
+        &store.num_u64(444),
+        &expect!["27"],
+    );
+    assert_resume_stream(
+        output,
+        store.num_u64(111),
+        &store,
+        &store.num_u64(0),
+        &expect!["28"],
+    );
+
+    // and the ones that aren't caught end the stream call with an error
+    let callable_str = "(letrec ((add (lambda (counter x)
+            (let ((counter (+ counter x)))
+            (cons counter (add (if (< 500 counter) (throw 'overflow counter) counter)))))))
+        (add 0))";
+    let callable = get_callable(callable_str, &store);
+    let (t1, t2) = pair_terminals();
+    t2.send(store.num_u64(501)).unwrap();
+    let (output, _) =
+        start_stream_simple::<Fr, Coproc<Fr>>(None, callable, &store, LIMIT, &t1).unwrap();
+    let overflow = store.read_with_default_state("overflow").unwrap();
+    assert_eq!(output[2], store.intern_error("uncaught throw", overflow));
+}
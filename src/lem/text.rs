@@ -0,0 +1,1118 @@
+//! A textual format for LEM, following the syntax accepted by the `func!` macro
+//!
+//! A `.lem` file is a sequence of function definitions such as
+//!
+//! ```text
+//! fn car_cdr(xs): 2 => {
+//!     match xs.tag {
+//!         Expr::Cons => {
+//!             let (car, cdr) = decons2(xs);
+//!             return (car, cdr)
+//!         }
+//!     };
+//!     let nil = Symbol("nil");
+//!     let nil = cast(nil, Expr::Nil);
+//!     return (nil, nil)
+//! }
+//! ```
+//!
+//! Functions can call the ones defined before them and the last one is the
+//! entry point, which `Func::from_file` loads. `Toplevel::from_file` loads all
+//! of them instead, as coroutines named after the functions. Displaying a `Func` produces text in this format, with the
+//! functions it calls defined first. Distinct functions that share a name get
+//! a `#k` suffix, which is what happens to the copies of a function after
+//! `Func::new` renames their variables.
+//!
+//! On top of what the macro accepts, `let x = copy(y)` stands for `Op::Copy`
+//! and `let (ys) = CPROC("name", xs)` for `Op::Cproc`. Symbols in literals are
+//! relative to the `.lurk` package while the names of coroutines and
+//! coprocessors are relative to `.lurk.user`, unless written as absolute
+//! paths, such as `".lurk.meta.load"`. `Op::Unit` has no textual form.
+
+use anyhow::{anyhow, bail, Context, Result};
+use indexmap::IndexMap;
+use std::{fmt, fs::read_to_string, path::Path, str::FromStr};
+use strum::EnumCount;
+
+use crate::{
+    state::{lurk_sym, user_sym, State},
+    symbol::Symbol,
+    tag::{
+        ContTag, ExprTag, Op1, Op2, Op3, CONT_TAG_INIT, EXPR_TAG_INIT, OP1_TAG_INIT, OP2_TAG_INIT,
+        OP3_TAG_INIT,
+    },
+};
+
+use super::{Block, Ctrl, Func, Lit, Op, Tag, Var};
+
+pub(super) fn fmt_tag(tag: &Tag) -> String {
+    match tag {
+        Tag::Expr(tag) => format!("Expr::{tag:?}"),
+        Tag::Cont(tag) => format!("Cont::{tag:?}"),
+        Tag::Op1(tag) => format!("Op1::{tag:?}"),
+        Tag::Op2(tag) => format!("Op2::{tag:?}"),
+        Tag::Op3(tag) => format!("Op3::{tag:?}"),
+    }
+}
+
+fn parse_tag(kind: &str, name: &str) -> Option<Tag> {
+    let (init, count) = match kind {
+        "Expr" => (EXPR_TAG_INIT, ExprTag::COUNT),
+        "Cont" => (CONT_TAG_INIT, ContTag::COUNT),
+        "Op1" => (OP1_TAG_INIT, Op1::COUNT),
+        "Op2" => (OP2_TAG_INIT, Op2::COUNT),
+        "Op3" => (OP3_TAG_INIT, Op3::COUNT),
+        _ => return None,
+    };
+    let text = format!("{kind}::{name}");
+    (init..init + count as u16)
+        .filter_map(|val| Tag::try_from(val).ok())
+        .find(|tag| fmt_tag(tag) == text)
+}
+
+/// Symbols are written by name if `relative` recovers them from it and as
+/// absolute paths otherwise
+fn fmt_symbol(sym: &Symbol, relative: fn(&str) -> Symbol) -> String {
+    match sym.name() {
+        Ok(name) if !name.starts_with(['.', ':', '~']) && *sym == relative(name) => {
+            format!("{name:?}")
+        }
+        _ => format!("{:?}", sym.fmt_to_string()),
+    }
+}
+
+fn parse_symbol(text: &str, relative: fn(&str) -> Symbol) -> Result<Symbol> {
+    if text.starts_with(['.', ':', '~']) {
+        let sym = Symbol::interned(text, State::init_lurk_state().rccell())?;
+        Ok((*sym).clone())
+    } else {
+        Ok(relative(text))
+    }
+}
+
+pub(super) fn fmt_lit(lit: &Lit) -> String {
+    match lit {
+        Lit::Num(num) => format!("Num({num})"),
+        Lit::String(string) => format!("String({string:?})"),
+        Lit::Symbol(sym) => format!("Symbol({})", fmt_symbol(sym, lurk_sym)),
+    }
+}
+
+pub(super) fn fmt_vars(vars: &[Var]) -> String {
+    vars.iter()
+        .map(|var| var.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn indent(f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+    write!(f, "{:1$}", "", 4 * depth)
+}
+
+struct Printer<'a> {
+    main: &'a str,
+    /// The functions called by `main`, in an order in which every function
+    /// comes after the ones it calls, with the names they're printed with
+    aux: Vec<(&'a Func, String)>,
+}
+
+impl<'a> Printer<'a> {
+    fn collect(&mut self, block: &'a Block) {
+        for op in &block.ops {
+            if let Op::Call(_, func, _) = op {
+                let func: &'a Func = func;
+                if self.aux.iter().any(|(aux, _)| *aux == func) {
+                    continue;
+                }
+                self.collect(&func.body);
+                let mut name = func.name.clone();
+                let mut k = 1;
+                while name == self.main || self.aux.iter().any(|(_, aux)| *aux == name) {
+                    k += 1;
+                    name = format!("{}#{k}", func.name);
+                }
+                self.aux.push((func, name));
+            }
+        }
+        match &block.ctrl {
+            Ctrl::MatchTag(_, cases, def) => {
+                cases.values().for_each(|block| self.collect(block));
+                if let Some(def) = def {
+                    self.collect(def);
+                }
+            }
+            Ctrl::MatchValue(_, _, cases, def) => {
+                cases.values().for_each(|block| self.collect(block));
+                if let Some(def) = def {
+                    self.collect(def);
+                }
+            }
+            Ctrl::If(_, true_block, false_block) => {
+                self.collect(true_block);
+                self.collect(false_block);
+            }
+            Ctrl::Return(..) => (),
+        }
+    }
+
+    fn name_of<'b>(&'b self, func: &'b Func) -> &'b str {
+        self.aux
+            .iter()
+            .find(|(aux, _)| *aux == func)
+            .map_or(&func.name, |(_, name)| name)
+    }
+
+    fn func(&self, func: &Func, name: &str, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "fn {name}({}): {} => {{",
+            fmt_vars(&func.input_params),
+            func.output_size
+        )?;
+        self.seq(&func.body, 1, f)?;
+        writeln!(f, "}}")
+    }
+
+    fn block(&self, block: &Block, depth: usize, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{{")?;
+        self.seq(block, depth + 1, f)?;
+        indent(f, depth)?;
+        write!(f, "}}")
+    }
+
+    /// Prints the cases of a match, putting adjacent cases with equal blocks
+    /// together, followed by its default
+    fn cases(
+        &self,
+        cases: Vec<(String, &Block)>,
+        def: &Option<Box<Block>>,
+        depth: usize,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        let mut cases = cases.into_iter().peekable();
+        while let Some((pattern, block)) = cases.next() {
+            indent(f, depth + 1)?;
+            write!(f, "{pattern}")?;
+            while let Some((pattern, _)) = cases.next_if(|(_, other)| *other == block) {
+                write!(f, " | {pattern}")?;
+            }
+            write!(f, " => ")?;
+            self.block(block, depth + 1, f)?;
+            writeln!(f)?;
+        }
+        indent(f, depth)?;
+        match def {
+            None => writeln!(f, "}}"),
+            Some(def) => {
+                writeln!(f, "}};")?;
+                self.seq(def, depth, f)
+            }
+        }
+    }
+
+    fn seq(&self, block: &Block, depth: usize, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for op in &block.ops {
+            indent(f, depth)?;
+            self.op(op, f)?;
+            writeln!(f, ";")?;
+        }
+        indent(f, depth)?;
+        match &block.ctrl {
+            Ctrl::Return(vars) => writeln!(f, "return ({})", fmt_vars(vars)),
+            Ctrl::If(x, true_block, false_block) => {
+                write!(f, "if {x} ")?;
+                self.block(true_block, depth, f)?;
+                writeln!(f)?;
+                self.seq(false_block, depth, f)
+            }
+            Ctrl::MatchTag(x, cases, def) => {
+                writeln!(f, "match {x}.tag {{")?;
+                let cases = cases.iter().map(|(tag, block)| (fmt_tag(tag), block));
+                self.cases(cases.collect(), def, depth, f)
+            }
+            Ctrl::MatchValue(x, _, cases, def) => {
+                writeln!(f, "match {x}.value {{")?;
+                let cases = cases.iter().map(|(lit, block)| (fmt_lit(lit), block));
+                self.cases(cases.collect(), def, depth, f)
+            }
+        }
+    }
+
+    fn op(&self, op: &Op, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match op {
+            Op::Crout(y, name, xs) => {
+                let name = fmt_symbol(name, user_sym);
+                match xs.as_slice() {
+                    [] => write!(f, "let {y} = QUERY({name})"),
+                    _ => write!(f, "let {y} = QUERY({name}, {})", fmt_vars(xs)),
+                }
+            }
+            Op::Cproc(ys, name, xs) => {
+                let (ys, name) = (fmt_vars(ys), fmt_symbol(name, user_sym));
+                match xs.as_slice() {
+                    [] => write!(f, "let ({ys}) = CPROC({name})"),
+                    _ => write!(f, "let ({ys}) = CPROC({name}, {})", fmt_vars(xs)),
+                }
+            }
+            Op::Call(ys, func, xs) => write!(
+                f,
+                "let ({}) = {}({})",
+                fmt_vars(ys),
+                self.name_of(func),
+                fmt_vars(xs)
+            ),
+            Op::Copy(y, x) => write!(f, "let {y} = copy({x})"),
+            Op::Zero(x, tag) => write!(f, "let {x}: {}", fmt_tag(tag)),
+            Op::Hash3Zeros(x, tag) => write!(f, "let {x}: {} = HASH_3_ZEROS", fmt_tag(tag)),
+            Op::Hash4Zeros(x, tag) => write!(f, "let {x}: {} = HASH_4_ZEROS", fmt_tag(tag)),
+            Op::Hash6Zeros(x, tag) => write!(f, "let {x}: {} = HASH_6_ZEROS", fmt_tag(tag)),
+            Op::Hash8Zeros(x, tag) => write!(f, "let {x}: {} = HASH_8_ZEROS", fmt_tag(tag)),
+            Op::Lit(x, lit) => write!(f, "let {x} = {}", fmt_lit(lit)),
+            Op::Cast(y, tag, x) => write!(f, "let {y} = cast({x}, {})", fmt_tag(tag)),
+            Op::EqTag(y, a, b) => write!(f, "let {y} = eq_tag({a}, {b})"),
+            Op::EqVal(y, a, b) => write!(f, "let {y} = eq_val({a}, {b})"),
+            Op::Not(y, a) => write!(f, "let {y} = not({a})"),
+            Op::And(y, a, b) => write!(f, "let {y} = and({a}, {b})"),
+            Op::Or(y, a, b) => write!(f, "let {y} = or({a}, {b})"),
+            Op::Add(y, a, b) => write!(f, "let {y} = add({a}, {b})"),
+            Op::Sub(y, a, b) => write!(f, "let {y} = sub({a}, {b})"),
+            Op::Mul(y, a, b) => write!(f, "let {y} = mul({a}, {b})"),
+            Op::Div(y, a, b) => write!(f, "let {y} = div({a}, {b})"),
+            Op::Inv(y, a) => write!(f, "let {y} = inv({a})"),
+            Op::Lt(y, a, b) => write!(f, "let {y} = lt({a}, {b})"),
+            Op::Trunc(y, a, n) => write!(f, "let {y} = truncate({a}, {n})"),
+            Op::DivRem64([q, r], a, b) => write!(f, "let ({q}, {r}) = div_rem64({a}, {b})"),
+            Op::BitAnd(y, a, b) => write!(f, "let {y} = bit_and({a}, {b})"),
+            Op::BitOr(y, a, b) => write!(f, "let {y} = bit_or({a}, {b})"),
+            Op::BitXor(y, a, b) => write!(f, "let {y} = bit_xor({a}, {b})"),
+            Op::Shl(y, a, b) => write!(f, "let {y} = shl({a}, {b})"),
+            Op::Shr(y, a, b) => write!(f, "let {y} = shr({a}, {b})"),
+            Op::Pow(y, a, b) => write!(f, "let {y} = pow({a}, {b})"),
+            Op::Emit(v) => write!(f, "emit({v})"),
+            Op::Recv(v) => write!(f, "let {v} =! recv()"),
+            Op::Cons2(x, tag, ys) => {
+                write!(f, "let {x}: {} = cons2({})", fmt_tag(tag), fmt_vars(ys))
+            }
+            Op::Cons3(x, tag, ys) => {
+                write!(f, "let {x}: {} = cons3({})", fmt_tag(tag), fmt_vars(ys))
+            }
+            Op::Cons4(x, tag, ys) => {
+                write!(f, "let {x}: {} = cons4({})", fmt_tag(tag), fmt_vars(ys))
+            }
+            Op::Decons2(ys, x) => write!(f, "let ({}) = decons2({x})", fmt_vars(ys)),
+            Op::Decons3(ys, x) => write!(f, "let ({}) = decons3({x})", fmt_vars(ys)),
+            Op::Decons4(ys, x) => write!(f, "let ({}) = decons4({x})", fmt_vars(ys)),
+            Op::PushBinding(x, ys) => write!(f, "let {x} = push_binding({})", fmt_vars(ys)),
+            Op::PopBinding(ys, x) => write!(f, "let ({}) = pop_binding({x})", fmt_vars(ys)),
+            Op::Hide(x, s, p) => write!(f, "let {x} = hide({s}, {p})"),
+            Op::Open(s, p, h) => write!(f, "let ({s}, {p}) = open({h})"),
+            Op::Unit(_) => write!(f, "unit(<fn>)"),
+        }
+    }
+}
+
+impl fmt::Display for Func {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut printer = Printer {
+            main: &self.name,
+            aux: vec![],
+        };
+        printer.collect(&self.body);
+        for (func, name) in &printer.aux {
+            printer.func(func, name, f)?;
+            writeln!(f)?;
+        }
+        printer.func(self, &self.name, f)
+    }
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let printer = Printer {
+            main: "",
+            aux: vec![],
+        };
+        printer.op(self, f)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Pos {
+    line: usize,
+    col: usize,
+}
+
+impl fmt::Display for Pos {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.col)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    Num(u128),
+    Str(String),
+    Punct(&'static str),
+}
+
+/// Punctuation, with the longer ones first so they're lexed greedily
+const PUNCTS: [&str; 13] = [
+    "=>", "::", "(", ")", "{", "}", ",", ";", ":", ".", "|", "!", "=",
+];
+
+struct Lexer {
+    chars: Vec<char>,
+    idx: usize,
+    pos: Pos,
+}
+
+impl Lexer {
+    fn peek(&self, k: usize) -> Option<char> {
+        self.chars.get(self.idx + k).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek(0)?;
+        self.idx += 1;
+        if c == '\n' {
+            self.pos.line += 1;
+            self.pos.col = 1;
+        } else {
+            self.pos.col += 1;
+        }
+        Some(c)
+    }
+
+    /// Reads the rest of a string literal, undoing the escapes of `{:?}`
+    fn string(&mut self, start: Pos) -> Result<String> {
+        let mut string = String::new();
+        loop {
+            match self.bump() {
+                None => bail!("{start}: Unterminated string"),
+                Some('"') => return Ok(string),
+                Some('\\') => {
+                    let c = match self.bump() {
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('0') => '\0',
+                        Some(c @ ('\\' | '"' | '\'')) => c,
+                        Some('u') if self.bump() == Some('{') => {
+                            let mut hex = String::new();
+                            while let Some(c) = self.bump().filter(|c| *c != '}') {
+                                hex.push(c);
+                            }
+                            u32::from_str_radix(&hex, 16)
+                                .ok()
+                                .and_then(char::from_u32)
+                                .ok_or_else(|| anyhow!("{start}: Invalid unicode escape"))?
+                        }
+                        _ => bail!("{start}: Invalid escape sequence"),
+                    };
+                    string.push(c);
+                }
+                Some(c) => string.push(c),
+            }
+        }
+    }
+
+    fn tokenize(mut self) -> Result<Vec<(Token, Pos)>> {
+        let mut tokens = vec![];
+        while let Some(c) = self.peek(0) {
+            let pos = self.pos;
+            if c.is_whitespace() {
+                self.bump();
+            } else if c == '/' && self.peek(1) == Some('/') {
+                while !matches!(self.bump(), None | Some('\n')) {}
+            } else if c.is_ascii_digit() {
+                let mut num: u128 = 0;
+                while let Some(digit) = self.peek(0).and_then(|c| c.to_digit(10)) {
+                    self.bump();
+                    num = num
+                        .checked_mul(10)
+                        .and_then(|num| num.checked_add(digit.into()))
+                        .ok_or_else(|| anyhow!("{pos}: Number too large"))?;
+                }
+                tokens.push((Token::Num(num), pos));
+            } else if c.is_ascii_alphabetic() || c == '_' {
+                let mut ident = String::new();
+                while let Some(c) = self
+                    .peek(0)
+                    .filter(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '#')
+                {
+                    self.bump();
+                    ident.push(c);
+                }
+                tokens.push((Token::Ident(ident), pos));
+            } else if c == '"' {
+                self.bump();
+                let string = self.string(pos)?;
+                tokens.push((Token::Str(string), pos));
+            } else if let Some(punct) = PUNCTS.into_iter().find(|punct| {
+                punct
+                    .chars()
+                    .enumerate()
+                    .all(|(k, c)| self.peek(k) == Some(c))
+            }) {
+                for _ in 0..punct.len() {
+                    self.bump();
+                }
+                tokens.push((Token::Punct(punct), pos));
+            } else {
+                bail!("{pos}: Unexpected character {c:?}")
+            }
+        }
+        Ok(tokens)
+    }
+}
+
+/// The operations of the form `let y = name(a, b)`
+fn binop(name: &str) -> Option<fn(Var, Var, Var) -> Op> {
+    let op: fn(Var, Var, Var) -> Op = match name {
+        "eq_tag" => Op::EqTag,
+        "eq_val" => Op::EqVal,
+        "and" => Op::And,
+        "or" => Op::Or,
+        "add" => Op::Add,
+        "sub" => Op::Sub,
+        "mul" => Op::Mul,
+        "div" => Op::Div,
+        "lt" => Op::Lt,
+        "bit_and" => Op::BitAnd,
+        "bit_or" => Op::BitOr,
+        "bit_xor" => Op::BitXor,
+        "shl" => Op::Shl,
+        "shr" => Op::Shr,
+        "pow" => Op::Pow,
+        "hide" => Op::Hide,
+        _ => return None,
+    };
+    Some(op)
+}
+
+struct Parser {
+    tokens: Vec<(Token, Pos)>,
+    idx: usize,
+    /// The functions parsed so far, which can be called by the following ones
+    funcs: Vec<Func>,
+}
+
+impl Parser {
+    fn error_at(&self, idx: usize, msg: impl fmt::Display) -> anyhow::Error {
+        match self.tokens.get(idx) {
+            Some((_, pos)) => anyhow!("{pos}: {msg}"),
+            None => anyhow!("End of input: {msg}"),
+        }
+    }
+
+    fn error(&self, msg: impl fmt::Display) -> anyhow::Error {
+        self.error_at(self.idx, msg)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.idx).map(|(token, _)| token)
+    }
+
+    fn is_punct(&self, punct: &str) -> bool {
+        matches!(self.peek(), Some(Token::Punct(p)) if *p == punct)
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(ident)) if ident == keyword)
+    }
+
+    fn eat_punct(&mut self, punct: &str) -> bool {
+        let is_punct = self.is_punct(punct);
+        if is_punct {
+            self.idx += 1;
+        }
+        is_punct
+    }
+
+    fn punct(&mut self, punct: &str) -> Result<()> {
+        if self.eat_punct(punct) {
+            Ok(())
+        } else {
+            Err(self.error(format!("Expected `{punct}`")))
+        }
+    }
+
+    fn keyword(&mut self, keyword: &str) -> Result<()> {
+        if self.is_keyword(keyword) {
+            self.idx += 1;
+            Ok(())
+        } else {
+            Err(self.error(format!("Expected `{keyword}`")))
+        }
+    }
+
+    fn ident(&mut self) -> Result<String> {
+        match self.peek() {
+            Some(Token::Ident(ident)) => {
+                let ident = ident.clone();
+                self.idx += 1;
+                Ok(ident)
+            }
+            _ => Err(self.error("Expected an identifier")),
+        }
+    }
+
+    fn num(&mut self) -> Result<u128> {
+        match self.peek() {
+            Some(Token::Num(num)) => {
+                let num = *num;
+                self.idx += 1;
+                Ok(num)
+            }
+            _ => Err(self.error("Expected a number")),
+        }
+    }
+
+    fn string(&mut self) -> Result<String> {
+        match self.peek() {
+            Some(Token::Str(string)) => {
+                let string = string.clone();
+                self.idx += 1;
+                Ok(string)
+            }
+            _ => Err(self.error("Expected a string")),
+        }
+    }
+
+    fn symbol(&mut self, relative: fn(&str) -> Symbol) -> Result<Symbol> {
+        let start = self.idx;
+        let text = self.string()?;
+        parse_symbol(&text, relative).map_err(|e| self.error_at(start, e))
+    }
+
+    fn var(&mut self) -> Result<Var> {
+        Ok(Var::new(&self.ident()?))
+    }
+
+    /// Parses a parenthesized list of comma separated variables
+    fn vars(&mut self) -> Result<Vec<Var>> {
+        self.punct("(")?;
+        let mut vars = vec![];
+        if !self.eat_punct(")") {
+            loop {
+                vars.push(self.var()?);
+                if self.eat_punct(")") {
+                    break;
+                }
+                self.punct(",")?;
+            }
+        }
+        Ok(vars)
+    }
+
+    fn sized<const N: usize>(&self, start: usize, vars: Vec<Var>) -> Result<[Var; N]> {
+        let len = vars.len();
+        vars.try_into()
+            .map_err(|_| self.error_at(start, format!("Expected {N} variables, found {len}")))
+    }
+
+    fn args<const N: usize>(&mut self) -> Result<[Var; N]> {
+        let start = self.idx;
+        let vars = self.vars()?;
+        self.sized(start, vars)
+    }
+
+    fn tag(&mut self) -> Result<Tag> {
+        let start = self.idx;
+        let kind = self.ident()?;
+        self.punct("::")?;
+        let name = self.ident()?;
+        parse_tag(&kind, &name)
+            .ok_or_else(|| self.error_at(start, format!("Unknown tag `{kind}::{name}`")))
+    }
+
+    fn lit(&mut self) -> Result<Lit> {
+        let start = self.idx;
+        let constr = self.ident()?;
+        self.punct("(")?;
+        let lit = match constr.as_str() {
+            "Num" => Lit::Num(self.num()?),
+            "String" => Lit::String(self.string()?),
+            "Symbol" => Lit::Symbol(self.symbol(lurk_sym)?),
+            _ => return Err(self.error_at(start, format!("Unknown literal `{constr}`"))),
+        };
+        self.punct(")")?;
+        Ok(lit)
+    }
+
+    /// Parses the `("name", xs)` of coroutine and coprocessor calls
+    fn query(&mut self) -> Result<(Symbol, Vec<Var>)> {
+        self.punct("(")?;
+        let name = self.symbol(user_sym)?;
+        let mut args = vec![];
+        while self.eat_punct(",") {
+            args.push(self.var()?);
+        }
+        self.punct(")")?;
+        Ok((name, args))
+    }
+
+    fn func(&mut self) -> Result<Func> {
+        self.keyword("fn")?;
+        let name = self.ident()?;
+        let input_params = self.vars()?;
+        self.punct(":")?;
+        let start = self.idx;
+        let output_size = usize::try_from(self.num()?)
+            .map_err(|_| self.error_at(start, "Output size too large"))?;
+        self.punct("=>")?;
+        let body = self.block()?;
+        Ok(Func::new_unchecked(name, input_params, output_size, body))
+    }
+
+    fn block(&mut self) -> Result<Block> {
+        self.punct("{")?;
+        let block = self.seq()?;
+        self.punct("}")?;
+        Ok(block)
+    }
+
+    /// Parses operations up to a control, which ends the block
+    fn seq(&mut self) -> Result<Block> {
+        let mut ops = vec![];
+        loop {
+            let ctrl = if self.is_keyword("return") {
+                self.idx += 1;
+                let vars = self.vars()?;
+                self.eat_punct(";");
+                Ctrl::Return(vars)
+            } else if self.is_keyword("if") {
+                self.idx += 1;
+                let negated = self.eat_punct("!");
+                let x = self.var()?;
+                let block = self.block()?;
+                let rest = self.seq()?;
+                if negated {
+                    Ctrl::if_(x, rest, block)
+                } else {
+                    Ctrl::if_(x, block, rest)
+                }
+            } else if self.is_keyword("match") {
+                self.idx += 1;
+                self.match_()?
+            } else {
+                ops.push(self.op()?);
+                self.punct(";")?;
+                continue;
+            };
+            return Ok(Block { ops, ctrl });
+        }
+    }
+
+    fn match_(&mut self) -> Result<Ctrl> {
+        let x = self.var()?;
+        self.punct(".")?;
+        let start = self.idx;
+        match self.ident()?.as_str() {
+            "tag" => {
+                self.punct("{")?;
+                let mut cases = IndexMap::new();
+                while !self.eat_punct("}") {
+                    let start = self.idx;
+                    let mut tags = vec![self.tag()?];
+                    while self.eat_punct("|") {
+                        tags.push(self.tag()?);
+                    }
+                    self.punct("=>")?;
+                    let block = self.block()?;
+                    for tag in tags {
+                        if cases.insert(tag, block.clone()).is_some() {
+                            return Err(self.error_at(start, "Repeated tag on `match`"));
+                        }
+                    }
+                }
+                Ok(Ctrl::MatchTag(x, cases, self.default()?))
+            }
+            "value" => {
+                self.punct("{")?;
+                let mut cases = IndexMap::new();
+                let mut lit_type = None;
+                while !self.eat_punct("}") {
+                    let start = self.idx;
+                    let mut lits = vec![self.lit()?];
+                    while self.eat_punct("|") {
+                        lits.push(self.lit()?);
+                    }
+                    self.punct("=>")?;
+                    let block = self.block()?;
+                    for lit in lits {
+                        if *lit_type.get_or_insert(lit.lit_type()) != lit.lit_type() {
+                            return Err(self.error_at(start, "Mixed literal types on `match`"));
+                        }
+                        if cases.insert(lit, block.clone()).is_some() {
+                            return Err(self.error_at(start, "Repeated value on `match`"));
+                        }
+                    }
+                }
+                let lit_type =
+                    lit_type.ok_or_else(|| self.error_at(start, "Empty `match` on value"))?;
+                Ok(Ctrl::MatchValue(x, lit_type, cases, self.default()?))
+            }
+            _ => Err(self.error_at(start, "Expected `tag` or `value`")),
+        }
+    }
+
+    fn default(&mut self) -> Result<Option<Box<Block>>> {
+        if self.eat_punct(";") {
+            Ok(Some(Box::new(self.seq()?)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn op(&mut self) -> Result<Op> {
+        let start = self.idx;
+        match self.ident()?.as_str() {
+            "let" => self.let_(),
+            "emit" => {
+                let [v] = self.args()?;
+                Ok(Op::Emit(v))
+            }
+            "unit" => Err(self.error_at(start, "`unit` has no textual form")),
+            other => Err(self.error_at(start, format!("Unexpected `{other}`"))),
+        }
+    }
+
+    fn let_(&mut self) -> Result<Op> {
+        if self.is_punct("(") {
+            let outs_start = self.idx;
+            let outs = self.vars()?;
+            self.punct("=")?;
+            let start = self.idx;
+            let name = self.ident()?;
+            return match name.as_str() {
+                "div_rem64" => {
+                    let [a, b] = self.args()?;
+                    Ok(Op::DivRem64(self.sized(outs_start, outs)?, a, b))
+                }
+                "decons2" => {
+                    let [x] = self.args()?;
+                    Ok(Op::Decons2(self.sized(outs_start, outs)?, x))
+                }
+                "decons3" => {
+                    let [x] = self.args()?;
+                    Ok(Op::Decons3(self.sized(outs_start, outs)?, x))
+                }
+                "decons4" => {
+                    let [x] = self.args()?;
+                    Ok(Op::Decons4(self.sized(outs_start, outs)?, x))
+                }
+                "pop_binding" => {
+                    let [x] = self.args()?;
+                    Ok(Op::PopBinding(self.sized(outs_start, outs)?, x))
+                }
+                "open" => {
+                    let [h] = self.args()?;
+                    let [s, p] = self.sized(outs_start, outs)?;
+                    Ok(Op::Open(s, p, h))
+                }
+                "CPROC" => {
+                    let (name, args) = self.query()?;
+                    Ok(Op::Cproc(outs, name, args))
+                }
+                _ => {
+                    let func = match self.funcs.iter().rev().find(|func| func.name == name) {
+                        Some(func) => Box::new(func.clone()),
+                        None => {
+                            return Err(self.error_at(start, format!("Unknown function `{name}`")))
+                        }
+                    };
+                    let args = self.vars()?;
+                    if args.len() != func.input_params.len() || outs.len() != func.output_size {
+                        return Err(self.error_at(
+                            start,
+                            format!(
+                                "`{name}` takes {} arguments and returns {} values",
+                                func.input_params.len(),
+                                func.output_size
+                            ),
+                        ));
+                    }
+                    Ok(Op::Call(outs, func, args))
+                }
+            };
+        }
+        let tgt = self.var()?;
+        if self.eat_punct(":") {
+            let tag = self.tag()?;
+            if self.is_punct(";") {
+                return Ok(Op::Zero(tgt, tag));
+            }
+            self.punct("=")?;
+            let start = self.idx;
+            return match self.ident()?.as_str() {
+                "HASH_3_ZEROS" => Ok(Op::Hash3Zeros(tgt, tag)),
+                "HASH_4_ZEROS" => Ok(Op::Hash4Zeros(tgt, tag)),
+                "HASH_6_ZEROS" => Ok(Op::Hash6Zeros(tgt, tag)),
+                "HASH_8_ZEROS" => Ok(Op::Hash8Zeros(tgt, tag)),
+                "cons2" => Ok(Op::Cons2(tgt, tag, self.args()?)),
+                "cons3" => Ok(Op::Cons3(tgt, tag, self.args()?)),
+                "cons4" => Ok(Op::Cons4(tgt, tag, self.args()?)),
+                other => Err(self.error_at(start, format!("Unexpected `{other}`"))),
+            };
+        }
+        self.punct("=")?;
+        if self.eat_punct("!") {
+            self.keyword("recv")?;
+            self.punct("(")?;
+            self.punct(")")?;
+            return Ok(Op::Recv(tgt));
+        }
+        let start = self.idx;
+        let name = self.ident()?;
+        if let Some(binop) = binop(&name) {
+            let [a, b] = self.args()?;
+            return Ok(binop(tgt, a, b));
+        }
+        match name.as_str() {
+            "Num" | "String" | "Symbol" => {
+                self.idx = start;
+                Ok(Op::Lit(tgt, self.lit()?))
+            }
+            "copy" => {
+                let [x] = self.args()?;
+                Ok(Op::Copy(tgt, x))
+            }
+            "not" => {
+                let [a] = self.args()?;
+                Ok(Op::Not(tgt, a))
+            }
+            "inv" => {
+                let [a] = self.args()?;
+                Ok(Op::Inv(tgt, a))
+            }
+            "push_binding" => Ok(Op::PushBinding(tgt, self.args()?)),
+            "cast" => {
+                self.punct("(")?;
+                let x = self.var()?;
+                self.punct(",")?;
+                let tag = self.tag()?;
+                self.punct(")")?;
+                Ok(Op::Cast(tgt, tag, x))
+            }
+            "truncate" => {
+                self.punct("(")?;
+                let a = self.var()?;
+                self.punct(",")?;
+                let start = self.idx;
+                let n = u32::try_from(self.num()?)
+                    .map_err(|_| self.error_at(start, "Too many bits"))?;
+                self.punct(")")?;
+                Ok(Op::Trunc(tgt, a, n))
+            }
+            "QUERY" => {
+                let (name, args) = self.query()?;
+                Ok(Op::Crout(tgt, name, args))
+            }
+            _ => Err(self.error_at(start, format!("Unknown operation `{name}`"))),
+        }
+    }
+}
+
+/// Parses all the functions in `input`, in order, without performing the
+/// transformations and checks of `Func::new`
+pub fn parse_unchecked(input: &str) -> Result<Vec<Func>> {
+    let lexer = Lexer {
+        chars: input.chars().collect(),
+        idx: 0,
+        pos: Pos { line: 1, col: 1 },
+    };
+    let mut parser = Parser {
+        tokens: lexer.tokenize()?,
+        idx: 0,
+        funcs: vec![],
+    };
+    while parser.peek().is_some() {
+        let func = parser.func()?;
+        parser.funcs.push(func);
+    }
+    Ok(parser.funcs)
+}
+
+/// Parses all the functions in `input`, in order, checking each one like
+/// `Func::new` does
+pub fn parse(input: &str) -> Result<Vec<Func>> {
+    parse_unchecked(input)?
+        .into_iter()
+        .map(|func| Func::new(func.name, func.input_params, func.output_size, func.body))
+        .collect()
+}
+
+impl FromStr for Func {
+    type Err = anyhow::Error;
+
+    /// Parses the last function in `input`, which can call the ones before it
+    fn from_str(input: &str) -> Result<Self> {
+        let func = parse_unchecked(input)?
+            .pop()
+            .ok_or_else(|| anyhow!("No function to parse"))?;
+        Func::new(func.name, func.input_params, func.output_size, func.body)
+    }
+}
+
+fn read_lem_file(path: &Path) -> Result<String> {
+    read_to_string(path).with_context(|| format!("Couldn't read {}", path.display()))
+}
+
+impl Func {
+    /// Loads the entry point of the `.lem` file at `path`, which is its last
+    /// function
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        read_lem_file(path.as_ref())?.parse()
+    }
+
+    /// Loads all the functions of the `.lem` file at `path`, in order
+    pub fn all_from_file<P: AsRef<Path>>(path: P) -> Result<Vec<Self>> {
+        parse(&read_lem_file(path.as_ref())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_unchecked;
+    use crate::{
+        aux_func,
+        lem::{eval::eval_step, Func},
+    };
+
+    const SOURCE: &str = r#"fn car_cdr(xs): 2 => {
+    match xs.tag {
+        Expr::Cons => {
+            let (car, cdr) = decons2(xs);
+            return (car, cdr)
+        }
+    };
+    let nil = Symbol("nil");
+    let nil = cast(nil, Expr::Nil);
+    return (nil, nil)
+}
+
+fn head_is_zero(xs): 1 => {
+    let (car, _cdr) = car_cdr(xs);
+    match car.tag {
+        Expr::Num | Expr::U64 => {
+            let zero = Num(0);
+            let is_zero = eq_val(car, zero);
+            if is_zero {
+                let t = Symbol("t");
+                return (t)
+            }
+            let msg = String("not \"zero\"\n");
+            emit(msg);
+            let nil = Symbol("nil");
+            return (nil)
+        }
+    };
+    let err = Symbol(".lurk.user.not-a-number");
+    return (err)
+}
+"#;
+
+    fn head_is_zero() -> Func {
+        let car_cdr = aux_func!(car_cdr(xs): 2 => {
+            match xs.tag {
+                Expr::Cons => {
+                    let (car, cdr) = decons2(xs);
+                    return (car, cdr)
+                }
+            };
+            let nil = Symbol("nil");
+            let nil = cast(nil, Expr::Nil);
+            return (nil, nil)
+        });
+        aux_func!(head_is_zero(xs): 1 => {
+            let (car, _cdr) = car_cdr(xs);
+            match car.tag {
+                Expr::Num | Expr::U64 => {
+                    let zero = Num(0);
+                    let is_zero = eq_val(car, zero);
+                    if !is_zero {
+                        let msg = String("not \"zero\"\n");
+                        emit(msg);
+                        let nil = Symbol("nil");
+                        return (nil)
+                    }
+                    let t = Symbol("t");
+                    return (t)
+                }
+            };
+            let err = QUERY("not-a-number", xs);
+            return (err)
+        })
+    }
+
+    fn source() -> String {
+        SOURCE.replace(
+            "Symbol(\".lurk.user.not-a-number\")",
+            "QUERY(\"not-a-number\", xs)",
+        )
+    }
+
+    #[test]
+    fn test_print() {
+        assert_eq!(head_is_zero().to_string(), source());
+    }
+
+    #[test]
+    fn test_parse() {
+        let source = source();
+        let func = head_is_zero();
+        let funcs = parse_unchecked(&source).unwrap();
+        assert_eq!(funcs.len(), 2);
+        assert_eq!(funcs[1], func);
+        let checked = Func::new(func.name, func.input_params, func.output_size, func.body);
+        assert_eq!(source.parse::<Func>().unwrap(), checked.unwrap());
+
+        // symbol literals outside of `.lurk` are written as absolute paths
+        let funcs = parse_unchecked(SOURCE).unwrap();
+        assert_eq!(funcs[1].to_string(), SOURCE);
+    }
+
+    #[test]
+    fn test_eval_step_round_trip() {
+        let text = eval_step().to_string();
+        let funcs = parse_unchecked(&text).unwrap();
+        assert_eq!(funcs.last().unwrap().to_string(), text);
+        assert!(text.parse::<Func>().is_ok());
+    }
+
+    #[test]
+    fn test_from_file() {
+        let funcs = Func::all_from_file("demo/coroutines.lem").unwrap();
+        let names: Vec<_> = funcs.iter().map(|func| func.name.as_str()).collect();
+        assert_eq!(names, ["id", "factorial", "even", "odd"]);
+        assert_eq!(Func::from_file("demo/coroutines.lem").unwrap(), funcs[3]);
+        assert_eq!(
+            Func::from_file("demo/missing.lem").unwrap_err().to_string(),
+            "Couldn't read demo/missing.lem"
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |source: &str| source.parse::<Func>().unwrap_err().to_string();
+        assert_eq!(
+            error("fn f(x): 1 => {\n    let (y) = g(x);\n    return (y)\n}"),
+            "2:15: Unknown function `g`"
+        );
+        assert_eq!(
+            error("fn f(x): 1 => {\n    let y = cast(x, Expr::Foo);\n    return (y)\n}"),
+            "2:21: Unknown tag `Expr::Foo`"
+        );
+        assert_eq!(
+            error("fn f(x): 1 => {\n    unit(f);\n    return (x)\n}"),
+            "2:5: `unit` has no textual form"
+        );
+        assert_eq!(
+            error("fn f(x): 1 => {\n    return (y)\n}"),
+            "Data for variable y not found"
+        );
+        assert_eq!(error(""), "No function to parse");
+    }
+}
@@ -11,6 +11,7 @@ pub mod dual_channel;
 pub mod error;
 pub mod field;
 mod hash;
+mod int;
 pub mod lang;
 pub mod lem;
 mod num;
@@ -25,6 +26,7 @@ mod syntax_macros;
 pub mod tag;
 mod uint;
 pub mod z_data;
+pub use int::Int;
 pub use num::Num;
 pub use symbol::Symbol;
 pub use uint::UInt;
@@ -11,6 +11,7 @@ use nom_locate::LocatedSpan;
 
 use crate::{
     field::LurkField,
+    int::Int,
     num::Num,
     package::SymbolRef,
     parser::{
@@ -131,7 +132,7 @@ pub fn parse_relative_symbol<F: LurkField>(
     create_unknown_packages: bool,
 ) -> impl Fn(Span<'_>) -> ParseResult<'_, F, SymbolRef> {
     move |from: Span<'_>| {
-        let (i, _) = peek(none_of(",~#(){}[]1234567890."))(from)?;
+        let (i, _) = peek(none_of(",`~#(){}[]1234567890."))(from)?;
         let (upto, path) = parse_symbol_limbs()(i)?;
         intern_path(&state, upto, &path, None, create_unknown_packages)
     }
@@ -195,6 +196,7 @@ pub fn parse_numeric_suffix<F: LurkField>() -> impl Fn(Span<'_>) -> ParseResult<
             tag("i32"),
             tag("i64"),
             tag("i128"),
+            tag("s64"),
             tag("/"),
         ))(from)?;
         Ok((upto, suffix))
@@ -229,7 +231,8 @@ pub fn parse_numeric<F: LurkField>() -> impl Fn(Span<'_>) -> ParseResult<'_, F,
                 }
             }
             // when more uint types are supported we can do:
-            Some("i64") => {
+            // `i64` reads the two's complement into a U64, `s64` reads an I64
+            Some(suffix @ ("i64" | "s64")) => {
                 let mut int_digits = match neg.map(|x| *x.fragment()) {
                     Some("-") => String::from("-"),
                     _ => String::from("+"),
@@ -240,7 +243,11 @@ pub fn parse_numeric<F: LurkField>() -> impl Fn(Span<'_>) -> ParseResult<'_, F,
                         ParseErrorKind::ParseIntErr(e)
                     })?;
                 let pos = Pos::from_upto(from, upto);
-                Ok((upto, Syntax::UInt(pos, UInt::U64(x as u64))))
+                if suffix == "i64" {
+                    Ok((upto, Syntax::UInt(pos, UInt::U64(x as u64))))
+                } else {
+                    Ok((upto, Syntax::Int(pos, Int::I64(x))))
+                }
             }
             // when more uint types are supported we can do:
             #[allow(clippy::unnested_or_patterns)]
@@ -340,6 +347,14 @@ pub fn parse_char<F: LurkField>() -> impl Fn(Span<'_>) -> ParseResult<'_, F, Syn
         let mut chars: Vec<char> = s.chars().collect();
         if chars.len() == 1 {
             let c = chars.pop().unwrap();
+            // in '`'a, the backquote quasiquotes 'a instead
+            let delimited = upto
+                .fragment()
+                .starts_with(|x: char| x.is_whitespace() || "()".contains(x))
+                || upto.fragment().is_empty();
+            if (c == '`' || c == ',') && !delimited {
+                return ParseError::throw(from, ParseErrorKind::InvalidChar(s));
+            }
             let pos = Pos::from_upto(from, upto);
             Ok((upto, Syntax::Char(pos, c)))
         } else {
@@ -411,10 +426,17 @@ pub fn parse_quote<F: LurkField>(
         if let Some(c) = c {
             Ok((i, c))
         } else {
-            let (i, _) = tag("'")(from)?;
+            let (i, prefix) = alt((tag("'"), tag("`"), tag(",@"), tag(",")))(from)?;
             let (upto, s) = parse_syntax(state.clone(), false, create_unknown_packages)(i)?;
             let pos = Pos::from_upto(from, upto);
-            Ok((upto, Syntax::Quote(pos, Box::new(s))))
+            let s = Box::new(s);
+            let syntax = match *prefix.fragment() {
+                "'" => Syntax::Quote(pos, s),
+                "`" => Syntax::Quasiquote(pos, s),
+                ",@" => Syntax::UnquoteSplicing(pos, s),
+                _ => Syntax::Unquote(pos, s),
+            };
+            Ok((upto, syntax))
         }
     }
 }
@@ -468,7 +490,7 @@ pub mod tests {
     use proptest::prelude::*;
 
     use super::*;
-    use crate::{char, keyword, list, num, state::State, str, symbol, uint};
+    use crate::{char, int, keyword, list, num, state::State, str, symbol, uint};
 
     fn test<'a, P, R>(mut p: P, i: &'a str, expected: Option<R>) -> bool
     where
@@ -811,6 +833,9 @@ pub mod tests {
         assert!(test(parse_char(), "'\\t'", Some(char!('\t'))));
         assert!(test(parse_char(), "'('", None));
         assert!(test(parse_char(), "'\\('", Some(char!('('))));
+        assert!(test(parse_char(), "'`'", Some(char!('`'))));
+        assert!(test(parse_char(), "','", Some(char!(','))));
+        assert!(test(parse_char(), "'`'a", None));
     }
 
     #[test]
@@ -877,6 +902,23 @@ pub mod tests {
             "('a)",
             Some(list!([Syntax::Quote(Pos::No, Box::new(symbol!(['a'])))]))
         ));
+        assert!(test(
+            parse_syntax(state(), false, true),
+            "`(a ,b ,@c)",
+            Some(Syntax::Quasiquote(
+                Pos::No,
+                Box::new(list!([
+                    symbol!(["a"]),
+                    Syntax::Unquote(Pos::No, Box::new(symbol!(["b"]))),
+                    Syntax::UnquoteSplicing(Pos::No, Box::new(symbol!(["c"])))
+                ]))
+            ))
+        ));
+        assert!(test(
+            parse_syntax(state(), false, true),
+            ",'a'",
+            Some(Syntax::Unquote(Pos::No, Box::new(char!('a'))))
+        ));
         assert!(test(
             parse_syntax(state(), false, true),
             "('a' 'b' 'c')",
@@ -961,6 +1003,22 @@ pub mod tests {
             "-1i64",
             Some(Syntax::UInt(Pos::No, UInt::U64(u64::MAX))),
         ));
+        assert!(test(
+            parse_numeric(),
+            "-1s64",
+            Some(Syntax::Int(Pos::No, Int::I64(-1))),
+        ));
+        assert!(test(
+            parse_numeric(),
+            "-9223372036854775808s64",
+            Some(Syntax::Int(Pos::No, Int::I64(i64::MIN))),
+        ));
+        assert!(test(
+            parse_numeric(),
+            "0x7fffffffffffffffs64",
+            Some(Syntax::Int(Pos::No, Int::I64(i64::MAX))),
+        ));
+        assert!(test(parse_numeric(), "9223372036854775808s64", None,));
         assert!(test(
             parse_numeric(),
             "18446744073709551615u64",
@@ -1029,6 +1087,11 @@ pub mod tests {
             "11242421860377074631u64",
             Some(uint!(11242421860377074631))
         ));
+        assert!(test(
+            parse_syntax(state(), false, true),
+            "-5s64",
+            Some(int!(-5))
+        ));
         assert!(test(
             parse_syntax(state(), false, true),
             ":\u{ae}\u{60500}\u{87}..)",
@@ -1107,6 +1170,35 @@ pub mod tests {
         assert_eq!(x, res)
     }
 
+    #[test]
+    fn test_quasiquote_chars_round_trip() {
+        // backquotes and commas only start quasiquote syntax where a form
+        // starts, so they stand for themselves in characters and symbols
+        let unquoted_char =
+            Syntax::Quasiquote(Pos::No, Box::new(list!([char!(','), symbol!(["a"])])));
+        let quoted_quasiquote = Syntax::Quote(
+            Pos::No,
+            Box::new(Syntax::Quasiquote(
+                Pos::No,
+                Box::new(Syntax::Quote(Pos::No, Box::new(symbol!(["a"])))),
+            )),
+        );
+        let cases: [(Syntax<Scalar>, &str); 6] = [
+            (char!('`'), "'`'"),
+            (char!(','), "','"),
+            (symbol!(["a`b"]), ".a`b"),
+            (symbol!(["a,b"]), ".a\\,b"),
+            (unquoted_char, "`(',' .a)"),
+            (quoted_quasiquote, "'`'.a"),
+        ];
+        for (x, text) in cases {
+            assert_eq!(text, format!("{x}"));
+            let (_, res) = parse_syntax(State::default().rccell(), false, true)(Span::new(text))
+                .expect("valid parse");
+            assert_eq!(x, res)
+        }
+    }
+
     proptest! {
         #[test]
         fn prop_syntax(x in any::<Syntax<Scalar>>()) {
@@ -13,7 +13,7 @@ pub mod nova;
 pub mod supernova;
 
 #[cfg(test)]
-mod tests;
+pub(crate) mod tests;
 
 use ff::Field;
 use std::{borrow::Cow, sync::Arc};
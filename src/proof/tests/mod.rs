@@ -10,6 +10,7 @@ use std::sync::Arc;
 use crate::{
     coprocessor::Coprocessor,
     dual_channel::pair_terminals,
+    field::LurkField,
     lang::Lang,
     lem::{eval::EvalConfig, pointers::Ptr, store::Store},
     proof::{
@@ -37,6 +38,12 @@ fn mismatch<T: PartialEq + Copy>(a: &[T], b: &[T]) -> Option<(usize, (Option<T>,
     }
 }
 
+/// Interns the error continuation with `reason` and the offending expression
+/// read from `expr`
+pub(crate) fn error_cont<F: LurkField>(s: &Store<F>, reason: &str, expr: &str) -> Ptr {
+    s.intern_error(reason, s.read_with_default_state(expr).unwrap())
+}
+
 fn test_aux<F: CurveCycleEquipped, C: Coprocessor<F>>(
     s: &Arc<Store<F>>,
     expr: &str,
@@ -267,7 +274,16 @@ fn nova_test_full_aux2<'a, F: CurveCycleEquipped, C: Coprocessor<F> + 'a>(
         assert!(s.ptr_eq(&expected_env, output.env()));
     }
     if let Some(expected_cont) = expected_cont {
-        assert_eq!(&expected_cont, output.cont());
+        // error continuations also record the reason and the offending expression
+        if let Some((expected_reason, expected_expr)) = s.fetch_error(&expected_cont) {
+            let (reason, expr) = s
+                .fetch_error(output.cont())
+                .expect("expected an error continuation");
+            assert_eq!(expected_reason, reason);
+            assert!(s.ptr_eq(&expected_expr, &expr));
+        } else {
+            assert_eq!(&expected_cont, output.cont());
+        }
     } else {
         assert_eq!(&s.get_cont_terminal(), output.cont());
     }
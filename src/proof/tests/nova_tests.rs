@@ -3,15 +3,17 @@ use halo2curves::bn256::Fr;
 use std::sync::Arc;
 
 use crate::{
+    dual_channel::dummy_terminal,
     lang::{Coproc, Lang},
-    lem::{store::Store, tag::Tag},
+    lem::{eval::evaluate_simple, store::Store, tag::Tag},
     num::Num,
     state::{user_sym, State, StateRcCell},
     tag::{ExprTag, Op, Op1, Op2},
 };
 
 use super::{
-    nova_test_full_aux, nova_test_full_aux2, test_aux, test_aux_ptr, DEFAULT_REDUCTION_COUNT,
+    error_cont, nova_test_full_aux, nova_test_full_aux2, test_aux, test_aux_ptr,
+    DEFAULT_REDUCTION_COUNT,
 };
 
 #[test]
@@ -182,25 +184,23 @@ fn test_prove_num_equal() {
 fn test_prove_invalid_num_equal() {
     let s = Arc::new(Store::<Fr>::default());
     let expected = s.intern_nil();
-    let error = s.cont_error();
     test_aux::<_, Coproc<_>>(
         &s,
         "(= 5 nil)",
         Some(expected),
         None,
-        Some(error),
+        Some(error_cont(&s, "non-numeric argument", "nil")),
         None,
         &expect!["3"],
         &None,
     );
 
-    let expected = s.num_u64(5);
     test_aux::<_, Coproc<_>>(
         &s,
         "(= nil 5)",
         Some(expected),
         None,
-        Some(error),
+        Some(error_cont(&s, "non-numeric argument", "nil")),
         None,
         &expect!["3"],
         &None,
@@ -259,7 +259,7 @@ fn test_prove_equal() {
 #[test]
 fn test_prove_quote_end_is_nil_error() {
     let s = Arc::new(Store::<Fr>::default());
-    let error = s.cont_error();
+    let error = error_cont(&s, "malformed expression", "(quote (1) (2))");
     test_aux::<_, Coproc<_>>(
         &s,
         "(quote (1) (2))",
@@ -305,11 +305,12 @@ fn test_prove_if() {
 #[test]
 fn test_prove_if_end_is_nil_error() {
     let s = Arc::new(Store::<Fr>::default());
-    let expected = s.num_u64(5);
-    let error = s.cont_error();
+    let expr = "(if nil 5 6 7)";
+    let expected = s.read_with_default_state(expr).unwrap();
+    let error = error_cont(&s, "malformed expression", expr);
     test_aux::<_, Coproc<_>>(
         &s,
-        "(if nil 5 6 7)",
+        expr,
         Some(expected),
         None,
         Some(error),
@@ -604,7 +605,7 @@ fn test_prove_evaluate_sum() {
 fn test_prove_binop_rest_is_nil() {
     let s = Arc::new(Store::<Fr>::default());
     let expected = s.num_u64(9);
-    let error = s.cont_error();
+    let error = error_cont(&s, "arity mismatch", "9");
     test_aux::<_, Coproc<_>>(
         &s,
         "(- 9 8 7)",
@@ -629,7 +630,6 @@ fn test_prove_binop_rest_is_nil() {
 
 fn op_syntax_error<T: Op + Copy>() {
     let s = Arc::new(Store::<Fr>::default());
-    let error = s.cont_error();
     let test = |op: T| {
         let name = op.symbol_name();
 
@@ -641,7 +641,7 @@ fn op_syntax_error<T: Op + Copy>() {
                 &expr,
                 None,
                 None,
-                Some(error),
+                Some(error_cont(&s, "arity mismatch", &expr)),
                 None,
                 &expect!["1"],
                 &None,
@@ -655,7 +655,7 @@ fn op_syntax_error<T: Op + Copy>() {
                 &expr,
                 None,
                 None,
-                Some(error),
+                Some(error_cont(&s, "arity mismatch", &expr)),
                 None,
                 &expect!["1"],
                 &None,
@@ -669,7 +669,7 @@ fn op_syntax_error<T: Op + Copy>() {
                 &expr,
                 None,
                 None,
-                Some(error),
+                Some(error_cont(&s, "arity mismatch", &expr)),
                 None,
                 &expect!["1"],
                 &None,
@@ -679,10 +679,11 @@ fn op_syntax_error<T: Op + Copy>() {
         if !op.supports_arity(3) {
             let expr = format!("({name} 123 456 789)");
             tracing::debug!("{:?}", &expr);
-            let iterations = if op.supports_arity(2) {
-                &expect!["2"]
+            // binops only find the extra argument once the first one is evaluated
+            let (error, iterations) = if op.supports_arity(2) {
+                (error_cont(&s, "arity mismatch", "123"), &expect!["2"])
             } else {
-                &expect!["1"]
+                (error_cont(&s, "arity mismatch", &expr), &expect!["1"])
             };
             test_aux::<_, Coproc<_>>(&s, &expr, None, None, Some(error), None, iterations, &None);
         }
@@ -762,7 +763,7 @@ fn test_prove_quotient() {
 fn test_prove_error_div_by_zero() {
     let s = Arc::new(Store::<Fr>::default());
     let expected = s.num_u64(0);
-    let error = s.cont_error();
+    let error = error_cont(&s, "division by zero", "0");
     test_aux::<_, Coproc<_>>(
         &s,
         "(/ 21 0)",
@@ -779,7 +780,7 @@ fn test_prove_error_div_by_zero() {
 fn test_prove_error_invalid_type_and_not_cons() {
     let s = Arc::new(Store::<Fr>::default());
     let expected = s.intern_nil();
-    let error = s.cont_error();
+    let error = error_cont(&s, "non-numeric argument", "nil");
     test_aux::<_, Coproc<_>>(
         &s,
         "(/ 21 nil)",
@@ -835,7 +836,7 @@ fn test_prove_current_env_simple() {
 fn test_prove_current_env_rest_is_nil_error() {
     let s = Arc::new(Store::<Fr>::default());
     let expected = s.read_with_default_state("(current-env a)").unwrap();
-    let error = s.cont_error();
+    let error = error_cont(&s, "arity mismatch", "(current-env a)");
     test_aux::<_, Coproc<_>>(
         &s,
         "(current-env a)",
@@ -870,7 +871,7 @@ fn test_prove_let_simple() {
 #[test]
 fn test_prove_let_end_is_nil_error() {
     let s = Arc::new(Store::<Fr>::default());
-    let error = s.cont_error();
+    let error = error_cont(&s, "malformed expression", "(let ((a 1 2)) a)");
     test_aux::<_, Coproc<_>>(
         &s,
         "(let ((a 1 2)) a)",
@@ -886,7 +887,7 @@ fn test_prove_let_end_is_nil_error() {
 #[test]
 fn test_prove_letrec_end_is_nil_error() {
     let s = Arc::new(Store::<Fr>::default());
-    let error = s.cont_error();
+    let error = error_cont(&s, "malformed expression", "(letrec ((a 1 2)) a)");
     test_aux::<_, Coproc<_>>(
         &s,
         "(letrec ((a 1 2)) a)",
@@ -902,7 +903,7 @@ fn test_prove_letrec_end_is_nil_error() {
 #[test]
 fn test_prove_lambda_empty_error() {
     let s = Arc::new(Store::<Fr>::default());
-    let error = s.cont_error();
+    let error = error_cont(&s, "malformed expression", "(lambda (x))");
     test_aux::<_, Coproc<_>>(
         &s,
         "((lambda (x)) 0)",
@@ -918,7 +919,7 @@ fn test_prove_lambda_empty_error() {
 #[test]
 fn test_prove_let_empty_error() {
     let s = Arc::new(Store::<Fr>::default());
-    let error = s.cont_error();
+    let error = error_cont(&s, "malformed expression", "(let)");
     test_aux::<_, Coproc<_>>(
         &s,
         "(let)",
@@ -934,7 +935,7 @@ fn test_prove_let_empty_error() {
 #[test]
 fn test_prove_let_empty_body_error() {
     let s = Arc::new(Store::<Fr>::default());
-    let error = s.cont_error();
+    let error = error_cont(&s, "malformed expression", "(let ((a 1)))");
     test_aux::<_, Coproc<_>>(
         &s,
         "(let ((a 1)))",
@@ -950,7 +951,7 @@ fn test_prove_let_empty_body_error() {
 #[test]
 fn test_prove_letrec_empty_error() {
     let s = Arc::new(Store::<Fr>::default());
-    let error = s.cont_error();
+    let error = error_cont(&s, "malformed expression", "(letrec)");
     test_aux::<_, Coproc<_>>(
         &s,
         "(letrec)",
@@ -966,7 +967,7 @@ fn test_prove_letrec_empty_error() {
 #[test]
 fn test_prove_letrec_empty_body_error() {
     let s = Arc::new(Store::<Fr>::default());
-    let error = s.cont_error();
+    let error = error_cont(&s, "malformed expression", "(letrec ((a 1)))");
     test_aux::<_, Coproc<_>>(
         &s,
         "(letrec ((a 1)))",
@@ -999,7 +1000,7 @@ fn test_prove_let_body_nil() {
 #[test]
 fn test_prove_let_rest_body_is_nil_error() {
     let s = Arc::new(Store::<Fr>::default());
-    let error = s.cont_error();
+    let error = error_cont(&s, "malformed expression", "(let ((a 1)) a 1)");
     test_aux::<_, Coproc<_>>(
         &s,
         "(let ((a 1)) a 1)",
@@ -1015,7 +1016,7 @@ fn test_prove_let_rest_body_is_nil_error() {
 #[test]
 fn test_prove_letrec_rest_body_is_nil_error() {
     let s = Arc::new(Store::<Fr>::default());
-    let error = s.cont_error();
+    let error = error_cont(&s, "malformed expression", "(letrec ((a 1)) a 1)");
     test_aux::<_, Coproc<_>>(
         &s,
         "(letrec ((a 1)) a 1)",
@@ -1364,23 +1365,46 @@ fn test_prove_no_mutual_recursion() {
                                   (odd (- n 1)))))
                       (odd (lambda (n)
                              (even (- n 1)))))
-                    ;; NOTE: This is not true mutual-recursion.
-                    ;; However, it exercises the behavior of LETREC.
+                    ;; NOTE: `odd` calls `even`, which stops before calling
+                    ;; `odd` back, so this never looks up a later binding.
                     (odd 1))",
         Some(expected),
         None,
         Some(terminal),
         None,
-        &expect!["19"],
+        &expect!["18"],
         &None,
     );
 }
 
 #[test]
-#[ignore]
-fn test_prove_no_mutual_recursion_error() {
+fn test_mutual_recursion_without_base_case() {
+    // `odd` has no base case, so `(odd 2)` calls `even` with a negative number,
+    // which calls `odd` back, and never terminates
+    let s = Store::<Fr>::default();
+    let limit = 100;
+    let expr = s
+        .read_with_default_state(
+            "(letrec ((even (lambda (n)
+                              (if (= 0 n)
+                                  t
+                                  (odd (- n 1)))))
+                      (odd (lambda (n)
+                             (even (- n 1)))))
+                    (odd 2))",
+        )
+        .unwrap();
+
+    let (_, iterations) =
+        evaluate_simple::<Fr, Coproc<Fr>>(None, expr, &s, limit, &dummy_terminal()).unwrap();
+    assert_eq!(limit, iterations);
+}
+
+#[test]
+fn test_prove_mutual_recursion_forward_reference() {
     let s = Arc::new(Store::<Fr>::default());
-    let error = s.cont_error();
+    let expected = s.intern_nil();
+    let terminal = s.cont_terminal();
     test_aux::<_, Coproc<_>>(
         &s,
         "(letrec ((even (lambda (n)
@@ -1388,15 +1412,40 @@ fn test_prove_no_mutual_recursion_error() {
                                   t
                                   (odd (- n 1)))))
                       (odd (lambda (n)
-                             (even (- n 1)))))
-                    ;; NOTE: This is not true mutual-recursion.
-                    ;; However, it exercises the behavior of LETREC.
-                    (odd 2))",
+                             (if (= 0 n)
+                                 nil
+                                 (even (- n 1))))))
+                    (even 1))",
+        Some(expected),
         None,
+        Some(terminal),
         None,
-        Some(error),
+        &expect!["23"],
+        &None,
+    );
+}
+
+#[test]
+fn test_prove_mutual_recursion() {
+    let s = Arc::new(Store::<Fr>::default());
+    let expected = s.intern_lurk_symbol("t");
+    let terminal = s.cont_terminal();
+    test_aux::<_, Coproc<_>>(
+        &s,
+        "(letrec ((even (lambda (n)
+                              (if (= 0 n)
+                                  t
+                                  (odd (- n 1)))))
+                      (odd (lambda (n)
+                             (if (= 0 n)
+                                 nil
+                                 (even (- n 1))))))
+                    (even 2))",
+        Some(expected),
         None,
-        &expect!["20"],
+        Some(terminal),
+        None,
+        &expect!["34"],
         &None,
     );
 }
@@ -1422,7 +1471,7 @@ fn test_prove_cons1() {
 #[test]
 fn test_prove_car_end_is_nil_error() {
     let s = Arc::new(Store::<Fr>::default());
-    let error = s.cont_error();
+    let error = error_cont(&s, "arity mismatch", "(car (1 2) 3)");
     test_aux::<_, Coproc<_>>(
         &s,
         "(car (1 2) 3)",
@@ -1438,7 +1487,7 @@ fn test_prove_car_end_is_nil_error() {
 #[test]
 fn test_prove_cdr_end_is_nil_error() {
     let s = Arc::new(Store::<Fr>::default());
-    let error = s.cont_error();
+    let error = error_cont(&s, "arity mismatch", "(cdr (1 2) 3)");
     test_aux::<_, Coproc<_>>(
         &s,
         "(cdr (1 2) 3)",
@@ -1454,7 +1503,7 @@ fn test_prove_cdr_end_is_nil_error() {
 #[test]
 fn test_prove_atom_end_is_nil_error() {
     let s = Arc::new(Store::<Fr>::default());
-    let error = s.cont_error();
+    let error = error_cont(&s, "arity mismatch", "(atom 123 4)");
     test_aux::<_, Coproc<_>>(
         &s,
         "(atom 123 4)",
@@ -1470,7 +1519,7 @@ fn test_prove_atom_end_is_nil_error() {
 #[test]
 fn test_prove_emit_end_is_nil_error() {
     let s = Arc::new(Store::<Fr>::default());
-    let error = s.cont_error();
+    let error = error_cont(&s, "arity mismatch", "(emit 123 4)");
     test_aux::<_, Coproc<_>>(
         &s,
         "(emit 123 4)",
@@ -1562,7 +1611,8 @@ fn test_prove_zero_arg_lambda3() {
 #[test]
 fn test_prove_zero_arg_lambda4() {
     let s = Arc::new(Store::<Fr>::default());
-    let error = s.cont_error();
+    let fun = s.intern_fun(s.intern_nil(), s.num_u64(123), s.intern_empty_env());
+    let error = s.intern_error("arity mismatch", fun);
     test_aux::<_, Coproc<_>>(
         &s,
         "((lambda () 123) 1)",
@@ -1700,7 +1750,7 @@ fn test_prove_multiple_letrec_bindings() {
         None,
         Some(terminal),
         None,
-        &expect!["68"],
+        &expect!["67"],
         &None,
     );
 }
@@ -1724,7 +1774,7 @@ fn test_prove_tail_call2() {
         None,
         Some(terminal),
         None,
-        &expect!["71"],
+        &expect!["70"],
         &None,
     );
 }
@@ -1744,7 +1794,7 @@ fn test_prove_multiple_letrecstar_bindings() {
         None,
         Some(terminal),
         None,
-        &expect!["19"],
+        &expect!["18"],
         &None,
     );
 }
@@ -1764,7 +1814,7 @@ fn test_prove_multiple_letrecstar_bindings_referencing() {
         None,
         Some(terminal),
         None,
-        &expect!["25"],
+        &expect!["24"],
         &None,
     );
 }
@@ -1795,7 +1845,7 @@ fn test_prove_multiple_letrecstar_bindings_recursive() {
         None,
         Some(terminal),
         None,
-        &expect!["144"],
+        &expect!["142"],
         &None,
     );
 }
@@ -1817,7 +1867,7 @@ fn test_prove_dont_discard_rest_env() {
         None,
         Some(terminal),
         None,
-        &expect!["14"],
+        &expect!["12"],
         &None,
     );
 }
@@ -1843,7 +1893,7 @@ fn test_prove_fibonacci() {
         None,
         Some(terminal),
         None,
-        &expect!["35"],
+        &expect!["34"],
         5,
         false,
         None,
@@ -1907,7 +1957,7 @@ fn test_prove_chained_functional_commitment() {
         None,
         Some(terminal),
         None,
-        &expect!["22"],
+        &expect!["21"],
         &None,
     );
 }
@@ -2035,7 +2085,7 @@ fn test_prove_strcons() {
 #[test]
 fn test_prove_str_cons_error() {
     let s = Arc::new(Store::<Fr>::default());
-    let error = s.cont_error();
+    let error = error_cont(&s, "invalid argument", "123");
     test_aux::<_, Coproc<_>>(
         &s,
         r"(strcons #\a 123)",
@@ -2051,7 +2101,7 @@ fn test_prove_str_cons_error() {
 #[test]
 fn test_prove_one_arg_cons_error() {
     let s = Arc::new(Store::<Fr>::default());
-    let error = s.cont_error();
+    let error = error_cont(&s, "arity mismatch", r#"(cons "")"#);
     test_aux::<_, Coproc<_>>(
         &s,
         r#"(cons "")"#,
@@ -2101,13 +2151,12 @@ fn test_prove_cdr_nil() {
 #[test]
 fn test_prove_car_cdr_invalid_tag_error_sym() {
     let s = Arc::new(Store::<Fr>::default());
-    let error = s.cont_error();
     test_aux::<_, Coproc<_>>(
         &s,
         r#"(car car)"#,
         None,
         None,
-        Some(error),
+        Some(error_cont(&s, "unbound variable", "car")),
         None,
         &expect!["2"],
         &None,
@@ -2117,7 +2166,7 @@ fn test_prove_car_cdr_invalid_tag_error_sym() {
         r#"(cdr car)"#,
         None,
         None,
-        Some(error),
+        Some(error_cont(&s, "unbound variable", "car")),
         None,
         &expect!["2"],
         &None,
@@ -2127,13 +2176,12 @@ fn test_prove_car_cdr_invalid_tag_error_sym() {
 #[test]
 fn test_prove_car_cdr_invalid_tag_error_char() {
     let s = Arc::new(Store::<Fr>::default());
-    let error = s.cont_error();
     test_aux::<_, Coproc<_>>(
         &s,
         r"(car #\a)",
         None,
         None,
-        Some(error),
+        Some(error_cont(&s, "car of non-list", r"#\a")),
         None,
         &expect!["2"],
         &None,
@@ -2143,7 +2191,7 @@ fn test_prove_car_cdr_invalid_tag_error_char() {
         r"(cdr #\a)",
         None,
         None,
-        Some(error),
+        Some(error_cont(&s, "cdr of non-list", r"#\a")),
         None,
         &expect!["2"],
         &None,
@@ -2153,13 +2201,12 @@ fn test_prove_car_cdr_invalid_tag_error_char() {
 #[test]
 fn test_prove_car_cdr_invalid_tag_error_num() {
     let s = Arc::new(Store::<Fr>::default());
-    let error = s.cont_error();
     test_aux::<_, Coproc<_>>(
         &s,
         r#"(car 42)"#,
         None,
         None,
-        Some(error),
+        Some(error_cont(&s, "car of non-list", "42")),
         None,
         &expect!["2"],
         &None,
@@ -2169,7 +2216,7 @@ fn test_prove_car_cdr_invalid_tag_error_num() {
         r#"(cdr 42)"#,
         None,
         None,
-        Some(error),
+        Some(error_cont(&s, "cdr of non-list", "42")),
         None,
         &expect!["2"],
         &None,
@@ -2207,13 +2254,14 @@ fn test_prove_car_cdr_of_cons() {
 #[test]
 fn test_prove_car_cdr_invalid_tag_error_lambda() {
     let s = Arc::new(Store::<Fr>::default());
-    let error = s.cont_error();
+    let x = s.intern_user_symbol("x");
+    let fun = s.intern_fun(s.list(vec![x]), x, s.intern_empty_env());
     test_aux::<_, Coproc<_>>(
         &s,
         r#"(car (lambda (x) x))"#,
         None,
         None,
-        Some(error),
+        Some(s.intern_error("car of non-list", fun)),
         None,
         &expect!["2"],
         &None,
@@ -2223,7 +2271,7 @@ fn test_prove_car_cdr_invalid_tag_error_lambda() {
         r#"(cdr (lambda (x) x))"#,
         None,
         None,
-        Some(error),
+        Some(s.intern_error("cdr of non-list", fun)),
         None,
         &expect!["2"],
         &None,
@@ -2252,7 +2300,7 @@ fn test_prove_hide_open() {
 fn test_prove_hide_wrong_secret_type() {
     let s = Arc::new(Store::<Fr>::default());
     let expr = "(hide 'x 456)";
-    let error = s.cont_error();
+    let error = error_cont(&s, "non-numeric argument", "x");
     test_aux::<_, Coproc<_>>(
         &s,
         expr,
@@ -2341,7 +2389,7 @@ fn test_prove_commit_open() {
 fn test_prove_commit_error() {
     let s = Arc::new(Store::<Fr>::default());
     let expr = "(commit 123 456)";
-    let error = s.cont_error();
+    let error = error_cont(&s, "arity mismatch", expr);
     test_aux::<_, Coproc<_>>(
         &s,
         expr,
@@ -2358,7 +2406,7 @@ fn test_prove_commit_error() {
 fn test_prove_open_error() {
     let s = Arc::new(Store::<Fr>::default());
     let expr = "(open 123 456)";
-    let error = s.cont_error();
+    let error = error_cont(&s, "arity mismatch", expr);
     test_aux::<_, Coproc<_>>(
         &s,
         expr,
@@ -2375,7 +2423,7 @@ fn test_prove_open_error() {
 fn test_prove_open_wrong_type() {
     let s = Arc::new(Store::<Fr>::default());
     let expr = "(open 'asdf)";
-    let error = s.cont_error();
+    let error = error_cont(&s, "invalid argument", "asdf");
     test_aux::<_, Coproc<_>>(
         &s,
         expr,
@@ -2392,7 +2440,7 @@ fn test_prove_open_wrong_type() {
 fn test_prove_secret_wrong_type() {
     let s = Arc::new(Store::<Fr>::default());
     let expr = "(secret 'asdf)";
-    let error = s.cont_error();
+    let error = error_cont(&s, "invalid argument", "asdf");
     test_aux::<_, Coproc<_>>(
         &s,
         expr,
@@ -2620,13 +2668,12 @@ fn test_prove_num_invalid_tag() {
     let expr = "(num (quote x))";
     let expr1 = "(num \"asdf\")";
     let expr2 = "(num '(1))";
-    let error = s.cont_error();
     test_aux::<_, Coproc<_>>(
         &s,
         expr,
         None,
         None,
-        Some(error),
+        Some(error_cont(&s, "non-numeric argument", "x")),
         None,
         &expect!["2"],
         &None,
@@ -2636,7 +2683,7 @@ fn test_prove_num_invalid_tag() {
         expr1,
         None,
         None,
-        Some(error),
+        Some(error_cont(&s, "non-numeric argument", r#""asdf""#)),
         None,
         &expect!["2"],
         &None,
@@ -2646,7 +2693,7 @@ fn test_prove_num_invalid_tag() {
         expr2,
         None,
         None,
-        Some(error),
+        Some(error_cont(&s, "non-numeric argument", "(1)")),
         None,
         &expect!["2"],
         &None,
@@ -2659,13 +2706,12 @@ fn test_prove_comm_invalid_tag() {
     let expr = "(comm (quote x))";
     let expr1 = "(comm \"asdf\")";
     let expr2 = "(comm '(1))";
-    let error = s.cont_error();
     test_aux::<_, Coproc<_>>(
         &s,
         expr,
         None,
         None,
-        Some(error),
+        Some(error_cont(&s, "non-numeric argument", "x")),
         None,
         &expect!["2"],
         &None,
@@ -2675,7 +2721,7 @@ fn test_prove_comm_invalid_tag() {
         expr1,
         None,
         None,
-        Some(error),
+        Some(error_cont(&s, "non-numeric argument", r#""asdf""#)),
         None,
         &expect!["2"],
         &None,
@@ -2685,7 +2731,7 @@ fn test_prove_comm_invalid_tag() {
         expr2,
         None,
         None,
-        Some(error),
+        Some(error_cont(&s, "non-numeric argument", "(1)")),
         None,
         &expect!["2"],
         &None,
@@ -2698,13 +2744,12 @@ fn test_prove_char_invalid_tag() {
     let expr = "(char (quote x))";
     let expr1 = "(char \"asdf\")";
     let expr2 = "(char '(1))";
-    let error = s.cont_error();
     test_aux::<_, Coproc<_>>(
         &s,
         expr,
         None,
         None,
-        Some(error),
+        Some(error_cont(&s, "non-numeric argument", "x")),
         None,
         &expect!["2"],
         &None,
@@ -2714,7 +2759,7 @@ fn test_prove_char_invalid_tag() {
         expr1,
         None,
         None,
-        Some(error),
+        Some(error_cont(&s, "non-numeric argument", r#""asdf""#)),
         None,
         &expect!["2"],
         &None,
@@ -2724,7 +2769,7 @@ fn test_prove_char_invalid_tag() {
         expr2,
         None,
         None,
-        Some(error),
+        Some(error_cont(&s, "non-numeric argument", "(1)")),
         None,
         &expect!["2"],
         &None,
@@ -2783,7 +2828,6 @@ fn test_str_car_cdr_cons() {
     let empty = s.intern_string("");
     let nil = s.intern_nil();
     let terminal = s.cont_terminal();
-    let error = s.cont_error();
 
     test_aux::<_, Coproc<_>>(
         &s,
@@ -2852,7 +2896,7 @@ fn test_str_car_cdr_cons() {
         r"(strcons #\a #\b)",
         None,
         None,
-        Some(error),
+        Some(error_cont(&s, "invalid argument", r"#\b")),
         None,
         &expect!["3"],
         &None,
@@ -2863,7 +2907,7 @@ fn test_str_car_cdr_cons() {
         r#"(strcons "a" "b")"#,
         None,
         None,
-        Some(error),
+        Some(error_cont(&s, "invalid argument", r#""b""#)),
         None,
         &expect!["3"],
         &None,
@@ -2874,7 +2918,7 @@ fn test_str_car_cdr_cons() {
         r#"(strcons 1 2)"#,
         None,
         None,
-        Some(error),
+        Some(error_cont(&s, "invalid argument", "2")),
         None,
         &expect!["3"],
         &None,
@@ -3165,7 +3209,7 @@ fn test_prove_complicated_functional_commitment() {
         None,
         Some(terminal),
         None,
-        &expect!["68"],
+        &expect!["67"],
         &None,
     );
 }
@@ -3217,7 +3261,7 @@ fn test_prove_reduce_sym_contradiction_regression() {
     let s = Arc::new(Store::<Fr>::default());
 
     let expr = "(eval 'a '(nil))";
-    let error = s.cont_error();
+    let error = error_cont(&s, "invalid argument", "(nil)");
 
     test_aux::<_, Coproc<_>>(
         &s,
@@ -3281,7 +3325,7 @@ fn test_prove_test_env_not_nil_and_binding_nil() {
 
     let expr = "(let ((a 1) (b 2)) c)";
 
-    let error = s.cont_error();
+    let error = error_cont(&s, "unbound variable", "c");
     test_aux::<_, Coproc<_>>(
         &s,
         expr,
@@ -3298,7 +3342,7 @@ fn test_prove_test_env_not_nil_and_binding_nil() {
 fn test_prove_test_eval_bad_form() {
     let s = Arc::new(Store::<Fr>::default());
     let expr = "(* 5 (eval '(+ 1 a) '((0 . 3))))"; // two-arg eval, optional second arg is env. This tests for error on malformed env.
-    let error = s.cont_error();
+    let error = error_cont(&s, "invalid argument", "((0 . 3))");
 
     test_aux::<_, Coproc<_>>(
         &s,
@@ -3454,7 +3498,7 @@ fn test_prove_test_u64_div() {
     let expr3 = "(/ 100u64 0u64)";
 
     let terminal = s.cont_terminal();
-    let error = s.cont_error();
+    let error = error_cont(&s, "division by zero", "0u64");
 
     test_aux::<_, Coproc<_>>(
         &s,
@@ -3501,7 +3545,7 @@ fn test_prove_test_u64_mod() {
     let expr3 = "(% 100u64 0u64)";
 
     let terminal = s.cont_terminal();
-    let error = s.cont_error();
+    let error = error_cont(&s, "division by zero", "0u64");
 
     test_aux::<_, Coproc<_>>(
         &s,
@@ -3543,14 +3587,12 @@ fn test_prove_test_num_mod() {
     let expr2 = "(% 100 3u64)";
     let expr3 = "(% 100u64 3)";
 
-    let error = s.cont_error();
-
     test_aux::<_, Coproc<_>>(
         &s,
         expr,
         None,
         None,
-        Some(error),
+        Some(error_cont(&s, "invalid argument", "100")),
         None,
         &expect!["3"],
         &None,
@@ -3560,7 +3602,7 @@ fn test_prove_test_num_mod() {
         expr2,
         None,
         None,
-        Some(error),
+        Some(error_cont(&s, "invalid argument", "100")),
         None,
         &expect!["3"],
         &None,
@@ -3570,7 +3612,7 @@ fn test_prove_test_num_mod() {
         expr3,
         None,
         None,
-        Some(error),
+        Some(error_cont(&s, "invalid argument", "3")),
         None,
         &expect!["3"],
         &None,
@@ -3826,7 +3868,7 @@ fn test_prove_test_hide_u64_secret() {
     let s = Arc::new(Store::<Fr>::default());
 
     let expr = "(hide 0u64 123)";
-    let error = s.cont_error();
+    let error = error_cont(&s, "invalid argument", "0u64");
 
     test_aux::<_, Coproc<_>>(
         &s,
@@ -3845,7 +3887,7 @@ fn test_prove_test_mod_by_zero_error() {
     let s = Arc::new(Store::<Fr>::default());
 
     let expr = "(% 0 0)";
-    let error = s.cont_error();
+    let error = error_cont(&s, "invalid argument", "0");
 
     test_aux::<_, Coproc<_>>(
         &s,
@@ -3863,7 +3905,7 @@ fn test_prove_test_mod_by_zero_error() {
 fn test_prove_dotted_syntax_error() {
     let s = Arc::new(Store::<Fr>::default());
     let expr = "(let ((a (lambda (x) (+ x 1)))) (a . 1))";
-    let error = s.cont_error();
+    let error = error_cont(&s, "malformed expression", "(a . 1)");
 
     test_aux::<_, Coproc<_>>(
         &s,
@@ -3908,14 +3950,13 @@ fn test_prove_call_literal_fun() {
 #[test]
 fn test_prove_lambda_body_syntax() {
     let s = Arc::new(Store::<Fr>::default());
-    let error = s.cont_error();
 
     test_aux::<_, Coproc<_>>(
         &s,
         "((lambda ()))",
         None,
         None,
-        Some(error),
+        Some(error_cont(&s, "malformed expression", "(lambda ())")),
         None,
         &expect!["2"],
         &None,
@@ -3925,7 +3966,7 @@ fn test_prove_lambda_body_syntax() {
         "((lambda () 1 2))",
         None,
         None,
-        Some(error),
+        Some(error_cont(&s, "malformed expression", "(lambda () 1 2)")),
         None,
         &expect!["2"],
         &None,
@@ -3935,7 +3976,7 @@ fn test_prove_lambda_body_syntax() {
         "((lambda (x)) 1)",
         None,
         None,
-        Some(error),
+        Some(error_cont(&s, "malformed expression", "(lambda (x))")),
         None,
         &expect!["2"],
         &None,
@@ -3945,7 +3986,7 @@ fn test_prove_lambda_body_syntax() {
         "((lambda (x) 1 2) 1)",
         None,
         None,
-        Some(error),
+        Some(error_cont(&s, "malformed expression", "(lambda (x) 1 2)")),
         None,
         &expect!["2"],
         &None,
@@ -3956,7 +3997,6 @@ fn test_prove_lambda_body_syntax() {
 #[ignore]
 fn test_prove_non_symbol_binding_error() {
     let s = Arc::new(Store::<Fr>::default());
-    let error = s.cont_error();
 
     let test = |x| {
         let expr = format!("(let (({x} 123)) {x})");
@@ -3968,7 +4008,7 @@ fn test_prove_non_symbol_binding_error() {
             &expr,
             None,
             None,
-            Some(error),
+            Some(error_cont(&s, "malformed expression", &expr)),
             None,
             &expect!["1"],
             &None,
@@ -3978,7 +4018,7 @@ fn test_prove_non_symbol_binding_error() {
             &expr2,
             None,
             None,
-            Some(error),
+            Some(error_cont(&s, "malformed expression", &expr2)),
             None,
             &expect!["1"],
             &None,
@@ -3988,7 +4028,7 @@ fn test_prove_non_symbol_binding_error() {
             &expr3,
             None,
             None,
-            Some(error),
+            Some(error_cont(&s, "malformed expression", &expr3)),
             None,
             &expect!["1"],
             &None,
@@ -4005,7 +4045,6 @@ fn test_prove_non_symbol_binding_error() {
 #[test]
 fn test_prove_head_with_sym_mimicking_value() {
     let s = Arc::new(Store::<Fr>::default());
-    let error = s.cont_error();
 
     let mk_expr = |s: &Store<Fr>, state: StateRcCell, name, args| {
         let sym_as_char = s.intern_lurk_symbol(name).cast(Tag::Expr(ExprTag::Char));
@@ -4022,7 +4061,7 @@ fn test_prove_head_with_sym_mimicking_value() {
             expr,
             None,
             None,
-            Some(error),
+            Some(s.intern_error("not a function", expr)),
             None,
             &expect!["1"],
             &None,
@@ -4036,7 +4075,7 @@ fn test_prove_head_with_sym_mimicking_value() {
             expr,
             None,
             None,
-            Some(error),
+            Some(s.intern_error("not a function", expr)),
             None,
             &expect!["1"],
             &None,
@@ -4050,7 +4089,7 @@ fn test_prove_head_with_sym_mimicking_value() {
             expr,
             None,
             None,
-            Some(error),
+            Some(s.intern_error("not a function", expr)),
             None,
             &expect!["1"],
             &None,
@@ -4064,7 +4103,7 @@ fn test_prove_head_with_sym_mimicking_value() {
             expr,
             None,
             None,
-            Some(error),
+            Some(s.intern_error("not a function", expr)),
             None,
             &expect!["1"],
             &None,
@@ -4078,7 +4117,7 @@ fn test_prove_head_with_sym_mimicking_value() {
             expr,
             None,
             None,
-            Some(error),
+            Some(s.intern_error("not a function", expr)),
             None,
             &expect!["1"],
             &None,
@@ -4092,7 +4131,7 @@ fn test_prove_head_with_sym_mimicking_value() {
             expr,
             None,
             None,
-            Some(error),
+            Some(s.intern_error("not a function", expr)),
             None,
             &expect!["1"],
             &None,
@@ -4106,7 +4145,7 @@ fn test_prove_head_with_sym_mimicking_value() {
             expr,
             None,
             None,
-            Some(error),
+            Some(s.intern_error("not a function", expr)),
             None,
             &expect!["1"],
             &None,
@@ -4151,6 +4190,7 @@ fn test_dumb_lang() {
     let error6 = s.char('x');
     let error7 = s.char('y');
 
+    // the coprocessor itself doesn't record why it failed
     let error = s.cont_error();
     let terminal = s.cont_terminal();
     let lang = Arc::new(lang);
@@ -4190,7 +4230,7 @@ fn test_dumb_lang() {
         expr4,
         Some(error4),
         None,
-        Some(error),
+        Some(s.intern_error("arity mismatch", error4)),
         None,
         &expect!["4"],
         &Some(lang.clone()),
@@ -4200,7 +4240,7 @@ fn test_dumb_lang() {
         expr5,
         Some(error5),
         None,
-        Some(error),
+        Some(s.intern_error("arity mismatch", error5)),
         None,
         &expect!["2"],
         &Some(lang.clone()),
@@ -4487,7 +4527,7 @@ fn test_letrec_sequencing() {
         None,
         Some(terminal),
         None,
-        &expect!["7"],
+        &expect!["6"],
         &None,
     );
 }
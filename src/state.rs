@@ -239,7 +239,7 @@ const LURK_PACKAGE_SYMBOL_NAME: &str = "lurk";
 const USER_PACKAGE_SYMBOL_NAME: &str = "user";
 const META_PACKAGE_SYMBOL_NAME: &str = "meta";
 
-const LURK_PACKAGE_SYMBOLS_NAMES: [&str; 36] = [
+const LURK_PACKAGE_SYMBOLS_NAMES: [&str; 83] = [
     "atom",
     "begin",
     "car",
@@ -261,6 +261,7 @@ const LURK_PACKAGE_SYMBOLS_NAMES: [&str; 36] = [
     "nil",
     "num",
     "u64",
+    "i64",
     "open",
     "quote",
     "secret",
@@ -276,11 +277,59 @@ const LURK_PACKAGE_SYMBOLS_NAMES: [&str; 36] = [
     ">",
     "<=",
     ">=",
+    "lognot",
+    "logand",
+    "logor",
+    "logxor",
+    "shl",
+    "shr",
+    "vector",
+    "vector-length",
+    "vector?",
+    "vector-ref",
+    "vector-set",
+    "catch",
+    "throw",
+    "apply",
+    "&rest",
+    "cond",
+    "and",
+    "or",
+    "when",
+    "unless",
+    "match",
+    "string-length",
+    "string->list",
+    "string-append",
+    "string<",
+    "substring",
+    "num->string",
+    "string->num",
+    "u64->string",
+    "char->num",
+    "symbol->string",
+    "string->symbol",
+    "symbol-package",
+    "keyword?",
+    "expt",
+    "inverse",
+    "receive",
+    "let-values",
+    "values",
+    "call/cc",
+    "env->list",
+    "env-lookup",
+    "env-extend",
+    "+!",
+    "-!",
+    "*!",
 ];
 
-const META_PACKAGE_SYMBOLS_NAMES: [&str; 28] = [
+const META_PACKAGE_SYMBOLS_NAMES: [&str; 30] = [
     "def",
     "defrec",
+    "defmacro",
+    "defstruct",
     "load",
     "assert",
     "assert-eq",
@@ -204,8 +204,8 @@ impl Symbol {
             let head = &self.path[0];
             head.is_empty()
                 || head.starts_with([
-                    '~', '#', '1', '2', '3', '4', '5', '6', '7', '8', '9', '0', '.', ':', '[', ']',
-                    '(', ')', '{', '}', '"', '\\',
+                    '~', '#', '`', '1', '2', '3', '4', '5', '6', '7', '8', '9', '0', '.', ':', '[',
+                    ']', '(', ')', '{', '}', '"', '\\',
                 ])
                 || head.starts_with("-1")
                 || head.starts_with("-2")
@@ -347,6 +347,7 @@ pub(crate) mod test {
         assert!(Symbol::sym(&[""]).prints_as_absolute());
         assert!(Symbol::sym(&["~"]).prints_as_absolute());
         assert!(Symbol::sym(&["#"]).prints_as_absolute());
+        assert!(Symbol::sym(&["`"]).prints_as_absolute());
         assert!(Symbol::sym(&["1"]).prints_as_absolute());
         assert!(Symbol::sym(&["2"]).prints_as_absolute());
         assert!(Symbol::sym(&["3"]).prints_as_absolute());
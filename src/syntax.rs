@@ -1,6 +1,7 @@
 use std::fmt;
 
 use crate::field::LurkField;
+use crate::int::Int;
 use crate::num::Num;
 use crate::package::SymbolRef;
 use crate::parser::position::Pos;
@@ -16,6 +17,8 @@ pub enum Syntax<F: LurkField> {
     Num(Pos, Num<F>),
     /// A u64 integer: 1u64, 0xffu64
     UInt(Pos, UInt),
+    /// An i64 integer: -1s64, 0xffs64
+    Int(Pos, Int),
     /// A hierarchical symbol: foo, foo.bar.baz or keyword :foo
     Symbol(Pos, SymbolRef),
     /// A string literal: "foobar", "foo\nbar"
@@ -24,6 +27,12 @@ pub enum Syntax<F: LurkField> {
     Char(Pos, char),
     /// A quoted expression: 'a, '(1 2)
     Quote(Pos, Box<Syntax<F>>),
+    /// A quasiquoted expression: `a, `(1 ,x)
+    Quasiquote(Pos, Box<Syntax<F>>),
+    /// An unquoted expression inside a quasiquote: ,a
+    Unquote(Pos, Box<Syntax<F>>),
+    /// A spliced expression inside a quasiquote: ,@a
+    UnquoteSplicing(Pos, Box<Syntax<F>>),
     /// A nil-terminated cons-list of expressions: (1 2 3)
     List(Pos, Vec<Syntax<F>>),
     /// An improper cons-list of expressions: (1 2 . 3)
@@ -36,10 +45,14 @@ impl<F: LurkField> Syntax<F> {
         match self {
             Self::Num(pos, _)
             | Self::UInt(pos, _)
+            | Self::Int(pos, _)
             | Self::Symbol(pos, _)
             | Self::String(pos, _)
             | Self::Char(pos, _)
             | Self::Quote(pos, _)
+            | Self::Quasiquote(pos, _)
+            | Self::Unquote(pos, _)
+            | Self::UnquoteSplicing(pos, _)
             | Self::List(pos, _)
             | Self::Improper(pos, ..) => pos,
         }
@@ -56,6 +69,7 @@ impl<Fr: LurkField> Arbitrary for Syntax<Fr> {
         let leaf = prop_oneof![
             any::<Num<Fr>>().prop_map(|x| Syntax::Num(Pos::No, x)),
             any::<UInt>().prop_map(|x| Syntax::UInt(Pos::No, x)),
+            any::<Int>().prop_map(|x| Syntax::Int(Pos::No, x)),
             any::<Symbol>().prop_map(|x| Syntax::Symbol(Pos::No, x.into())),
             any::<String>().prop_map(|x| Syntax::String(Pos::No, x)),
             any::<char>().prop_map(|x| Syntax::Char(Pos::No, x))
@@ -65,6 +79,15 @@ impl<Fr: LurkField> Arbitrary for Syntax<Fr> {
                 inner
                     .clone()
                     .prop_map(|x| Syntax::Quote(Pos::No, Box::new(x))),
+                inner
+                    .clone()
+                    .prop_map(|x| Syntax::Quasiquote(Pos::No, Box::new(x))),
+                inner
+                    .clone()
+                    .prop_map(|x| Syntax::Unquote(Pos::No, Box::new(x))),
+                inner
+                    .clone()
+                    .prop_map(|x| Syntax::UnquoteSplicing(Pos::No, Box::new(x))),
                 prop::collection::vec(inner.clone(), 0..10).prop_map(|x| Syntax::List(Pos::No, x)),
                 prop::collection::vec(inner, 2..12).prop_map(|mut xs| {
                     let x = xs.pop().unwrap();
@@ -81,6 +104,7 @@ impl<F: LurkField> fmt::Display for Syntax<F> {
         match self {
             Self::Num(_, x) => write!(f, "{x}"),
             Self::UInt(_, x) => write!(f, "{x}u64"),
+            Self::Int(_, x) => write!(f, "{x}s64"),
             Self::Symbol(_, x) => write!(f, "{x}"),
             Self::String(_, x) => write!(f, "\"{}\"", x.escape_default()),
             Self::Char(_, x) => {
@@ -91,6 +115,9 @@ impl<F: LurkField> fmt::Display for Syntax<F> {
                 }
             }
             Self::Quote(_, x) => write!(f, "'{x}"),
+            Self::Quasiquote(_, x) => write!(f, "`{x}"),
+            Self::Unquote(_, x) => write!(f, ",{x}"),
+            Self::UnquoteSplicing(_, x) => write!(f, ",@{x}"),
             Self::List(_, xs) => {
                 let mut iter = xs.iter().peekable();
                 write!(f, "(")?;
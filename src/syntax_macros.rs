@@ -21,6 +21,16 @@ macro_rules! uint {
     };
 }
 
+#[macro_export]
+macro_rules! int {
+    ($f:ty, $i:literal) => {
+        $crate::syntax::Syntax::<$f>::Int(Pos::No, $crate::int::Int::I64($i))
+    };
+    ($i:literal) => {
+        $crate::syntax::Syntax::Int(Pos::No, $crate::int::Int::I64($i))
+    };
+}
+
 #[macro_export]
 macro_rules! str {
     ($f:ty, $i:literal) => {
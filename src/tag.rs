@@ -22,7 +22,7 @@ pub trait Tag:
 }
 
 pub(crate) const EXPR_TAG_INIT: u16 = 0b0000_0000_0000_0000;
-/// A tag for expressions. Note that ExprTag, ContTag, Op1, Op2 all live in the same u16 namespace
+/// A tag for expressions. Note that ExprTag, ContTag, Op1, Op2, Op3 all live in the same u16 namespace
 #[derive(
     Debug,
     Copy,
@@ -55,6 +55,11 @@ pub enum ExprTag {
     Env,
     Rec,
     Prov,
+    I64,
+    Vector,
+    Cont,
+    RecEnv,
+    RecTop,
 }
 
 impl From<ExprTag> for u16 {
@@ -87,6 +92,11 @@ impl fmt::Display for ExprTag {
             ExprTag::Env => write!(f, "env#"),
             ExprTag::Rec => write!(f, "rec#"),
             ExprTag::Prov => write!(f, "prov#"),
+            ExprTag::I64 => write!(f, "i64#"),
+            ExprTag::Vector => write!(f, "vector#"),
+            ExprTag::Cont => write!(f, "cont#"),
+            ExprTag::RecEnv => write!(f, "rec_env#"),
+            ExprTag::RecTop => write!(f, "rec_top#"),
         }
     }
 }
@@ -132,6 +142,10 @@ pub enum ContTag {
     Call,
     Call2,
     Tail,
+    /// Records the reason of an evaluation error (a string) and the offending
+    /// expression. Until the error continuation is built by `make_thunk`, the
+    /// reason is passed in the `cont` slot of the step's outputs, next to the
+    /// `error` control symbol
     Error,
     Lookup,
     Unop,
@@ -147,6 +161,32 @@ pub enum ContTag {
     StreamStart,
     StreamDispatch,
     StreamPause,
+    Vector,
+    VectorBuild,
+    VectorRef,
+    VectorSet,
+    VectorNode,
+    Ternop,
+    Ternop2,
+    Ternop3,
+    Catch,
+    Rest,
+    RestReverse,
+    Cond,
+    And,
+    Or,
+    Match,
+    MatchPattern,
+    Splice,
+    StrScan,
+    StrBuild,
+    Digits,
+    Receive,
+    Values,
+    EnvScan,
+    Quasiquote,
+    Quasiquote2,
+    Quasiquote3,
 }
 
 impl From<ContTag> for u16 {
@@ -201,6 +241,32 @@ impl fmt::Display for ContTag {
             ContTag::StreamStart => write!(f, "stream-start#"),
             ContTag::StreamDispatch => write!(f, "stream-dispatch#"),
             ContTag::StreamPause => write!(f, "stream-pause#"),
+            ContTag::Vector => write!(f, "vector#"),
+            ContTag::VectorBuild => write!(f, "vector-build#"),
+            ContTag::VectorRef => write!(f, "vector-ref#"),
+            ContTag::VectorSet => write!(f, "vector-set#"),
+            ContTag::VectorNode => write!(f, "vector-node#"),
+            ContTag::Ternop => write!(f, "ternop#"),
+            ContTag::Ternop2 => write!(f, "ternop2#"),
+            ContTag::Ternop3 => write!(f, "ternop3#"),
+            ContTag::Catch => write!(f, "catch#"),
+            ContTag::Rest => write!(f, "rest#"),
+            ContTag::RestReverse => write!(f, "rest-reverse#"),
+            ContTag::Cond => write!(f, "cond#"),
+            ContTag::And => write!(f, "and#"),
+            ContTag::Or => write!(f, "or#"),
+            ContTag::Match => write!(f, "match#"),
+            ContTag::MatchPattern => write!(f, "match-pattern#"),
+            ContTag::Splice => write!(f, "splice#"),
+            ContTag::StrScan => write!(f, "str-scan#"),
+            ContTag::StrBuild => write!(f, "str-build#"),
+            ContTag::Digits => write!(f, "digits#"),
+            ContTag::Receive => write!(f, "receive#"),
+            ContTag::Values => write!(f, "values#"),
+            ContTag::EnvScan => write!(f, "env-scan#"),
+            ContTag::Quasiquote => write!(f, "quasiquote#"),
+            ContTag::Quasiquote2 => write!(f, "quasiquote2#"),
+            ContTag::Quasiquote3 => write!(f, "quasiquote3#"),
         }
     }
 }
@@ -235,6 +301,21 @@ pub enum Op1 {
     Char,
     Eval,
     U64,
+    LogNot,
+    I64,
+    VectorLength,
+    StringLength,
+    StringToList,
+    NumToString,
+    StringToNum,
+    U64ToString,
+    CharToNum,
+    SymbolToString,
+    SymbolPackage,
+    IsKeyword,
+    Inverse,
+    EnvToList,
+    IsVector,
 }
 
 impl From<Op1> for u16 {
@@ -294,6 +375,21 @@ impl Op for Op1 {
             Op1::Char => "char",
             Op1::Eval => "eval",
             Op1::U64 => "u64",
+            Op1::LogNot => "lognot",
+            Op1::I64 => "i64",
+            Op1::VectorLength => "vector-length",
+            Op1::StringLength => "string-length",
+            Op1::StringToList => "string->list",
+            Op1::NumToString => "num->string",
+            Op1::StringToNum => "string->num",
+            Op1::U64ToString => "u64->string",
+            Op1::CharToNum => "char->num",
+            Op1::SymbolToString => "symbol->string",
+            Op1::SymbolPackage => "symbol-package",
+            Op1::IsKeyword => "keyword?",
+            Op1::Inverse => "inverse",
+            Op1::EnvToList => "env->list",
+            Op1::IsVector => "vector?",
         }
     }
 
@@ -311,6 +407,21 @@ impl Op for Op1 {
             &Op1::Char,
             &Op1::Eval,
             &Op1::U64,
+            &Op1::LogNot,
+            &Op1::I64,
+            &Op1::VectorLength,
+            &Op1::StringLength,
+            &Op1::StringToList,
+            &Op1::NumToString,
+            &Op1::StringToNum,
+            &Op1::U64ToString,
+            &Op1::CharToNum,
+            &Op1::SymbolToString,
+            &Op1::SymbolPackage,
+            &Op1::IsKeyword,
+            &Op1::Inverse,
+            &Op1::EnvToList,
+            &Op1::IsVector,
         ]
     }
 
@@ -334,6 +445,21 @@ impl fmt::Display for Op1 {
             Op1::Char => write!(f, "char#"),
             Op1::Eval => write!(f, "eval#"),
             Op1::U64 => write!(f, "u64#"),
+            Op1::LogNot => write!(f, "lognot#"),
+            Op1::I64 => write!(f, "i64#"),
+            Op1::VectorLength => write!(f, "vector-length#"),
+            Op1::StringLength => write!(f, "string-length#"),
+            Op1::StringToList => write!(f, "string->list#"),
+            Op1::NumToString => write!(f, "num->string#"),
+            Op1::StringToNum => write!(f, "string->num#"),
+            Op1::U64ToString => write!(f, "u64->string#"),
+            Op1::CharToNum => write!(f, "char->num#"),
+            Op1::SymbolToString => write!(f, "symbol->string#"),
+            Op1::SymbolPackage => write!(f, "symbol-package#"),
+            Op1::IsKeyword => write!(f, "keyword?#"),
+            Op1::Inverse => write!(f, "inverse#"),
+            Op1::EnvToList => write!(f, "env->list#"),
+            Op1::IsVector => write!(f, "vector?#"),
         }
     }
 }
@@ -372,6 +498,23 @@ pub enum Op2 {
     Hide,
     Modulo,
     Eval,
+    LogAnd,
+    LogOr,
+    LogXor,
+    Shl,
+    Shr,
+    VectorRef,
+    Catch,
+    Throw,
+    Apply,
+    StringAppend,
+    StringLess,
+    StringToSymbol,
+    Expt,
+    EnvLookup,
+    CheckedSum,
+    CheckedDiff,
+    CheckedProduct,
 }
 
 impl From<Op2> for u16 {
@@ -417,6 +560,9 @@ impl Op2 {
                 | Op2::GreaterEqual
                 | Op2::NumEqual
                 | Op2::Modulo
+                | Op2::CheckedSum
+                | Op2::CheckedDiff
+                | Op2::CheckedProduct
         )
     }
 }
@@ -440,6 +586,23 @@ impl Op for Op2 {
             Op2::Hide => "hide",
             Op2::Modulo => "%",
             Op2::Eval => "eval",
+            Op2::LogAnd => "logand",
+            Op2::LogOr => "logor",
+            Op2::LogXor => "logxor",
+            Op2::Shl => "shl",
+            Op2::Shr => "shr",
+            Op2::VectorRef => "vector-ref",
+            Op2::Catch => "catch",
+            Op2::Throw => "throw",
+            Op2::Apply => "apply",
+            Op2::StringAppend => "string-append",
+            Op2::StringLess => "string<",
+            Op2::StringToSymbol => "string->symbol",
+            Op2::Expt => "expt",
+            Op2::EnvLookup => "env-lookup",
+            Op2::CheckedSum => "+!",
+            Op2::CheckedDiff => "-!",
+            Op2::CheckedProduct => "*!",
         }
     }
 
@@ -461,11 +624,31 @@ impl Op for Op2 {
             &Op2::Hide,
             &Op2::Modulo,
             &Op2::Eval,
+            &Op2::LogAnd,
+            &Op2::LogOr,
+            &Op2::LogXor,
+            &Op2::Shl,
+            &Op2::Shr,
+            &Op2::VectorRef,
+            &Op2::Catch,
+            &Op2::Throw,
+            &Op2::Apply,
+            &Op2::StringAppend,
+            &Op2::StringLess,
+            &Op2::StringToSymbol,
+            &Op2::Expt,
+            &Op2::EnvLookup,
+            &Op2::CheckedSum,
+            &Op2::CheckedDiff,
+            &Op2::CheckedProduct,
         ]
     }
 
     fn supports_arity(&self, n: usize) -> bool {
-        matches!((self, n), (Op2::Begin, _) | (Op2::Eval, 1 | 2) | (_, 2))
+        matches!(
+            (self, n),
+            (Op2::Begin, _) | (Op2::Eval | Op2::StringToSymbol, 1 | 2) | (_, 2)
+        )
     }
 }
 
@@ -488,6 +671,103 @@ impl fmt::Display for Op2 {
             Op2::Hide => write!(f, "hide"),
             Op2::Modulo => write!(f, "modulo"),
             Op2::Eval => write!(f, "eval#"),
+            Op2::LogAnd => write!(f, "logand#"),
+            Op2::LogOr => write!(f, "logor#"),
+            Op2::LogXor => write!(f, "logxor#"),
+            Op2::Shl => write!(f, "shl#"),
+            Op2::Shr => write!(f, "shr#"),
+            Op2::VectorRef => write!(f, "vector-ref#"),
+            Op2::Catch => write!(f, "catch#"),
+            Op2::Throw => write!(f, "throw#"),
+            Op2::Apply => write!(f, "apply#"),
+            Op2::StringAppend => write!(f, "string-append#"),
+            Op2::StringLess => write!(f, "string-less#"),
+            Op2::StringToSymbol => write!(f, "string->symbol#"),
+            Op2::Expt => write!(f, "expt#"),
+            Op2::EnvLookup => write!(f, "env-lookup#"),
+            Op2::CheckedSum => write!(f, "checked-sum#"),
+            Op2::CheckedDiff => write!(f, "checked-diff#"),
+            Op2::CheckedProduct => write!(f, "checked-product#"),
+        }
+    }
+}
+
+pub(crate) const OP3_TAG_INIT: u16 = 0b0100_0000_0000_0000;
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Serialize_repr,
+    Deserialize_repr,
+    TryFromRepr,
+    EnumCount,
+)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Arbitrary))]
+#[repr(u16)]
+pub enum Op3 {
+    VectorSet = OP3_TAG_INIT,
+    Substring,
+    EnvExtend,
+}
+
+impl From<Op3> for u16 {
+    fn from(val: Op3) -> Self {
+        val as u16
+    }
+}
+
+impl From<Op3> for u64 {
+    fn from(val: Op3) -> Self {
+        val as u64
+    }
+}
+
+impl Tag for Op3 {
+    fn from_field<F: LurkField>(f: &F) -> Option<Self> {
+        Self::try_from(f.to_u16()?).ok()
+    }
+
+    fn to_field<F: From<u64> + ff::Field>(&self) -> F {
+        F::from(*self as u64)
+    }
+
+    fn to_field_bytes<F: LurkField>(&self) -> F::Repr {
+        let mut res = F::Repr::default();
+        let u: u16 = (*self).into();
+        res.as_mut()[..2].copy_from_slice(&u.to_le_bytes());
+        res
+    }
+}
+
+impl Op for Op3 {
+    fn symbol_name(&self) -> &'static str {
+        match self {
+            Op3::VectorSet => "vector-set",
+            Op3::Substring => "substring",
+            Op3::EnvExtend => "env-extend",
+        }
+    }
+
+    fn all() -> Vec<&'static Self> {
+        vec![&Op3::VectorSet, &Op3::Substring, &Op3::EnvExtend]
+    }
+
+    fn supports_arity(&self, n: usize) -> bool {
+        n == 3
+    }
+}
+
+impl fmt::Display for Op3 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Op3::VectorSet => write!(f, "vector-set#"),
+            Op3::Substring => write!(f, "substring#"),
+            Op3::EnvExtend => write!(f, "env-extend#"),
         }
     }
 }
@@ -523,6 +803,11 @@ pub(crate) mod tests {
             (ExprTag::Env, 12),
             (ExprTag::Rec, 13),
             (ExprTag::Prov, 14),
+            (ExprTag::I64, 15),
+            (ExprTag::Vector, 16),
+            (ExprTag::Cont, 17),
+            (ExprTag::RecEnv, 18),
+            (ExprTag::RecTop, 19),
         ]);
         assert_eq!(map.len(), ExprTag::COUNT);
         assert_tags_u16s(map)
@@ -551,6 +836,32 @@ pub(crate) mod tests {
             (ContTag::StreamStart, 4113),
             (ContTag::StreamDispatch, 4114),
             (ContTag::StreamPause, 4115),
+            (ContTag::Vector, 4116),
+            (ContTag::VectorBuild, 4117),
+            (ContTag::VectorRef, 4118),
+            (ContTag::VectorSet, 4119),
+            (ContTag::VectorNode, 4120),
+            (ContTag::Ternop, 4121),
+            (ContTag::Ternop2, 4122),
+            (ContTag::Ternop3, 4123),
+            (ContTag::Catch, 4124),
+            (ContTag::Rest, 4125),
+            (ContTag::RestReverse, 4126),
+            (ContTag::Cond, 4127),
+            (ContTag::And, 4128),
+            (ContTag::Or, 4129),
+            (ContTag::Match, 4130),
+            (ContTag::MatchPattern, 4131),
+            (ContTag::Splice, 4132),
+            (ContTag::StrScan, 4133),
+            (ContTag::StrBuild, 4134),
+            (ContTag::Digits, 4135),
+            (ContTag::Receive, 4136),
+            (ContTag::Values, 4137),
+            (ContTag::EnvScan, 4138),
+            (ContTag::Quasiquote, 4139),
+            (ContTag::Quasiquote2, 4140),
+            (ContTag::Quasiquote3, 4141),
         ]);
         assert_eq!(map.len(), ContTag::COUNT);
         assert_tags_u16s(map)
@@ -571,6 +882,21 @@ pub(crate) mod tests {
             (Op1::Char, 8201),
             (Op1::Eval, 8202),
             (Op1::U64, 8203),
+            (Op1::LogNot, 8204),
+            (Op1::I64, 8205),
+            (Op1::VectorLength, 8206),
+            (Op1::StringLength, 8207),
+            (Op1::StringToList, 8208),
+            (Op1::NumToString, 8209),
+            (Op1::StringToNum, 8210),
+            (Op1::U64ToString, 8211),
+            (Op1::CharToNum, 8212),
+            (Op1::SymbolToString, 8213),
+            (Op1::SymbolPackage, 8214),
+            (Op1::IsKeyword, 8215),
+            (Op1::Inverse, 8216),
+            (Op1::EnvToList, 8217),
+            (Op1::IsVector, 8218),
         ]);
         assert_eq!(map.len(), Op1::COUNT);
         assert_tags_u16s(map)
@@ -595,11 +921,39 @@ pub(crate) mod tests {
             (Op2::Hide, 12301),
             (Op2::Modulo, 12302),
             (Op2::Eval, 12303),
+            (Op2::LogAnd, 12304),
+            (Op2::LogOr, 12305),
+            (Op2::LogXor, 12306),
+            (Op2::Shl, 12307),
+            (Op2::Shr, 12308),
+            (Op2::VectorRef, 12309),
+            (Op2::Catch, 12310),
+            (Op2::Throw, 12311),
+            (Op2::Apply, 12312),
+            (Op2::StringAppend, 12313),
+            (Op2::StringLess, 12314),
+            (Op2::StringToSymbol, 12315),
+            (Op2::Expt, 12316),
+            (Op2::EnvLookup, 12317),
+            (Op2::CheckedSum, 12318),
+            (Op2::CheckedDiff, 12319),
+            (Op2::CheckedProduct, 12320),
         ]);
         assert_eq!(map.len(), Op2::COUNT);
         assert_tags_u16s(map)
     }
 
+    #[test]
+    fn stable_op3() {
+        let map = HashMap::from_iter([
+            (Op3::VectorSet, 16384),
+            (Op3::Substring, 16385),
+            (Op3::EnvExtend, 16386),
+        ]);
+        assert_eq!(map.len(), Op3::COUNT);
+        assert_tags_u16s(map)
+    }
+
     proptest! {
     #[test]
     fn prop_expr_tag_u16(x in any::<ExprTag>()) {
@@ -635,4 +989,13 @@ pub(crate) mod tests {
         assert_eq!(x, x2)
     }
     }
+
+    proptest! {
+    #[test]
+    fn prop_op3_u16(x in any::<Op3>()) {
+        let x_u16: u16 = x.into();
+        let x2 = Op3::try_from(x_u16).expect("read Op3 from u16");
+        assert_eq!(x, x2)
+    }
+    }
 }
@@ -11,7 +11,7 @@ use crate::hash::PoseidonCache;
 use crate::tag::{ExprTag, Tag};
 use crate::z_ptr::{ZContPtr, ZExprPtr, ZPtr};
 use crate::z_store::ZStore;
-use crate::UInt;
+use crate::{Int, UInt};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(
@@ -46,6 +46,7 @@ pub enum ZExpr<F: LurkField> {
     Thunk(ZExprPtr<F>, ZContPtr<F>),
     Char(char),
     UInt(UInt),
+    Int(Int),
 }
 
 impl<F: LurkField> std::fmt::Display for ZExpr<F> {
@@ -71,6 +72,7 @@ impl<F: LurkField> std::fmt::Display for ZExpr<F> {
             ZExpr::Char(x) => write!(f, "(char {x})"),
             ZExpr::Num(x) => write!(f, "(num  {x:?})"),
             ZExpr::UInt(x) => write!(f, "(uint {x})"),
+            ZExpr::Int(x) => write!(f, "(int {x})"),
         }
     }
 }
@@ -125,6 +127,7 @@ impl<F: LurkField> ZExpr<F> {
             ZExpr::UInt(x) => match x {
                 UInt::U64(x) => ZPtr(ExprTag::U64, F::from_u64(*x)),
             },
+            ZExpr::Int(x) => ZPtr(ExprTag::I64, F::from_u64(x.to_bits_u64())),
         }
     }
 }
@@ -155,6 +158,7 @@ impl<F: LurkField> Arbitrary for ZExpr<F> {
             any::<(ZExprPtr<F>, ZContPtr<F>)>().prop_map(|(x, y)| ZExpr::Thunk(x, y)),
             any::<char>().prop_map(|x| Self::Char(x)),
             any::<u64>().prop_map(|x| Self::UInt(UInt::U64(x))),
+            any::<i64>().prop_map(|x| Self::Int(Int::I64(x))),
         ]
         .boxed()
     }
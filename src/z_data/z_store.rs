@@ -9,6 +9,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
 use crate::hash::PoseidonCache;
+use crate::int::Int;
 use crate::symbol::Symbol;
 use crate::tag::ExprTag;
 use crate::uint::UInt;
@@ -59,6 +60,10 @@ impl<F: LurkField> ZStore<F> {
                 let x = F::to_u64(val)?;
                 Some(ZExpr::UInt(UInt::U64(x)))
             }
+            ZPtr(ExprTag::I64, val) => {
+                let x = F::to_u64(val)?;
+                Some(ZExpr::Int(Int::I64(x as i64)))
+            }
             ZPtr(ExprTag::Char, val) => {
                 let x = F::to_char(val)?;
                 Some(ZExpr::Char(x))
@@ -86,3 +86,180 @@ fn test_repl_panic() {
     cmd.arg(lurk_file.into_string());
     cmd.assert().failure();
 }
+
+#[test]
+fn test_defmacro() {
+    let tmp_dir = Builder::new().prefix("tmp").tempdir().unwrap();
+    let tmp_dir = Utf8Path::from_path(tmp_dir.path()).unwrap();
+    let lurk_file = tmp_dir.join("defmacro.lurk");
+
+    let mut file = File::create(lurk_file.clone()).unwrap();
+    file.write_all(b"!(defmacro my-if (c a b) (cons 'if (cons c (cons a (cons b nil)))))\n")
+        .unwrap();
+    file.write_all(b"!(assert-eq (my-if t 1 2) 1)\n").unwrap();
+    file.write_all(b"!(assert-eq (let ((x (my-if nil 1 2))) x) 2)\n")
+        .unwrap();
+
+    let mut cmd = lurk_cmd();
+    cmd.arg("load");
+    cmd.arg(lurk_file.into_string());
+    cmd.assert().success();
+}
+
+#[test]
+fn test_defmacro_quasiquote() {
+    let tmp_dir = Builder::new().prefix("tmp").tempdir().unwrap();
+    let tmp_dir = Utf8Path::from_path(tmp_dir.path()).unwrap();
+    let lurk_file = tmp_dir.join("defmacro_quasiquote.lurk");
+
+    let mut file = File::create(lurk_file.clone()).unwrap();
+    file.write_all(b"!(defmacro my-if (c a b) (cons 'if (cons c (cons a (cons b nil)))))\n")
+        .unwrap();
+    // macro calls in quasiquoted data are only expanded where they are unquoted
+    file.write_all(b"!(assert-eq `(my-if t 1 2) '(my-if t 1 2))\n")
+        .unwrap();
+    file.write_all(b"!(assert-eq `(x ,(my-if t 1 2)) '(x 1))\n")
+        .unwrap();
+    file.write_all(b"!(assert-eq `(x ,@(my-if nil '(1) '(2 3))) '(x 2 3))\n")
+        .unwrap();
+
+    let mut cmd = lurk_cmd();
+    cmd.arg("load");
+    cmd.arg(lurk_file.into_string());
+    cmd.assert().success();
+}
+
+#[test]
+fn test_defmacro_quasiquote_body() {
+    let tmp_dir = Builder::new().prefix("tmp").tempdir().unwrap();
+    let tmp_dir = Utf8Path::from_path(tmp_dir.path()).unwrap();
+    let lurk_file = tmp_dir.join("defmacro_quasiquote_body.lurk");
+
+    let mut file = File::create(lurk_file.clone()).unwrap();
+    file.write_all(b"!(defmacro quote-car (l) `(quote ,(car l)))\n")
+        .unwrap();
+    // the calls in the template are expanded with the arguments of `quote-both`,
+    // not when it's defined
+    file.write_all(b"!(defmacro quote-both (x y) `(cons (quote-car ,x) (quote-car ,y)))\n")
+        .unwrap();
+    file.write_all(b"!(assert-eq (quote-both (a b) (c d)) '(a . c))\n")
+        .unwrap();
+
+    let mut cmd = lurk_cmd();
+    cmd.arg("load");
+    cmd.arg(lurk_file.into_string());
+    cmd.assert().success();
+}
+
+#[test]
+fn test_defmacro_shadowed() {
+    let tmp_dir = Builder::new().prefix("tmp").tempdir().unwrap();
+    let tmp_dir = Utf8Path::from_path(tmp_dir.path()).unwrap();
+    let lurk_file = tmp_dir.join("defmacro_shadowed.lurk");
+
+    let mut file = File::create(lurk_file.clone()).unwrap();
+    file.write_all(b"!(defmacro my-if (c a b) `(if ,c ,a ,b))\n")
+        .unwrap();
+    // calls to local functions named like the macro aren't expanded
+    file.write_all(b"!(assert-eq ((lambda (my-if) (my-if 1)) (lambda (x) (+ x 1))) 2)\n")
+        .unwrap();
+    file.write_all(b"!(assert-eq (let ((my-if (lambda (x) x))) (my-if 3)) 3)\n")
+        .unwrap();
+    file.write_all(
+        b"!(assert-eq (letrec ((my-if (lambda (x) (if (= x 0) 0 (my-if (- x 1)))))) (my-if 2)) 0)\n",
+    )
+    .unwrap();
+    file.write_all(b"!(assert-eq (receive (my-if) (values (lambda (x) x)) (my-if 4)) 4)\n")
+        .unwrap();
+    file.write_all(b"!(assert-eq (match (lambda (x) x) (my-if (my-if 5))) 5)\n")
+        .unwrap();
+    // `let` binds the name after its value, and only in the binding's scope
+    file.write_all(b"!(assert-eq (let ((x (my-if t 1 2)) (my-if (lambda (y) y))) (my-if x)) 1)\n")
+        .unwrap();
+    file.write_all(b"!(assert-eq (cdr (cons (lambda (my-if) (my-if 1)) (my-if nil 1 2))) 2)\n")
+        .unwrap();
+
+    let mut cmd = lurk_cmd();
+    cmd.arg("load");
+    cmd.arg(lurk_file.into_string());
+    cmd.assert().success();
+}
+
+#[test]
+fn test_defmacro_meta_commands() {
+    let tmp_dir = Builder::new().prefix("tmp").tempdir().unwrap();
+    let tmp_dir = Utf8Path::from_path(tmp_dir.path()).unwrap();
+    let lurk_file = tmp_dir.join("defmacro_meta_commands.lurk");
+
+    let mut file = File::create(lurk_file.clone()).unwrap();
+    file.write_all(b"!(defmacro double (x) `(* 2 ,x))\n")
+        .unwrap();
+    file.write_all(b"!(assert-eq (double 2) 4)\n").unwrap();
+    // the vars of a protocol are a parameter list, not a call
+    file.write_all(b"!(defprotocol p (double x) (cons nil (lambda () (double x))))\n")
+        .unwrap();
+    // names bound by meta commands replace the macros
+    file.write_all(b"!(def double (lambda (x) (+ x x x)))\n")
+        .unwrap();
+    file.write_all(b"!(assert-eq (double 2) 6)\n").unwrap();
+    file.write_all(b"!(defmacro count (n) ''macro)\n").unwrap();
+    file.write_all(b"!(defrec count (lambda (n) (if (= n 0) 0 (+ 1 (count (- n 1))))))\n")
+        .unwrap();
+    file.write_all(b"!(assert-eq (count 3) 3)\n").unwrap();
+
+    let mut cmd = lurk_cmd();
+    cmd.arg("load");
+    cmd.arg(lurk_file.into_string());
+    cmd.assert().success();
+}
+
+#[test]
+fn test_defmacro_error() {
+    let tmp_dir = Builder::new().prefix("tmp").tempdir().unwrap();
+    let tmp_dir = Utf8Path::from_path(tmp_dir.path()).unwrap();
+    let lurk_file = tmp_dir.join("defmacro_error.lurk");
+
+    let mut file = File::create(lurk_file.clone()).unwrap();
+    file.write_all(b"!(defmacro quote-car (l) `(quote ,(car l)))\n")
+        .unwrap();
+    file.write_all(b"(quote-car 1)\n").unwrap();
+
+    let mut cmd = lurk_cmd();
+    cmd.arg("load");
+    cmd.arg(lurk_file.into_string());
+    let output = cmd.assert().failure().get_output().clone();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Expanding macro quote-car in (quote-car 1)"));
+}
+
+#[test]
+fn test_defstruct() {
+    let tmp_dir = Builder::new().prefix("tmp").tempdir().unwrap();
+    let tmp_dir = Utf8Path::from_path(tmp_dir.path()).unwrap();
+    let lurk_file = tmp_dir.join("defstruct.lurk");
+
+    let mut file = File::create(lurk_file.clone()).unwrap();
+    file.write_all(b"!(defstruct point x y)\n").unwrap();
+    file.write_all(b"!(def p (make-point 1 2))\n").unwrap();
+    file.write_all(b"!(assert-eq (point-x p) 1)\n").unwrap();
+    file.write_all(b"!(assert-eq (point-y p) 2)\n").unwrap();
+    file.write_all(b"!(assert (point? p))\n").unwrap();
+    file.write_all(b"!(assert-eq (point? (cons 1 2)) nil)\n")
+        .unwrap();
+    file.write_all(b"!(assert-error (point-x 3))\n").unwrap();
+    // hand-built records and records of other types with the same fields
+    file.write_all(b"!(assert-eq (point? (cons 'point '(1 2))) nil)\n")
+        .unwrap();
+    file.write_all(b"!(assert-eq (point? (cons (car p) '(2 1))) nil)\n")
+        .unwrap();
+    file.write_all(b"!(assert-error (point-x (cons (car p) '(2 1))))\n")
+        .unwrap();
+    file.write_all(b"!(defstruct pair x y)\n").unwrap();
+    file.write_all(b"!(assert-eq (point? (make-pair 1 2)) nil)\n")
+        .unwrap();
+
+    let mut cmd = lurk_cmd();
+    cmd.arg("load");
+    cmd.arg(lurk_file.into_string());
+    cmd.assert().success();
+}